@@ -1,13 +1,16 @@
 mod build;
 mod compose;
 mod container;
+mod from_kube;
 mod generate;
 mod global_args;
 mod image;
 mod install;
 mod k8s;
 mod kube;
+mod manifest;
 mod network;
+mod output_extension;
 mod pod;
 pub mod service;
 pub mod unit;
@@ -33,16 +36,20 @@ use color_eyre::{
 };
 use compose_spec::service::blkio_config::Weight;
 use path_clean::PathClean;
+use serde::Serialize;
 
-use crate::quadlet::{self, Downgrade, DowngradeError, Globals, HostPaths, PodmanVersion};
+use crate::{
+    quadlet::{self, Downgrade, DowngradeError, Globals, HostPaths, PodmanVersion},
+    warning,
+};
 
 use self::{
-    build::Build, compose::Compose, container::Container, generate::Generate,
+    build::Build, compose::Compose, container::Container, from_kube::FromKube, generate::Generate,
     global_args::GlobalArgs, image::Image, install::Install, kube::Kube, network::Network,
     pod::Pod, service::Service, unit::Unit, volume::Volume,
 };
 
-#[allow(clippy::option_option)]
+#[allow(clippy::option_option, clippy::struct_excessive_bools)]
 #[derive(Parser, Debug, Clone, PartialEq)]
 #[command(author, version, about, subcommand_precedence_over_arg = true)]
 pub struct Cli {
@@ -63,8 +70,8 @@ pub struct Cli {
     ///
     /// Conflicts with the --file option
     ///
-    /// Equivalent to `--file $XDG_CONFIG_HOME/containers/systemd/` for non-root users,
-    /// or `--file /etc/containers/systemd/` for root.
+    /// Equivalent to `--file $XDG_CONFIG_HOME/containers/systemd/` for rootless Podman,
+    /// or `--file /etc/containers/systemd/` for rootful Podman (see --root and --rootless).
     ///
     /// The name of the file can be specified with the --name option.
     #[arg(
@@ -76,6 +83,45 @@ pub struct Cli {
     )]
     unit_directory: bool,
 
+    /// Generate files for rootful Podman
+    ///
+    /// By default, rootful Podman is assumed if the effective user is root.
+    ///
+    /// Selects the rootful Podman unit directory when using the --unit-directory option and
+    /// warns about options which are only supported by rootless Podman, e.g. `UserNS=keep-id`.
+    #[arg(long = "root", conflicts_with = "rootless")]
+    rootful: bool,
+
+    /// Generate files for rootless Podman
+    ///
+    /// By default, rootless Podman is assumed if the effective user is not root.
+    ///
+    /// Selects the rootless Podman unit directory when using the --unit-directory option.
+    #[arg(long)]
+    rootless: bool,
+
+    /// Print generated files to stdout
+    ///
+    /// This is the default, the option guarantees files are printed instead of written,
+    /// regardless of other output options.
+    ///
+    /// Conflicts with the --file and --unit-directory options
+    #[arg(long, conflicts_with = "file_out")]
+    stdout: bool,
+
+    /// Check the conversion without printing or writing files
+    ///
+    /// All errors and warnings are still reported.
+    /// Podlet exits with a non-zero status if the conversion fails.
+    ///
+    /// Conflicts with the --file, --unit-directory, and --stdout options
+    #[arg(long, conflicts_with_all = ["file_out", "stdout"])]
+    check: bool,
+
+    /// With --check, print the names of generated files
+    #[arg(long, requires = "check")]
+    verbose: bool,
+
     /// Override the name of the generated file (without the extension)
     ///
     /// This only applies if a file was not given to the --file option,
@@ -101,6 +147,56 @@ pub struct Cli {
     #[arg(long, requires = "file_out")]
     skip_services_check: bool,
 
+    /// Print the paths of the files that would be generated instead of writing them
+    ///
+    /// One path is printed per line, the extension of each file is its kind.
+    /// Nothing is written and existing files and services are not checked.
+    ///
+    /// Requires the --file or --unit-directory option
+    #[arg(long, requires = "file_out")]
+    dry_run: bool,
+
+    /// Also write a Markdown manifest of the generated files to the given path
+    ///
+    /// The manifest lists each generated file, its kind,
+    /// and the other files it references or depends on, e.g. `web.container` using
+    /// `backend.network`.
+    ///
+    /// Requires the --file or --unit-directory option
+    #[arg(
+        long,
+        value_name = "PATH",
+        requires = "file_out",
+        conflicts_with = "dry_run"
+    )]
+    manifest: Option<PathBuf>,
+
+    /// Print a JSON array describing each generated file instead of the files themselves
+    ///
+    /// Each element is an object with the file's "name", "kind", "extension", and "content".
+    ///
+    /// Conflicts with the --file, --unit-directory, and --check options
+    #[arg(long, conflicts_with_all = ["file_out", "check"])]
+    json: bool,
+
+    /// Use a different extension for generated files of a kind
+    ///
+    /// Takes the form "{KIND}={EXTENSION}", e.g. `--output-extension-map kubernetes=k8s.yaml`
+    /// names the Kubernetes YAML files generated by `podlet compose --kube` "{name}.k8s.yaml".
+    /// The kinds are: container, pod, kube, network, volume, build, image, kubernetes, config-map,
+    /// persistent-volume-claim, and compose.
+    ///
+    /// Only the names of the files are changed, references to a renamed Kubernetes YAML file in
+    /// the `Yaml=` and `ConfigMap=` options of a generated `.kube` file are updated.
+    ///
+    /// Can be specified multiple times.
+    #[arg(
+        long,
+        value_name = "KIND=EXT",
+        value_parser = output_extension::parser()
+    )]
+    output_extension_map: Vec<output_extension::ExtensionMapping>,
+
     /// Podman version generated Quadlet files should conform to
     ///
     /// An error will occur if the Quadlet file cannot be downgraded to the given version.
@@ -113,11 +209,19 @@ pub struct Cli {
     #[arg(short, long, visible_aliases = ["compatibility", "compat"], default_value_t)]
     podman_version: PodmanVersion,
 
+    /// Omit Quadlet options unsupported by the --podman-version instead of returning an error
+    ///
+    /// A warning is emitted for each omitted option.
+    /// An error is still returned if the type of Quadlet file is not supported.
+    #[arg(long)]
+    omit_unsupported: bool,
+
     /// Convert relative host paths to absolute paths.
     ///
     /// Relative host paths in generated Quadlet files are resolved using the given directory or the
     /// current working directory. For `podlet compose`, the parent directory of the compose
-    /// file is used as the default if the compose file is not read from stdin.
+    /// file is used as the default if the compose file is not read from stdin. If a directory is
+    /// given to `podlet compose` instead of a file, that directory is used.
     ///
     /// All host paths are also cleaned to remove interior `/../`, `/./`, and `//`.
     ///
@@ -145,21 +249,43 @@ pub struct Cli {
 
 impl Cli {
     pub fn print_or_write_files(self) -> color_eyre::Result<()> {
-        if self.unit_directory || self.file.is_some() {
+        if self.check {
+            let verbose = self.verbose;
+            let files = self.try_into_files()?;
+            if verbose {
+                for file in files {
+                    println!("{}", file.relative_path().display());
+                }
+            }
+            Ok(())
+        } else if !self.stdout && (self.unit_directory || self.file.is_some()) {
             let path = self.file_path()?;
-            if matches!(path, FilePath::Full(..))
-                && matches!(self.command, Commands::Compose { .. })
-            {
-                return Err(eyre!(
-                    "A file path was provided to `--file` and the `compose` command was used"
-                )
-                .suggestion(
-                    "Provide a directory to `--file`. \
-                        `compose` can generate multiple files so a directory is needed.",
-                ));
+            if matches!(path, FilePath::Full(..)) {
+                let command = match self.command {
+                    Commands::Compose { .. } => Some("compose"),
+                    Commands::FromKube { .. } => Some("from-kube"),
+                    _ => None,
+                };
+                if let Some(command) = command {
+                    return Err(eyre!(
+                        "A file path was provided to `--file` and the `{command}` command was used"
+                    )
+                    .suggestion(format!(
+                        "Provide a directory to `--file`. \
+                            `{command}` can generate multiple files so a directory is needed."
+                    )));
+                }
+            }
+
+            if self.dry_run {
+                for path in self.dry_run_paths(&path)? {
+                    println!("{}", path.display());
+                }
+                return Ok(());
             }
 
             let overwrite = self.overwrite;
+            let manifest = self.manifest.clone();
             #[cfg(unix)]
             let services_check = !self.skip_services_check;
 
@@ -174,16 +300,30 @@ impl Cli {
                 )?;
             }
 
-            for file in files {
+            for file in &files {
                 file.write(&path, overwrite)?;
             }
 
+            if let Some(manifest_path) = manifest {
+                let mut file = open_file(&manifest_path, overwrite)?;
+                let manifest_path = manifest_path.display();
+                write!(file, "{}", manifest::render(&files))
+                    .wrap_err_with(|| format!("Failed to write to file: {manifest_path}"))?;
+                println!("Wrote to file: {manifest_path}");
+            }
+
+            Ok(())
+        } else if self.json {
+            let files: Vec<_> = self.try_into_files()?.iter().map(JsonFile::from).collect();
+            let json = serde_json::to_string_pretty(&files)
+                .wrap_err("error serializing generated files to JSON")?;
+            println!("{json}");
             Ok(())
         } else {
             let files = self
                 .try_into_files()?
                 .into_iter()
-                .map(|file| format!("# {}.{}\n{file}", file.name(), file.extension()))
+                .map(|file| format!("# {}\n{file}", file.relative_path().display()))
                 .collect::<Vec<_>>()
                 .join("\n---\n\n");
             print!("{files}");
@@ -191,24 +331,29 @@ impl Cli {
         }
     }
 
+    /// Returns `true` if generating files for rootful Podman.
+    ///
+    /// Set with the --root and --rootless options, or detected from the effective user.
+    fn is_root(&self) -> bool {
+        if self.rootful {
+            true
+        } else if self.rootless {
+            false
+        } else {
+            #[cfg(unix)]
+            return nix::unistd::Uid::effective().is_root();
+
+            #[cfg(not(unix))]
+            false
+        }
+    }
+
     /// Returns the file path for the generated file
     fn file_path(&self) -> color_eyre::Result<FilePath> {
         let path = if self.unit_directory {
             #[cfg(unix)]
-            if nix::unistd::Uid::current().is_root() {
-                let path = PathBuf::from("/etc/containers/systemd/");
-                if path.is_dir() {
-                    path
-                } else {
-                    PathBuf::from("/usr/share/containers/systemd/")
-                }
-            } else {
-                let mut path: PathBuf = env::var("XDG_CONFIG_HOME")
-                    .or_else(|_| env::var("HOME").map(|home| format!("{home}/.config")))
-                    .unwrap_or_else(|_| String::from("~/.config/"))
-                    .into();
-                path.push("containers/systemd/");
-                path
+            {
+                unit_directory(self.is_root())
             }
 
             #[cfg(not(unix))]
@@ -227,6 +372,15 @@ impl Cli {
         Ok(FilePath::Dir(path))
     }
 
+    /// Convert into [`File`]s and return the full path each one would be written to.
+    fn dry_run_paths(self, path: &FilePath) -> color_eyre::Result<Vec<PathBuf>> {
+        Ok(self
+            .try_into_files()?
+            .iter()
+            .map(|file| path.to_full(file).into_owned())
+            .collect())
+    }
+
     /// Take the directory to resolve relative paths with.
     ///
     /// Returns [`None`] if relative paths should not be resolved.
@@ -249,12 +403,18 @@ impl Cli {
                     );
                     Ok(path)
                 } else {
-                    match &self.command {
-                        Commands::Compose(Compose {
-                            compose_file: Some(path),
-                            ..
-                        }) if path.as_os_str() != "-" && !path.as_os_str().is_empty() => {
-                            if let Some(path) = path.parent() {
+                    let compose_file = match &self.command {
+                        Commands::Compose(compose) => compose.compose_file.as_deref(),
+                        _ => None,
+                    };
+                    match compose_file {
+                        Some(path) if path.as_os_str() != "-" && !path.as_os_str().is_empty() => {
+                            let dir = if path.is_dir() {
+                                Some(path)
+                            } else {
+                                path.parent()
+                            };
+                            if let Some(path) = dir {
                                 let current_dir = env::current_dir().wrap_err(CURRENT_DIR_ERR)?;
                                 Ok(absolute_clean_path(&current_dir, path))
                             } else {
@@ -275,11 +435,18 @@ impl Cli {
             .resolve_dir()
             .wrap_err("error with `--absolute-host-paths` resolve directory")?;
 
+        let root = self.is_root();
         let unit = (!self.unit.is_empty()).then_some(self.unit);
         let install = self.install.install.then(|| self.install.into());
 
         let mut files = self.command.try_into_files(self.name, unit, install)?;
 
+        if root {
+            for file in &files {
+                warn_rootless_only(file);
+            }
+        }
+
         let downgrade = self.podman_version < PodmanVersion::LATEST;
         if downgrade || resolve_dir.is_some() {
             for file in &mut files {
@@ -288,17 +455,94 @@ impl Cli {
                 }
 
                 if downgrade {
-                    file.downgrade(self.podman_version).wrap_err_with(|| {
-                        format!(
-                            "error downgrading Quadlet file to Podman v{}",
-                            self.podman_version
-                        )
-                    })?;
+                    downgrade_file(file, self.podman_version, self.omit_unsupported)
+                        .wrap_err_with(|| {
+                            format!(
+                                "error downgrading Quadlet file to Podman v{}",
+                                self.podman_version
+                            )
+                        })?;
                 }
             }
         }
 
-        Ok(files)
+        // After downgrading, as it may add to `PodmanArgs=`.
+        for file in files.iter_mut().filter_map(File::as_quadlet_file_mut) {
+            file.globals.dedup();
+            if let quadlet::Resource::Container(container) = &mut file.resource {
+                container.dedup_podman_args();
+            }
+        }
+
+        Ok(output_extension::apply(files, &self.output_extension_map))
+    }
+}
+
+/// Podman unit directory Quadlet files are read from, for rootful Podman if `root` is `true`.
+#[cfg(unix)]
+fn unit_directory(root: bool) -> PathBuf {
+    if root {
+        let path = PathBuf::from("/etc/containers/systemd/");
+        if path.is_dir() {
+            path
+        } else {
+            PathBuf::from("/usr/share/containers/systemd/")
+        }
+    } else {
+        let mut path: PathBuf = env::var("XDG_CONFIG_HOME")
+            .or_else(|_| env::var("HOME").map(|home| format!("{home}/.config")))
+            .unwrap_or_else(|_| String::from("~/.config/"))
+            .into();
+        path.push("containers/systemd/");
+        path
+    }
+}
+
+/// Warn if a [`File`] uses options which are only supported by rootless Podman.
+fn warn_rootless_only(file: &File) {
+    let Some(quadlet::File {
+        name,
+        resource: quadlet::Resource::Container(container),
+        ..
+    }) = file.as_quadlet_file()
+    else {
+        return;
+    };
+
+    if container
+        .user_ns
+        .as_deref()
+        .is_some_and(|user_ns| user_ns.starts_with("keep-id"))
+    {
+        warning::warn(format_args!(
+            "container `{name}` uses `UserNS=keep-id`, which is only supported by rootless \
+                Podman, but files are generated for rootful Podman"
+        ));
+    }
+}
+
+/// [Downgrade](Downgrade::downgrade()) a [`File`] to the Podman `version`.
+///
+/// If `omit_unsupported` is `true`, unsupported Quadlet options are omitted with a warning instead
+/// of returning an error.
+///
+/// # Errors
+///
+/// Returns an error if the type of Quadlet file is not supported by the `version` or, if not
+/// `omit_unsupported`, a Quadlet option is not supported.
+fn downgrade_file(
+    file: &mut File,
+    version: PodmanVersion,
+    omit_unsupported: bool,
+) -> Result<(), DowngradeError> {
+    loop {
+        match file.downgrade(version) {
+            // The unsupported option was removed, continue downgrading the rest of the file.
+            Err(error @ DowngradeError::Option { .. }) if omit_unsupported => {
+                warning::warn(format_args!("{error}, omitting it"));
+            }
+            result => return result,
+        }
     }
 }
 
@@ -315,14 +559,10 @@ impl FilePath {
     /// Convert to full file path
     ///
     /// If `self` is a directory, the [`File`] is used to set the filename.
-    fn to_full(&self, file: &File) -> Cow<Path> {
+    fn to_full(&self, file: &File) -> Cow<'_, Path> {
         match self {
             Self::Full(path) => path.into(),
-            Self::Dir(path) => {
-                let mut path = path.join(file.name());
-                path.set_extension(file.extension());
-                path.into()
-            }
+            Self::Dir(path) => path.join(file.relative_path()).into(),
         }
     }
 }
@@ -350,7 +590,17 @@ enum Commands {
     ///
     /// When Podlet encounters an unsupported option, an error will be returned.
     /// Modify the compose file to resolve the error.
-    Compose(#[command(flatten)] Compose),
+    Compose(#[command(flatten)] Box<Compose>),
+
+    /// Generate Podman Quadlet files from a Kubernetes YAML file
+    ///
+    /// Creates a `.container` file for each container in the pod
+    /// and a `.volume` file for each persistent volume claim used by the pod.
+    ///
+    /// The `--file` option must be a directory if used.
+    ///
+    /// When Podlet encounters an unsupported option, an error will be returned.
+    FromKube(#[command(flatten)] FromKube),
 
     /// Generate a Podman Quadlet file from an existing object.
     ///
@@ -382,6 +632,12 @@ impl Commands {
             Self::Compose(compose) => compose
                 .try_into_files(unit, install)
                 .wrap_err("error converting compose file"),
+            Self::FromKube(from_kube) => Ok(from_kube
+                .try_into_quadlet_files(unit.as_ref(), install.as_ref())
+                .wrap_err("error converting Kubernetes YAML file")?
+                .into_iter()
+                .map(Into::into)
+                .collect()),
             Self::Generate(command) => Ok(command
                 .try_into_quadlet_files(name, unit, install)
                 .wrap_err("error creating Quadlet file(s) from an existing object")?
@@ -542,6 +798,20 @@ impl PodmanCommands {
 enum File {
     Quadlet(quadlet::File),
     Kubernetes(k8s::File),
+    KubernetesConfigMaps(k8s::ConfigMapFile),
+    KubernetesPersistentVolumeClaim(k8s::PersistentVolumeClaimFile),
+    Compose(compose::NormalizedFile),
+    ConfigContent(compose::ContentFile),
+    /// A file written into a subdirectory of the output directory.
+    Subdirectory {
+        directory: String,
+        file: Box<File>,
+    },
+    /// A file written with a different extension, set with `--output-extension-map`.
+    Extension {
+        extension: String,
+        file: Box<File>,
+    },
 }
 
 impl From<quadlet::File> for File {
@@ -556,11 +826,40 @@ impl From<k8s::File> for File {
     }
 }
 
+impl From<k8s::ConfigMapFile> for File {
+    fn from(value: k8s::ConfigMapFile) -> Self {
+        Self::KubernetesConfigMaps(value)
+    }
+}
+
+impl From<k8s::PersistentVolumeClaimFile> for File {
+    fn from(value: k8s::PersistentVolumeClaimFile) -> Self {
+        Self::KubernetesPersistentVolumeClaim(value)
+    }
+}
+
+impl From<compose::ContentFile> for File {
+    fn from(value: compose::ContentFile) -> Self {
+        Self::ConfigContent(value)
+    }
+}
+
+impl From<compose::NormalizedFile> for File {
+    fn from(value: compose::NormalizedFile) -> Self {
+        Self::Compose(value)
+    }
+}
+
 impl Display for File {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::Quadlet(file) => file.fmt(f),
             Self::Kubernetes(file) => file.fmt(f),
+            Self::KubernetesConfigMaps(file) => file.fmt(f),
+            Self::KubernetesPersistentVolumeClaim(file) => file.fmt(f),
+            Self::Compose(file) => file.fmt(f),
+            Self::ConfigContent(file) => file.fmt(f),
+            Self::Subdirectory { file, .. } | Self::Extension { file, .. } => file.fmt(f),
         }
     }
 }
@@ -570,13 +869,82 @@ impl File {
         match self {
             Self::Quadlet(file) => &file.name,
             Self::Kubernetes(file) => &file.name,
+            Self::KubernetesConfigMaps(file) => &file.name,
+            Self::KubernetesPersistentVolumeClaim(file) => &file.name,
+            Self::Compose(file) => &file.name,
+            Self::ConfigContent(file) => &file.name,
+            Self::Subdirectory { file, .. } | Self::Extension { file, .. } => file.name(),
         }
     }
 
     fn extension(&self) -> &str {
         match self {
             Self::Quadlet(file) => file.resource.extension(),
-            Self::Kubernetes(_) => "yaml",
+            Self::Kubernetes(_)
+            | Self::KubernetesConfigMaps(_)
+            | Self::KubernetesPersistentVolumeClaim(_)
+            | Self::Compose(_) => "yaml",
+            // The name of a config content file is used as is.
+            Self::ConfigContent(_) => "",
+            Self::Subdirectory { file, .. } => file.extension(),
+            Self::Extension { extension, .. } => extension,
+        }
+    }
+
+    /// The kind of the file, the Quadlet resource kind for Quadlet files.
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Quadlet(file) => quadlet::ResourceKind::from(&file.resource).as_str(),
+            Self::Kubernetes(_) => "kubernetes",
+            Self::KubernetesConfigMaps(_) => "config-map",
+            Self::KubernetesPersistentVolumeClaim(_) => "persistent-volume-claim",
+            Self::Compose(_) => "compose",
+            Self::ConfigContent(_) => "config",
+            Self::Subdirectory { file, .. } | Self::Extension { file, .. } => file.kind(),
+        }
+    }
+
+    /// The permissions to give the file once written, if not the default.
+    #[cfg(unix)]
+    fn mode(&self) -> Option<u32> {
+        match self {
+            Self::ConfigContent(file) => file.mode,
+            Self::Subdirectory { file, .. } | Self::Extension { file, .. } => file.mode(),
+            Self::Kubernetes(file) => file.mode(),
+            Self::Quadlet(_)
+            | Self::KubernetesConfigMaps(_)
+            | Self::KubernetesPersistentVolumeClaim(_)
+            | Self::Compose(_) => None,
+        }
+    }
+
+    /// Returns the path of the file relative to the directory it is written to.
+    fn relative_path(&self) -> PathBuf {
+        match self {
+            Self::Subdirectory { directory, file } => {
+                Path::new(directory).join(file.relative_path())
+            }
+            Self::ConfigContent(file) => file.relative_path(),
+            _ => {
+                let mut path = PathBuf::from(self.name());
+                path.set_extension(self.extension());
+                path
+            }
+        }
+    }
+
+    /// Change the extension of the file, keeping its subdirectory, if any.
+    fn with_extension(self, extension: String) -> Self {
+        match self {
+            Self::Subdirectory { directory, file } => Self::Subdirectory {
+                directory,
+                file: Box::new(file.with_extension(extension)),
+            },
+            Self::Extension { file, .. } => file.with_extension(extension),
+            file => Self::Extension {
+                extension,
+                file: Box::new(file),
+            },
         }
     }
 
@@ -584,7 +952,14 @@ impl File {
     fn as_quadlet_file(&self) -> Option<&quadlet::File> {
         match self {
             Self::Quadlet(file) => Some(file),
-            Self::Kubernetes(_) => None,
+            Self::Kubernetes(_)
+            | Self::KubernetesConfigMaps(_)
+            | Self::KubernetesPersistentVolumeClaim(_)
+            | Self::Compose(_)
+            | Self::ConfigContent(_) => None,
+            Self::Subdirectory { file, .. } | Self::Extension { file, .. } => {
+                file.as_quadlet_file()
+            }
         }
     }
 
@@ -592,7 +967,14 @@ impl File {
     fn as_quadlet_file_mut(&mut self) -> Option<&mut quadlet::File> {
         match self {
             Self::Quadlet(file) => Some(file),
-            Self::Kubernetes(_) => None,
+            Self::Kubernetes(_)
+            | Self::KubernetesConfigMaps(_)
+            | Self::KubernetesPersistentVolumeClaim(_)
+            | Self::Compose(_)
+            | Self::ConfigContent(_) => None,
+            Self::Subdirectory { file, .. } | Self::Extension { file, .. } => {
+                file.as_quadlet_file_mut()
+            }
         }
     }
 
@@ -607,16 +989,59 @@ impl File {
 
     fn write(&self, path: &FilePath, overwrite: bool) -> color_eyre::Result<()> {
         let path = path.to_full(self);
+        if let (Self::Subdirectory { .. } | Self::ConfigContent(_), Some(directory)) =
+            (self, path.parent())
+        {
+            fs::create_dir_all(directory)
+                .wrap_err_with(|| format!("Failed to create directory: {}", directory.display()))?;
+        }
         let mut file = open_file(&path, overwrite)?;
 
         let path = path.display();
         write!(file, "{self}").wrap_err_with(|| format!("Failed to write to file: {path}"))?;
+        #[cfg(unix)]
+        if let Some(mode) = self.mode() {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(fs::Permissions::from_mode(mode))
+                .wrap_err_with(|| format!("Failed to set permissions of file: {path}"))?;
+        }
         println!("Wrote to file: {path}");
 
         Ok(())
     }
 }
 
+/// Description of a generated [`File`] for `--json` output.
+#[derive(Serialize, Debug)]
+struct JsonFile {
+    /// Name of the file without the extension, including its subdirectory, if any.
+    name: String,
+
+    /// Kind of the file, see [`File::kind()`].
+    kind: &'static str,
+
+    /// Extension of the file.
+    extension: String,
+
+    /// Content of the file.
+    content: String,
+}
+
+impl From<&File> for JsonFile {
+    fn from(file: &File) -> Self {
+        let name = match file {
+            File::Subdirectory { directory, file } => format!("{directory}/{}", file.name()),
+            _ => file.name().to_owned(),
+        };
+        Self {
+            name,
+            kind: file.kind(),
+            extension: file.extension().to_owned(),
+            content: file.to_string(),
+        }
+    }
+}
+
 /// If `path` is relative, it is resolved using `resolve_dir` and a cleaned version is returned.
 fn absolute_clean_path(resolve_dir: &Path, path: &Path) -> PathBuf {
     // Paths starting with "%" are also absolute because they start with a systemd specifier.
@@ -665,7 +1090,14 @@ impl Downgrade for File {
     fn downgrade(&mut self, version: PodmanVersion) -> Result<(), DowngradeError> {
         match self {
             Self::Quadlet(file) => file.downgrade(version),
-            Self::Kubernetes(_) => Ok(()),
+            Self::Kubernetes(_)
+            | Self::KubernetesConfigMaps(_)
+            | Self::KubernetesPersistentVolumeClaim(_)
+            | Self::Compose(_)
+            | Self::ConfigContent(_) => Ok(()),
+            Self::Subdirectory { file, .. } | Self::Extension { file, .. } => {
+                file.downgrade(version)
+            }
         }
     }
 }
@@ -724,6 +1156,8 @@ fn blkio_weight_parser() -> impl TypedValueParser<Value = Weight> {
 
 #[cfg(test)]
 mod tests {
+    use std::slice;
+
     use clap::CommandFactory;
 
     use super::*;
@@ -732,4 +1166,439 @@ mod tests {
     fn verify_cli() {
         Cli::command().debug_assert();
     }
+
+    #[test]
+    fn stdout_conflicts_with_file_out() {
+        let args = ["podlet", "--stdout", "podman", "run", "image"];
+        assert!(Cli::try_parse_from(args).is_ok());
+
+        for file_out in [["--file", "x"].as_slice(), &["--unit-directory"]] {
+            let args = ["podlet", "--stdout"]
+                .iter()
+                .chain(file_out)
+                .chain(&["podman", "run", "image"]);
+            assert!(Cli::try_parse_from(args).is_err(), "{file_out:?}");
+        }
+    }
+
+    #[test]
+    fn check() {
+        let cli = Cli::try_parse_from(["podlet", "--check", "podman", "run", "image"])
+            .expect("args are valid");
+        assert!(cli.print_or_write_files().is_ok());
+
+        let cli = Cli::try_parse_from([
+            "podlet",
+            "--check",
+            "--podman-version",
+            "4.6",
+            "podman",
+            "run",
+            "--sdnotify",
+            "healthy",
+            "image",
+        ])
+        .expect("args are valid");
+        assert!(cli.print_or_write_files().is_err());
+    }
+
+    #[test]
+    fn omit_unsupported() {
+        let args = [
+            "podlet",
+            "--podman-version",
+            "4.6",
+            "podman",
+            "run",
+            "--sdnotify",
+            "healthy",
+            "image",
+        ];
+        let cli = Cli::try_parse_from(args).expect("args are valid");
+        assert!(cli.try_into_files().is_err());
+
+        let args = ["podlet", "--omit-unsupported"].iter().chain(&args[1..]);
+        let cli = Cli::try_parse_from(args).expect("args are valid");
+        let files = cli.try_into_files().expect("unsupported option is omitted");
+        let files: Vec<_> = files.iter().map(ToString::to_string).collect();
+        assert_eq!(files, ["[Container]\nImage=image\n"]);
+        assert_eq!(warning::take().len(), 1);
+    }
+
+    #[test]
+    fn root_unit_directory() {
+        let root = unit_directory(true);
+        assert!(
+            root == Path::new("/etc/containers/systemd/")
+                || root == Path::new("/usr/share/containers/systemd/"),
+            "{}",
+            root.display()
+        );
+
+        let rootless = unit_directory(false);
+        assert!(
+            rootless.ends_with("containers/systemd/") && !rootless.starts_with("/etc"),
+            "{}",
+            rootless.display()
+        );
+    }
+
+    #[test]
+    fn root_keep_id_warning() {
+        let args = ["podman", "run", "--userns", "keep-id", "image"];
+
+        let cli =
+            Cli::try_parse_from(["podlet", "--root"].iter().chain(&args)).expect("args are valid");
+        cli.try_into_files().expect("conversion succeeds");
+        let [warning]: [String; 1] = warning::take().try_into().expect("a warning for `keep-id`");
+        assert!(warning.contains("keep-id"), "{warning}");
+
+        let cli = Cli::try_parse_from(["podlet", "--rootless"].iter().chain(&args))
+            .expect("args are valid");
+        cli.try_into_files().expect("conversion succeeds");
+        assert!(warning::take().is_empty());
+
+        assert!(
+            Cli::try_parse_from(["podlet", "--root", "--rootless"].iter().chain(&args)).is_err()
+        );
+    }
+
+    #[test]
+    fn unit_ordering_flags() {
+        let cli = Cli::try_parse_from([
+            "podlet",
+            "--after",
+            "network-online.target",
+            "--wants",
+            "network-online.target",
+            "podman",
+            "run",
+            "image",
+        ])
+        .expect("args are valid");
+        let yaml = "
+            services:
+              web:
+                image: web
+                depends_on:
+                  - db
+                networks:
+                  - backend
+              db:
+                image: db
+                networks:
+                  - backend
+            networks:
+              backend: {}
+        ";
+        let files = compose::tests::convert_with_unit(Compose::default(), yaml, Some(cli.unit))
+            .expect("conversion succeeds");
+        assert_eq!(files.len(), 3);
+
+        for file in files {
+            let file = file.to_string();
+            assert!(file.contains("Wants=network-online.target"), "{file}");
+            assert!(file.contains("After=network-online.target"), "{file}");
+        }
+    }
+
+    #[test]
+    fn compose_directory() {
+        let dir = env::temp_dir().join(format!("podlet-compose-directory-{}", std::process::id()));
+        fs::create_dir(&dir).expect("temporary directory is creatable");
+        fs::write(
+            dir.join("compose.yaml"),
+            "
+            services:
+              app:
+                image: docker.io/library/alpine
+                volumes:
+                  - ./data:/data
+            ",
+        )
+        .expect("compose file is writable");
+        let cli = Cli::try_parse_from([
+            "podlet".as_ref(),
+            "--absolute-host-paths".as_ref(),
+            "compose".as_ref(),
+            dir.as_os_str(),
+        ])
+        .expect("args are valid");
+        let files = cli.try_into_files();
+        fs::remove_dir_all(&dir).expect("temporary directory is removable");
+
+        let [container]: [File; 1] = files
+            .expect("`compose.yaml` is found in the directory")
+            .try_into()
+            .expect("one container file");
+        let container = container.to_string();
+        assert!(
+            container.contains(&format!("Volume={}:/data\n", dir.join("data").display())),
+            "{container}"
+        );
+    }
+
+    #[test]
+    fn dry_run() {
+        let path = env::temp_dir().join(format!("podlet-dry-run-{}.container", std::process::id()));
+        let cli = Cli::try_parse_from([
+            "podlet".as_ref(),
+            "--file".as_ref(),
+            path.as_os_str(),
+            "--dry-run".as_ref(),
+            "podman".as_ref(),
+            "run".as_ref(),
+            "image".as_ref(),
+        ])
+        .expect("args are valid");
+
+        let file_path = cli.file_path().expect("file path is valid");
+        let paths = cli
+            .clone()
+            .dry_run_paths(&file_path)
+            .expect("conversion succeeds");
+        assert_eq!(paths, slice::from_ref(&path));
+
+        cli.print_or_write_files().expect("dry run succeeds");
+        assert!(!path.exists(), "no file is written");
+
+        assert!(
+            Cli::try_parse_from(["podlet", "--dry-run", "podman", "run", "image"]).is_err(),
+            "`--dry-run` requires `--file` or `--unit-directory`"
+        );
+    }
+
+    #[test]
+    fn split_by_profile() {
+        let dir = env::temp_dir().join(format!("podlet-split-by-profile-{}", std::process::id()));
+        fs::create_dir(&dir).expect("temporary directory is creatable");
+        let compose_file = dir.join("compose.yaml");
+        fs::write(
+            &compose_file,
+            "
+            services:
+              web:
+                image: docker.io/library/nginx
+                networks:
+                  - frontend
+              debug:
+                image: docker.io/library/busybox
+                command: sleep infinity
+                profiles:
+                  - dev
+              metrics:
+                image: docker.io/prom/prometheus
+                networks:
+                  - frontend
+                profiles:
+                  - dev
+                  - prod
+            networks:
+              frontend:
+            ",
+        )
+        .expect("compose file is writable");
+        let cli = Cli::try_parse_from([
+            "podlet".as_ref(),
+            "--file".as_ref(),
+            dir.as_os_str(),
+            "compose".as_ref(),
+            "--split-by-profile".as_ref(),
+            compose_file.as_os_str(),
+        ])
+        .expect("args are valid");
+
+        let path = cli.file_path().expect("file path is valid");
+        let files = cli.try_into_files().expect("conversion succeeds");
+        for file in &files {
+            file.write(&path, false).expect("file is writable");
+        }
+
+        let expected = [
+            "base/web.container",
+            "base/frontend.network",
+            "dev/debug.container",
+            "dev/metrics.container",
+            "prod/metrics.container",
+        ]
+        .map(|file| dir.join(file));
+        let written = expected.iter().all(|file| file.is_file());
+        fs::remove_dir_all(&dir).expect("temporary directory is removable");
+
+        assert_eq!(
+            files
+                .iter()
+                .map(|file| path.to_full(file))
+                .collect::<Vec<_>>(),
+            expected
+        );
+        assert!(written, "files are written into subdirectories");
+    }
+
+    #[test]
+    fn json() {
+        let cli =
+            Cli::try_parse_from(["podlet", "--json", "podman", "network", "create", "backend"])
+                .expect("args are valid");
+
+        let files: Vec<_> = cli
+            .try_into_files()
+            .expect("conversion succeeds")
+            .iter()
+            .map(JsonFile::from)
+            .collect();
+        let json = serde_json::to_value(files).expect("files serialize to JSON");
+        assert_eq!(
+            json,
+            serde_json::json!([
+                {
+                    "name": "backend",
+                    "kind": "network",
+                    "extension": "network",
+                    "content": "[Network]\n",
+                },
+            ])
+        );
+
+        for conflict in ["--file", "--unit-directory", "--check"] {
+            assert!(
+                Cli::try_parse_from(["podlet", "--json", conflict, "podman", "run", "image"])
+                    .is_err(),
+                "{conflict}"
+            );
+        }
+    }
+
+    #[test]
+    fn manifest() {
+        assert!(Cli::try_parse_from([
+            "podlet",
+            "--file",
+            "--manifest",
+            "MANIFEST.md",
+            "podman",
+            "run",
+            "image"
+        ])
+        .is_ok());
+        assert!(
+            Cli::try_parse_from([
+                "podlet",
+                "--manifest",
+                "MANIFEST.md",
+                "podman",
+                "run",
+                "image"
+            ])
+            .is_err(),
+            "requires --file or --unit-directory"
+        );
+    }
+
+    #[test]
+    fn output_extension_map() {
+        let dir = env::temp_dir().join(format!("podlet-extension-map-{}", std::process::id()));
+        fs::create_dir(&dir).expect("temporary directory is creatable");
+        let compose_file = dir.join("compose.yaml");
+        fs::write(
+            &compose_file,
+            "
+            name: directory
+            services:
+              app:
+                image: docker.io/library/alpine
+            ",
+        )
+        .expect("compose file is writable");
+        let cli = Cli::try_parse_from([
+            "podlet".as_ref(),
+            "--file".as_ref(),
+            dir.as_os_str(),
+            "--output-extension-map".as_ref(),
+            "kubernetes=.k8s.yaml".as_ref(),
+            "compose".as_ref(),
+            "--kube".as_ref(),
+            compose_file.as_os_str(),
+        ])
+        .expect("args are valid");
+
+        let path = cli.file_path().expect("file path is valid");
+        let files = cli.try_into_files().expect("conversion succeeds");
+        for file in &files {
+            file.write(&path, false).expect("file is written");
+        }
+        let mut written: Vec<_> = fs::read_dir(&dir)
+            .expect("temporary directory is readable")
+            .map(|entry| entry.expect("entry is readable").file_name())
+            .collect();
+        written.sort();
+        let kube = fs::read_to_string(dir.join("directory.kube")).expect("kube file is readable");
+        fs::remove_dir_all(&dir).expect("temporary directory is removable");
+
+        assert_eq!(
+            written,
+            ["compose.yaml", "directory-kube.k8s.yaml", "directory.kube"]
+        );
+        assert!(kube.contains("Yaml=directory-kube.k8s.yaml\n"), "{kube}");
+
+        assert!(
+            Cli::try_parse_from([
+                "podlet",
+                "--output-extension-map",
+                "unknown=yaml",
+                "podman",
+                "run",
+                "image"
+            ])
+            .is_err(),
+            "unknown kind"
+        );
+    }
+
+    #[test]
+    fn start_limit_flags() {
+        let cli = Cli::try_parse_from([
+            "podlet",
+            "--start-limit-interval",
+            "90s",
+            "--start-limit-burst",
+            "2",
+            "podman",
+            "run",
+            "image",
+        ])
+        .expect("args are valid");
+        let [file]: [File; 1] = cli
+            .try_into_files()
+            .expect("conversion succeeds")
+            .try_into()
+            .expect("one file");
+        assert!(
+            file.to_string()
+                .starts_with("[Unit]\nStartLimitIntervalSec=1m30s\nStartLimitBurst=2\n"),
+            "{file}"
+        );
+
+        assert!(Cli::try_parse_from([
+            "podlet",
+            "--start-limit-interval",
+            "soon",
+            "podman",
+            "run",
+            "image"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn check_conflicts() {
+        for args in [
+            ["podlet", "--check", "--stdout"].as_slice(),
+            &["podlet", "--check", "--unit-directory"],
+            &["podlet", "--verbose"],
+        ] {
+            let args = args.iter().chain(&["podman", "run", "image"]);
+            assert!(Cli::try_parse_from(args).is_err());
+        }
+    }
 }
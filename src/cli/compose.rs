@@ -1,24 +1,85 @@
+mod anonymous_volume;
+mod attach;
+mod chown;
+mod config;
+mod create_host_path;
+mod default_network;
+mod develop;
+mod duration;
+mod environment;
+mod extends;
+mod extension;
+mod gpus;
+mod healthcheck;
+mod host_gateway;
+mod interpolate;
+mod labels;
+mod lifecycle;
+mod links;
+mod mask;
+mod merge;
+mod mode;
+mod pod_dns;
+mod podman;
+mod profiles;
+mod project;
+mod restart;
+mod rollout;
+mod sanitize;
+mod scale;
+pub(super) mod secrets;
+pub(super) mod size;
+mod subpath;
+mod timezone;
+mod unused;
+mod version;
+mod windows;
+
 use std::{
     collections::HashMap,
+    fmt::{self, Display, Formatter},
     fs,
-    io::{self, IsTerminal},
+    io::{self, IsTerminal, Read},
     iter, mem,
+    net::IpAddr,
     path::{Path, PathBuf},
 };
 
-use clap::Args;
+use clap::{
+    builder::{PossibleValuesParser, TypedValueParser},
+    Args, ValueEnum,
+};
 use color_eyre::{
     eyre::{bail, ensure, eyre, OptionExt, WrapErr},
     Help,
 };
 use compose_spec::{
-    service::Command, Identifier, Network, Networks, Options, Resource, Service, Volumes,
+    service::{Command, Healthcheck},
+    Configs, Identifier, Network, Networks, Options, Resource, Service, Volumes,
 };
 use indexmap::IndexMap;
 
-use crate::quadlet::{self, container::volume::Source, Globals};
+use crate::{
+    quadlet::{
+        self,
+        container::{volume::Source, PullPolicy},
+        Globals,
+    },
+    warning,
+};
+
+pub use self::{
+    config::ContentFile,
+    lifecycle::{Hook, Hooks},
+};
+
+use self::{
+    environment::EnvMerge,
+    extension::{HealthOnFailure, ServiceExtension},
+    podman::PodmanExtension,
+};
 
-use super::{k8s, Build, Container, File, GlobalArgs, Unit};
+use super::{container::gpu, k8s, Build, Container, File, GlobalArgs, Unit};
 
 /// Converts a [`Command`] into a [`Vec<String>`], splitting the [`String`](Command::String) variant
 /// as a shell would.
@@ -40,21 +101,193 @@ pub fn command_try_into_vec(command: Command) -> color_eyre::Result<Vec<String>>
 }
 
 /// [`Args`] for the `podlet compose` subcommand.
-#[derive(Args, Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Args, Default, Debug, Clone, PartialEq, Eq)]
 pub struct Compose {
     /// Create a `.pod` file and link it with each `.container` file.
     ///
     /// The top-level `name` field in the compose file is required when using this option.
     /// It is used for the name of the pod and in the filenames of the created files.
     ///
-    /// Each container becomes a part of the pod and is renamed to "{pod}-{container}".
+    /// Each container becomes a part of the pod and is renamed to "{pod}-{container}",
+    /// see `--prefix-separator`.
     ///
     /// Published ports are taken from each container and applied to the pod.
-    #[arg(long, conflicts_with = "kube")]
+    #[arg(long, conflicts_with = "kube", group = "name_prefix", help_heading = QUADLET_OPTIONS)]
     pub pod: bool,
 
+    /// Keep published ports on each container instead of applying them to the pod
+    ///
+    /// Useful when the pod's ports are managed manually.
+    /// Note that Podman does not allow containers in a pod to publish ports,
+    /// they must be published by the pod.
+    ///
+    /// Requires `--pod`.
+    #[arg(long, requires = "pod", help_heading = QUADLET_OPTIONS)]
+    pub no_pod_ports: bool,
+
+    /// Prefix the name of each generated file with the compose project's name
+    ///
+    /// The top-level `name` field in the compose file is required when using this option.
+    ///
+    /// Each container, network, and volume is renamed to "{name}-{resource}",
+    /// see `--prefix-separator`, and references between them are updated.
+    /// This keeps the files of multiple compose projects from colliding.
+    #[arg(long, conflicts_with = "kube", group = "name_prefix", help_heading = QUADLET_OPTIONS)]
+    pub use_project_name: bool,
+
+    /// Separator between the pod or project name and the name of each renamed file
+    ///
+    /// Defaults to "-".
+    ///
+    /// Requires `--pod`, `--use-project-name`, or `--docker-compat`.
+    #[arg(
+        long,
+        value_name = "CHAR",
+        value_parser = prefix_separator_parser(),
+        requires = "name_prefix",
+        help_heading = QUADLET_OPTIONS
+    )]
+    pub prefix_separator: Option<char>,
+
+    /// Name each container "{PREFIX}{service}" instead of Quadlet's default "systemd-{unit}"
+    ///
+    /// Converts to "ContainerName={PREFIX}{service}" for each service without a
+    /// `container_name`. The name of each container is not changed by `--pod` or
+    /// `--use-project-name`, add "{project}" to the prefix to include the compose project's name,
+    /// e.g. `--container-prefix {project}-`.
+    ///
+    /// The top-level `name` field in the compose file is required if the prefix contains
+    /// "{project}".
+    #[arg(long, value_name = "PREFIX", allow_hyphen_values = true, help_heading = QUADLET_OPTIONS)]
+    pub container_prefix: Option<String>,
+
+    /// Name the pod "{pod}{SUFFIX}" instead of Quadlet's default "systemd-{unit}"
+    ///
+    /// Converts to "PodName={name}{SUFFIX}", where name is the top-level `name` field of the
+    /// compose file, e.g. `--pod-suffix -pod`.
+    ///
+    /// Requires `--pod`.
+    #[arg(
+        long,
+        value_name = "SUFFIX",
+        allow_hyphen_values = true,
+        requires = "pod",
+        help_heading = QUADLET_OPTIONS
+    )]
+    pub pod_suffix: Option<String>,
+
+    /// Name of the pod, instead of Quadlet's default "systemd-{unit}"
+    ///
+    /// Converts to "PodName=NAME".
+    ///
+    /// Requires `--pod`.
+    #[arg(
+        long = "pod-name",
+        value_name = "NAME",
+        requires = "pod",
+        conflicts_with = "pod_suffix",
+        help_heading = QUADLET_OPTIONS
+    )]
+    pub podman_pod_name: Option<String>,
+
+    /// Set a DNS server for the pod, shared by each container in the pod
+    ///
+    /// Converts to "DNS=IP". The `dns` of each service is added.
+    ///
+    /// Requires `--pod`.
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "IP", requires = "pod", help_heading = QUADLET_OPTIONS)]
+    pub pod_dns: Vec<IpAddr>,
+
+    /// Image used for the pod's infra container
+    ///
+    /// Converts to "PodmanArgs=--infra-image IMAGE".
+    ///
+    /// Requires `--pod`.
+    #[arg(long, value_name = "IMAGE", requires = "pod", help_heading = QUADLET_OPTIONS)]
+    pub pod_infra_image: Option<String>,
+
+    /// Name of the pod's infra container
+    ///
+    /// Converts to "PodmanArgs=--infra-name NAME".
+    ///
+    /// Requires `--pod`.
+    #[arg(long, value_name = "NAME", requires = "pod", help_heading = QUADLET_OPTIONS)]
+    pub pod_infra_name: Option<String>,
+
+    /// Attach services without `networks` to the project's default network, like `docker compose`
+    ///
+    /// A "{name}_default" network is created, using the top-level `name` field of the
+    /// compose file, and each service without `networks` or a `network_mode` is attached to it.
+    /// The network is named "default" if the compose file does not have a `name`,
+    /// or if using `--use-project-name`, which prefixes it.
+    ///
+    /// The `default` network in the compose file's top-level `networks`,
+    /// if present, configures the created network.
+    #[arg(long, conflicts_with_all = ["pod", "kube"], help_heading = QUADLET_OPTIONS)]
+    pub default_network: bool,
+
+    /// Mirror the behavior of `docker compose up` as closely as Quadlet allows
+    ///
+    /// Equivalent to `--use-project-name --prefix-separator _ --default-network`,
+    /// so each file is named "{name}_{resource}" and services without `networks` are attached
+    /// to the "{name}_default" network. Docker Compose specific labels are removed.
+    ///
+    /// A different `--prefix-separator` may be given.
+    #[arg(
+        long,
+        conflicts_with_all = ["kube", "keep_docker_labels"],
+        group = "name_prefix",
+        help_heading = QUADLET_OPTIONS
+    )]
+    pub docker_compat: bool,
+
+    /// Reference networks by their generated `.network` Quadlet file (default)
+    ///
+    /// Each network a service is attached to is referenced as "{network}.network",
+    /// so the container depends on the network's generated service.
+    #[arg(long, overrides_with = "no_link_networks", help_heading = QUADLET_OPTIONS)]
+    pub link_networks: bool,
+
+    /// Reference networks by name, for networks which already exist
+    ///
+    /// Each network a service is attached to is referenced by its name instead of
+    /// "{network}.network", and `.network` files are not generated.
+    /// The options of networks in the compose file's top-level `networks` are ignored.
+    #[arg(long, overrides_with = "link_networks", help_heading = QUADLET_OPTIONS)]
+    pub no_link_networks: bool,
+
+    /// Write the services of each profile into their own subdirectory
+    ///
+    /// Services without `profiles`, networks, volumes, and the pod if using `--pod`,
+    /// are written into the "base" subdirectory.
+    /// Services of a profile are written into a subdirectory named after it,
+    /// a service with multiple profiles is written into each one.
+    ///
+    /// The `--file` option must be a directory if used.
+    #[arg(long, help_heading = QUADLET_OPTIONS)]
+    pub split_by_profile: bool,
+
+    /// The format to convert the compose file into
+    #[arg(long, value_enum, default_value_t, conflicts_with = "kube")]
+    pub format: Format,
+
+    /// Print the resolved compose file to stderr before converting it
+    ///
+    /// The compose file is printed after YAML anchors, aliases, and merge keys (`<<`) are
+    /// resolved, `extends` are applied, and values are normalized,
+    /// so it is exactly what Podlet converts.
+    ///
+    /// Unlike `--format compose`, the conversion continues as usual.
+    #[arg(long)]
+    pub dump_resolved: bool,
+
     /// Create a Kubernetes YAML file for a pod instead of separate containers
     ///
+    /// Alias for `--format kube`.
+    ///
     /// A `.kube` file using the generated Kubernetes YAML file is also created.
     ///
     /// The top-level `name` field in the compose file is required when using this option.
@@ -62,6 +295,152 @@ pub struct Compose {
     #[arg(long, conflicts_with = "pod")]
     pub kube: bool,
 
+    /// Kubernetes version the generated objects should conform to, e.g. "1.9"
+    ///
+    /// Fields unsupported by that version are omitted with a warning. A tmpfs volume's `size` is
+    /// not supported before 1.10. Startup probes, generated from a healthcheck's `start_period`,
+    /// are not supported before 1.18; the liveness probe is delayed by the start period instead.
+    ///
+    /// All generated objects are core `v1` objects, so their `apiVersion` is the same at every
+    /// level; only omitting fields is supported.
+    ///
+    /// Defaults to the latest version.
+    #[arg(long, value_name = "VERSION", help_heading = KUBERNETES_OPTIONS)]
+    pub kube_api_level: Option<k8s::KubeApiLevel>,
+
+    /// Write each Kubernetes object into its own YAML file
+    ///
+    /// The pod is written to "{name}-kube.yaml",
+    /// each persistent volume claim to "{name}-pvc-{volume}.yaml",
+    /// and each config map to "{name}-configmap-{config}.yaml".
+    ///
+    /// The `.kube` file only uses the pod and config map files,
+    /// persistent volume claims must be created separately.
+    #[arg(long, help_heading = KUBERNETES_OPTIONS)]
+    pub kube_split: bool,
+
+    /// Keep Docker Compose specific labels
+    ///
+    /// By default, labels starting with `com.docker.compose.` are removed from services, networks,
+    /// volumes, and configs as they are meaningless to Podman.
+    #[arg(long)]
+    pub keep_docker_labels: bool,
+
+    /// Action to take once a container with a healthcheck becomes unhealthy
+    ///
+    /// Converts to "HealthOnFailure=ACTION" for each service with a `healthcheck`.
+    ///
+    /// Can be overridden per service with the `health_on_failure` option of the `x-podlet`
+    /// extension.
+    #[arg(long, value_enum, value_name = "ACTION", help_heading = QUADLET_OPTIONS)]
+    pub health_on_failure: Option<HealthOnFailure>,
+
+    /// Set Docker's healthcheck defaults explicitly
+    ///
+    /// Healthchecks without an `interval`, `timeout`, or `retries` are given Docker's defaults of
+    /// 30s, 30s, and 3, which converts to "HealthInterval=30s", "HealthTimeout=30s", and
+    /// "HealthRetries=3". By default, they are left unset, so Podman's defaults are used.
+    /// Podman's defaults currently match Docker's, but are not guaranteed to stay the same.
+    #[arg(long, help_heading = QUADLET_OPTIONS)]
+    pub healthcheck_defaults: bool,
+
+    /// Describe each container's unit by its service name and image
+    ///
+    /// Sets "Description=SERVICE container (IMAGE)" in the [Unit] section of each container,
+    /// unless a description is given with `--description`.
+    #[arg(long, help_heading = QUADLET_OPTIONS)]
+    pub add_unit_description_from_image: bool,
+
+    /// Pull images only if the registry has a newer image for services with `pull_policy: always`
+    ///
+    /// Converts `pull_policy: always` to "Pull=newer" instead of "Pull=always",
+    /// so the image is not pulled again each time the container starts.
+    #[arg(long, help_heading = QUADLET_OPTIONS)]
+    pub prefer_newer: bool,
+
+    /// Convert `develop.watch` sync rules into bind mounts
+    ///
+    /// Each `sync` and `sync+restart` rule of a service's `develop.watch` with a `target` converts
+    /// to a read-write bind mount of the rule's `path` to the `target`,
+    /// so local edits are reflected in the container.
+    /// `rebuild` rules cannot be converted and are skipped with a warning.
+    ///
+    /// Not supported with `--format compose`.
+    #[arg(long)]
+    pub watch_as_mounts: bool,
+
+    /// Wait for each container to send a ready notification with sd-notify
+    ///
+    /// Converts to "Notify=true", which makes Quadlet set "Type=notify" for the service.
+    /// The application in each container must support sd-notify.
+    ///
+    /// Can be overridden per service with the `notify` option of the `x-podlet` extension.
+    #[arg(long, help_heading = QUADLET_OPTIONS)]
+    pub notify: bool,
+
+    /// Mask a path in each container, so it cannot be accessed
+    ///
+    /// Converts to "Mask=PATH". The path must be absolute.
+    ///
+    /// Paths from the `mask` option of the `x-podlet` extension are added.
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "PATH", help_heading = QUADLET_OPTIONS)]
+    pub mask: Vec<String>,
+
+    /// Unmask a path in each container, or `ALL` to unmask all paths
+    ///
+    /// Converts to "Unmask=PATH". The path must be absolute.
+    ///
+    /// Paths from the `unmask` option of the `x-podlet` extension are added.
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "PATH", help_heading = QUADLET_OPTIONS)]
+    pub unmask: Vec<String>,
+
+    /// Timezone of each container
+    ///
+    /// With `--format quadlet`, converts to "Timezone=TIMEZONE".
+    /// With `--format kube`, sets the `TZ` environment variable.
+    ///
+    /// A service's `TZ` environment variable, or the `timezone` option of the `x-podlet`
+    /// extension, takes precedence.
+    #[arg(long, value_name = "TIMEZONE")]
+    pub timezone: Option<String>,
+
+    /// Set an environment variable in each container
+    ///
+    /// Overrides a variable of the same name set by a service.
+    ///
+    /// Can be specified multiple times.
+    #[arg(short, long, value_name = "KEY=VALUE")]
+    pub env: Vec<String>,
+
+    /// Read environment variables for each container from a file
+    ///
+    /// Converts to "EnvironmentFile=PATH" for each container.
+    /// Relative paths are resolved from the current working directory.
+    ///
+    /// By default, a variable set by `--env`, a service's `environment`, or a service's `env_file`
+    /// takes precedence over the same variable in the file. See `--env-merge`.
+    ///
+    /// Can be specified multiple times.
+    #[arg(long, value_name = "PATH", help_heading = QUADLET_OPTIONS)]
+    pub env_file: Vec<PathBuf>,
+
+    /// Precedence of variables set in an `--env-file` and by a service
+    ///
+    /// `--env` always takes precedence.
+    #[arg(
+        long,
+        value_enum,
+        value_name = "PRECEDENCE",
+        default_value_t,
+        requires = "env_file",
+        help_heading = QUADLET_OPTIONS
+    )]
+    pub env_merge: EnvMerge,
+
     /// The compose file to convert
     ///
     /// If `-` or not provided and stdin is not a terminal,
@@ -70,8 +449,51 @@ pub struct Compose {
     /// If not provided, and stdin is a terminal, Podlet will look for (in order)
     /// `compose.yaml`, `compose.yml`, `docker-compose.yaml`, and `docker-compose.yml`,
     /// in the current working directory.
+    /// If a directory, Podlet will look for them in that directory instead.
     #[allow(clippy::struct_field_names)]
     pub compose_file: Option<PathBuf>,
+
+    /// Options for the Kubernetes pod, only supported with `--format kube`
+    #[command(flatten)]
+    pub pod_options: k8s::PodOptions,
+}
+
+/// Help heading of the [`Compose`] options which are only supported with `--format quadlet`.
+const QUADLET_OPTIONS: &str = "Quadlet Options";
+
+/// Help heading of the [`Compose`] options which are only supported with `--format kube`.
+const KUBERNETES_OPTIONS: &str = "Kubernetes Options";
+
+/// Ensure that none of the given `(flag, is_set)` pairs are set unless the `format` is
+/// `supported`.
+///
+/// # Errors
+///
+/// Returns an error naming the first set flag if the `format` is not `supported`.
+fn ensure_only_with<const N: usize>(
+    format: Format,
+    supported: Format,
+    flags: [(&str, bool); N],
+) -> color_eyre::Result<()> {
+    if format == supported {
+        return Ok(());
+    }
+    let supported = supported
+        .to_possible_value()
+        .expect("no `Format` variants are skipped");
+    for (flag, is_set) in flags {
+        ensure!(
+            !is_set,
+            "`{flag}` is only supported with `--format {}`",
+            supported.get_name()
+        );
+    }
+    Ok(())
+}
+
+/// Create a [`TypedValueParser`] for parsing the `prefix_separator` field of [`Compose`].
+fn prefix_separator_parser() -> impl TypedValueParser<Value = char> {
+    PossibleValuesParser::new(["-", "_", "."]).try_map(|separator| separator.parse::<char>())
 }
 
 impl Compose {
@@ -88,71 +510,526 @@ impl Compose {
         self,
         unit: Option<Unit>,
         install: Option<quadlet::Install>,
+    ) -> color_eyre::Result<Vec<File>> {
+        let mut options = compose_spec::Compose::options();
+        options.apply_merge(true);
+        let compose = read_from_file_or_stdin(self.compose_file.as_deref(), &options)
+            .wrap_err("error reading compose file")?;
+
+        self.compose_try_into_files(compose, unit, install)
+    }
+
+    /// Attempt to convert an already read [`compose_spec::Compose`] into [`File`]s.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there was an error validating the compose file or converting it to
+    /// Kubernetes YAML or Quadlet files.
+    #[allow(clippy::too_many_lines)]
+    fn compose_try_into_files(
+        self,
+        mut compose: compose_spec::Compose,
+        unit: Option<Unit>,
+        install: Option<quadlet::Install>,
     ) -> color_eyre::Result<Vec<File>> {
         let Self {
             pod,
+            no_pod_ports,
+            use_project_name,
+            prefix_separator,
+            container_prefix,
+            pod_suffix,
+            podman_pod_name,
+            pod_dns,
+            pod_infra_image,
+            pod_infra_name,
+            default_network,
+            docker_compat,
+            link_networks: _,
+            no_link_networks,
+            split_by_profile,
+            format,
+            dump_resolved,
             kube,
+            kube_api_level,
+            kube_split,
+            keep_docker_labels,
+            health_on_failure,
+            healthcheck_defaults,
+            add_unit_description_from_image,
+            prefer_newer,
+            watch_as_mounts,
+            notify,
+            mask,
+            unmask,
+            timezone,
+            env,
+            env_file,
+            env_merge,
             compose_file,
+            pod_options,
         } = self;
 
-        let mut options = compose_spec::Compose::options();
-        options.apply_merge(true);
-        let compose = read_from_file_or_stdin(compose_file.as_deref(), &options)
-            .wrap_err("error reading compose file")?;
+        if dump_resolved {
+            eprint!("{}", resolved_document(&compose)?);
+        }
+
+        let format = if kube { Format::Kube } else { format };
+        ensure_only_with(
+            format,
+            Format::Quadlet,
+            [("--docker-compat", docker_compat)],
+        )?;
+
+        let podman = if format == Format::Compose {
+            PodmanExtension::default()
+        } else {
+            PodmanExtension::take_from(&mut compose.extensions)?
+        };
+        let pod = pod || (format == Format::Quadlet && podman.in_pod == Some(true));
+        if !pod && !podman.pod_args.is_empty() {
+            warning::warn(format_args!(
+                "services are not put in a pod, ignoring `{}.pod_args`",
+                podman::KEY
+            ));
+        }
+
+        let use_project_name = use_project_name || docker_compat;
+        let default_network = default_network || docker_compat;
+        let prefix_separator = prefix_separator
+            .or(docker_compat.then_some('_'))
+            .or(podman.name_separator_compat.then_some('_'));
+        ensure_only_with(
+            format,
+            Format::Quadlet,
+            [
+                ("--pod", pod),
+                ("--use-project-name", use_project_name),
+                ("--container-prefix", container_prefix.is_some()),
+                ("--default-network", default_network),
+                ("--no-link-networks", no_link_networks),
+                ("--split-by-profile", split_by_profile),
+                ("--health-on-failure", health_on_failure.is_some()),
+                ("--healthcheck-defaults", healthcheck_defaults),
+                (
+                    "--add-unit-description-from-image",
+                    add_unit_description_from_image,
+                ),
+                ("--prefer-newer", prefer_newer),
+                ("--notify", notify),
+                ("--mask", !mask.is_empty()),
+                ("--unmask", !unmask.is_empty()),
+                ("--env-file", !env_file.is_empty()),
+            ],
+        )?;
+        ensure_only_with(
+            format,
+            Format::Kube,
+            [
+                ("--kube-api-level", kube_api_level.is_some()),
+                ("--kube-split", kube_split),
+            ],
+        )?;
+        mask::validate(&mask, false).wrap_err("invalid `--mask` path")?;
+        mask::validate(&unmask, true).wrap_err("invalid `--unmask` path")?;
+        ensure!(
+            timezone.is_none() || format != Format::Compose,
+            "`--timezone` is not supported with `--format compose`"
+        );
+        ensure!(
+            !watch_as_mounts || format != Format::Compose,
+            "`--watch-as-mounts` is not supported with `--format compose`"
+        );
+        ensure!(
+            pod_options.is_empty() || format == Format::Kube,
+            "Kubernetes pod options are only supported with `--format kube`"
+        );
+
         compose
             .validate_all()
             .wrap_err("error validating compose file")?;
 
-        if kube {
-            let mut k8s_file = k8s::File::try_from(compose)
-                .wrap_err("error converting compose file into Kubernetes YAML")?;
+        if !keep_docker_labels {
+            labels::remove_docker_labels(&mut compose);
+        }
+
+        if format != Format::Compose {
+            version::warn_legacy(compose.version.as_deref());
+            unused::warn_unreferenced(&compose);
+            unused::remove_unreferenced_configs_and_secrets(&mut compose);
+            if format == Format::Quadlet {
+                sanitize::file_names(&mut compose)?;
+                create_host_path::warn_missing_sources(&compose.services, compose_file.as_deref());
+            }
+            develop::remove_develop(&mut compose, watch_as_mounts)?;
+            windows::remove_windows_options(&mut compose);
+            rollout::remove_rollout_configs(&mut compose);
+            labels::remove_deploy_labels(&mut compose);
+            config::resolve_environment_sources(&mut compose.configs)?;
+            config::warn_content_interpolation(&compose.configs);
+
+            if default_network {
+                let network = match &compose.name {
+                    Some(name) if !use_project_name => format!("{name}_default"),
+                    _ => "default".to_owned(),
+                };
+                let network = Identifier::new(network)
+                    .wrap_err("error creating the name of the default network")?;
+                default_network::attach(&mut compose, &network);
+            }
+
+            if pod || format == Format::Kube {
+                links::remove_links_in_pod(&mut compose.services);
+            } else {
+                links::links_into_aliases(&mut compose.services)
+                    .wrap_err("error converting `links`")?;
+            }
+        }
+
+        if format != Format::Quadlet {
+            chown::remove(&mut compose.services);
+            host_gateway::remove(&mut compose.services);
+        }
+        if format != Format::Kube {
+            lifecycle::remove(&mut compose.services);
+        }
+
+        environment::add_env_files(&mut compose.services, &env_file, env_merge)
+            .wrap_err("error adding `--env-file` environment files")?;
+        environment::add_to_services(&mut compose.services, &env)
+            .wrap_err("error adding `--env` environment variables")?;
 
-            let kube =
-                quadlet::Kube::new(PathBuf::from(format!("{}-kube.yaml", k8s_file.name)).into());
-            let quadlet_file = quadlet::File {
-                name: k8s_file.name.clone(),
+        if let Some(timezone) = timezone.as_deref().filter(|_| format == Format::Kube) {
+            for service in compose.services.values_mut() {
+                timezone::set_environment(service, timezone);
+            }
+        }
+
+        match format {
+            Format::Quadlet => {
+                let mut pod_template = quadlet::Pod {
+                    dns: pod_dns,
+                    pod_name: podman_pod_name,
+                    ..quadlet::Pod::default()
+                };
+                if let Some(image) = pod_infra_image {
+                    pod_template.push_arg("infra-image", &image);
+                }
+                if let Some(name) = pod_infra_name {
+                    pod_template.push_arg("infra-name", &name);
+                }
+                for arg in podman.pod_args {
+                    pod_template.podman_args_push_str(&arg);
+                }
+                let options = QuadletOptions {
+                    pod_name: None,
+                    project_name: None,
+                    no_pod_ports,
+                    link_networks: !no_link_networks,
+                    prefix_separator: prefix_separator.unwrap_or('-'),
+                    container_prefix,
+                    pod_suffix,
+                    pod_template,
+                    health_on_failure,
+                    healthcheck_defaults,
+                    description_from_image: add_unit_description_from_image,
+                    notify,
+                    mask,
+                    unmask,
+                    timezone,
+                };
+                let mut files = if split_by_profile {
+                    compose_try_into_profile_files(
+                        compose,
+                        pod,
+                        use_project_name,
+                        &options,
+                        unit.as_ref(),
+                        install.as_ref(),
+                    )
+                } else {
+                    compose_try_into_quadlet_files(
+                        compose,
+                        pod,
+                        use_project_name,
+                        options,
+                        unit,
+                        install,
+                    )
+                }?;
+                if prefer_newer {
+                    for file in files.iter_mut().filter_map(File::as_quadlet_file_mut) {
+                        if let quadlet::Resource::Container(container) = &mut file.resource {
+                            if container.pull == Some(PullPolicy::Always) {
+                                container.pull = Some(PullPolicy::Newer);
+                            }
+                        }
+                    }
+                }
+                Ok(files)
+            }
+            Format::Kube => compose_try_into_kube_files(
+                compose,
+                compose_file.as_deref(),
+                kube_api_level,
+                pod_options,
+                kube_split,
                 unit,
-                resource: kube.into(),
-                globals: Globals::default(),
-                service: None,
                 install,
-            };
+            ),
+            Format::Compose => {
+                let name = compose
+                    .name
+                    .as_ref()
+                    .map_or_else(|| "compose".to_owned(), |name| format!("{name}-compose"));
+                Ok(vec![NormalizedFile { name, compose }.into()])
+            }
+        }
+    }
+}
 
-            k8s_file.name.push_str("-kube");
-            Ok(vec![quadlet_file.into(), k8s_file.into()])
-        } else {
-            let compose_spec::Compose {
-                version: _,
-                name,
-                include,
-                services,
-                networks,
-                volumes,
-                configs,
-                secrets,
-                extensions,
-            } = compose;
-
-            let pod_name = pod
-                .then(|| name.ok_or_eyre("`name` is required when using `--pod`"))
-                .transpose()?
-                .map(Into::into);
-
-            ensure!(include.is_empty(), "`include` is not supported");
-            ensure!(configs.is_empty(), "`configs` is not supported");
-            ensure!(
-                secrets.values().all(Resource::is_external),
-                "only external `secrets` are supported",
-            );
-            ensure!(
-                extensions.is_empty(),
-                "compose extensions are not supported"
-            );
+/// Serialize the resolved `compose` file, as read, for `podlet compose --dump-resolved`.
+///
+/// # Errors
+///
+/// Returns an error if the compose file could not be serialized.
+fn resolved_document(compose: &compose_spec::Compose) -> color_eyre::Result<String> {
+    serde_yaml::to_string(compose).wrap_err("error serializing resolved compose file")
+}
 
-            parts_try_into_files(services, networks, volumes, pod_name, unit, install)
-                .wrap_err("error converting compose file into Quadlet files")
-        }
+/// Output format for the `podlet compose` subcommand.
+#[derive(ValueEnum, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Quadlet files: a `.container` file for each service, and `.network` and `.volume` files
+    #[default]
+    Quadlet,
+
+    /// Kubernetes YAML for a pod with a `.kube` file which uses it
+    Kube,
+
+    /// The compose file after merging and normalization
+    Compose,
+}
+
+/// A normalized compose file, the output of `podlet compose --format compose`.
+#[derive(Debug)]
+pub struct NormalizedFile {
+    /// The name of the file, without the extension.
+    pub name: String,
+
+    /// The normalized compose file.
+    pub compose: compose_spec::Compose,
+}
+
+impl Display for NormalizedFile {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(&serde_yaml::to_string(&self.compose).map_err(|_| fmt::Error)?)
+    }
+}
+
+/// Attempt to convert a [`compose_spec::Compose`] into a Kubernetes YAML [`File`] and a Quadlet
+/// `.kube` [`File`] which uses it.
+///
+/// `compose_file` is the path of the compose file, used to resolve relative `file` sources of
+/// configs and secrets. `pod_options` are applied to the Kubernetes pod.
+///
+/// If the compose file has `configs`, their Kubernetes config maps are placed in a separate YAML
+/// [`File`] which the `.kube` file references with `ConfigMap=`.
+///
+/// If `kube_api_level` is set, fields unsupported at that level are removed from the pod.
+///
+/// If `split` is `true`, each config map and persistent volume claim is placed in its own YAML
+/// [`File`] instead. The persistent volume claims are not used by the `.kube` file.
+///
+/// # Errors
+///
+/// Returns an error if the compose file could not be converted into Kubernetes YAML.
+fn compose_try_into_kube_files(
+    compose: compose_spec::Compose,
+    compose_file: Option<&Path>,
+    kube_api_level: Option<k8s::KubeApiLevel>,
+    pod_options: k8s::PodOptions,
+    split: bool,
+    unit: Option<Unit>,
+    install: Option<quadlet::Install>,
+) -> color_eyre::Result<Vec<File>> {
+    let mut k8s_file = k8s::File::try_from_compose(compose, compose_file, pod_options)
+        .wrap_err("error converting compose file into Kubernetes YAML")?;
+    if let Some(kube_api_level) = kube_api_level {
+        k8s_file.downgrade(kube_api_level);
+    }
+    if !k8s_file.secrets.is_empty() {
+        let names: Vec<_> = k8s_file
+            .secrets
+            .iter()
+            .filter_map(|secret| secret.metadata.name.as_deref())
+            .collect();
+        warning::warn(format_args!(
+            "the contents of secrets `{}` are written in plain text to the Kubernetes YAML file, \
+                keep it private",
+            names.join("`, `")
+        ));
+    }
+
+    let (config_map_files, persistent_volume_claim_files) = if split {
+        (
+            k8s_file.split_config_maps(),
+            k8s_file.split_persistent_volume_claims(),
+        )
+    } else {
+        (
+            k8s_file.take_config_maps().into_iter().collect(),
+            Vec::new(),
+        )
+    };
+    if !persistent_volume_claim_files.is_empty() {
+        warning::warn(
+            "the `.kube` file does not use the split persistent volume claim files, \
+                create their volumes with `podman kube play` before starting it",
+        );
+    }
+
+    let kube = quadlet::Kube::new(
+        PathBuf::from(format!("{}-kube.yaml", k8s_file.name)).into(),
+        config_map_files
+            .iter()
+            .map(|file| PathBuf::from(format!("{}.yaml", file.name)))
+            .collect(),
+    );
+    let quadlet_file = quadlet::File {
+        name: k8s_file.name.clone(),
+        unit,
+        resource: kube.into(),
+        globals: Globals::default(),
+        service: None,
+        install,
+    };
+
+    k8s_file.name.push_str("-kube");
+    let mut files = vec![quadlet_file.into(), k8s_file.into()];
+    files.extend(persistent_volume_claim_files.into_iter().map(Into::into));
+    files.extend(config_map_files.into_iter().map(Into::into));
+    Ok(files)
+}
+
+/// Attempt to convert a [`compose_spec::Compose`] into Quadlet [`File`]s, split into a
+/// subdirectory for the services of each profile, for `podlet compose --split-by-profile`.
+///
+/// Services without `profiles`, networks, volumes, and the pod, if `pod` is `true`, are placed in
+/// the "base" subdirectory.
+///
+/// # Errors
+///
+/// Returns an error if a profile is named "base" or there was an error converting the services of
+/// a profile into Quadlet files.
+fn compose_try_into_profile_files(
+    compose: compose_spec::Compose,
+    pod: bool,
+    use_project_name: bool,
+    options: &QuadletOptions,
+    unit: Option<&Unit>,
+    install: Option<&quadlet::Install>,
+) -> color_eyre::Result<Vec<File>> {
+    let (base, profiles) = profiles::split(compose)?;
+    let convert = |compose, directory: &str| {
+        compose_try_into_quadlet_files(
+            compose,
+            pod,
+            use_project_name,
+            options.clone(),
+            unit.cloned(),
+            install.cloned(),
+        )
+        .wrap_err_with(|| format!("error converting services of `{directory}`"))
+    };
+
+    let mut files = convert(base, profiles::BASE)?
+        .into_iter()
+        .map(|file| File::Subdirectory {
+            directory: profiles::BASE.to_owned(),
+            file: Box::new(file),
+        })
+        .collect::<Vec<_>>();
+    for (profile, compose) in profiles {
+        // Networks, volumes, and the pod are only written to the base directory. Config content
+        // files are written relative to each container which mounts them.
+        files.extend(
+            convert(compose, &profile)?
+                .into_iter()
+                .filter(|file| {
+                    matches!(file, File::ConfigContent(_))
+                        || file.as_quadlet_file().is_some_and(|file| {
+                            matches!(file.resource, quadlet::Resource::Container(_))
+                        })
+                })
+                .map(|file| File::Subdirectory {
+                    directory: profile.clone(),
+                    file: Box::new(file),
+                }),
+        );
+    }
+
+    Ok(files)
+}
+
+/// Attempt to convert a [`compose_spec::Compose`] into Quadlet [`File`]s.
+///
+/// If `pod` is `true`, the `pod_name` of the `options` is set to the compose file's `name`, so a
+/// `.pod` Quadlet file is also created and each container is linked to it. If `use_project_name`
+/// is `true`, the `project_name` of the `options` is set instead, so each file is prefixed with it.
+///
+/// # Errors
+///
+/// Returns an error if an unsupported top-level option is used or there was an error converting
+/// the services, networks, or volumes into Quadlet files.
+fn compose_try_into_quadlet_files(
+    compose_spec::Compose {
+        version: _,
+        name,
+        include,
+        mut services,
+        networks,
+        volumes,
+        configs,
+        secrets,
+        extensions,
+    }: compose_spec::Compose,
+    pod: bool,
+    use_project_name: bool,
+    mut options: QuadletOptions,
+    unit: Option<Unit>,
+    install: Option<quadlet::Install>,
+) -> color_eyre::Result<Vec<File>> {
+    let require_name = |option: &str| {
+        name.clone()
+            .map(String::from)
+            .ok_or_else(|| eyre!("`name` is required when using `{option}`"))
+    };
+    options.pod_name = pod.then(|| require_name("--pod")).transpose()?;
+    options.project_name = use_project_name
+        .then(|| require_name("--use-project-name"))
+        .transpose()?;
+    if let Some(prefix) = options
+        .container_prefix
+        .as_mut()
+        .filter(|prefix| prefix.contains("{project}"))
+    {
+        *prefix = prefix.replace("{project}", &require_name("--container-prefix {project}")?);
     }
+
+    ensure!(include.is_empty(), "`include` is not supported");
+    ensure!(
+        secrets.values().all(Resource::is_external),
+        "only external `secrets` are supported",
+    );
+    secrets::use_external_names(&mut services, &secrets)?;
+    ensure!(
+        extensions.is_empty(),
+        "compose extensions are not supported"
+    );
+
+    parts_try_into_files(services, networks, volumes, configs, options, unit, install)
+        .wrap_err("error converting compose file into Quadlet files")
 }
 
 /// Read and deserialize a [`compose_spec::Compose`] from a file at the given [`Path`], stdin, or a
@@ -160,7 +1037,11 @@ impl Compose {
 ///
 /// If the path is '-', or stdin is not a terminal, the compose file is deserialized from stdin.
 /// If a path is not provided, the files `compose.yaml`, `compose.yml`, `docker-compose.yaml`,
-/// and `docker-compose.yml` are, in order, looked for in the current directory.
+/// and `docker-compose.yml` are, in order, looked for in the current directory. If the path is a
+/// directory, they are looked for in that directory instead.
+///
+/// The `extends` of each service are resolved, relative `extends.file` paths are resolved from
+/// the directory of the compose file.
 ///
 /// # Errors
 ///
@@ -168,7 +1049,8 @@ impl Compose {
 ///
 /// - There was an error opening the given file.
 /// - Stdin was selected and stdin is a terminal.
-/// - No path was given and none of the default files could be opened.
+/// - No path, or a directory, was given and none of the default files could be opened.
+/// - There was an error resolving `extends`.
 /// - There was an error deserializing [`compose_spec::Compose`].
 fn read_from_file_or_stdin(
     path: Option<&Path>,
@@ -178,69 +1060,196 @@ fn read_from_file_or_stdin(
         if path.as_os_str() == "-" {
             return read_from_stdin(options);
         }
-        let compose_file = fs::File::open(path)
-            .wrap_err("could not open provided compose file")
-            .suggestion("make sure you have the proper permissions for the given file")?;
-        (compose_file, path)
+        if path.is_dir() {
+            find_default_file(path).ok_or_else(|| {
+                eyre!(
+                    "none of `compose.yaml`, `compose.yml`, `docker-compose.yaml`, or \
+                        `docker-compose.yml` exist in directory `{}` or could not be read",
+                    path.display()
+                )
+            })?
+        } else {
+            let compose_file = fs::File::open(path)
+                .wrap_err("could not open provided compose file")
+                .suggestion("make sure you have the proper permissions for the given file")?;
+            (compose_file, path.to_owned())
+        }
     } else {
-        const FILE_NAMES: [&str; 4] = [
-            "compose.yaml",
-            "compose.yml",
-            "docker-compose.yaml",
-            "docker-compose.yml",
-        ];
-
         if !io::stdin().is_terminal() {
             return read_from_stdin(options);
         }
 
-        let mut result = None;
-        for file_name in FILE_NAMES {
-            if let Ok(compose_file) = fs::File::open(file_name) {
-                result = Some((compose_file, file_name.as_ref()));
-                break;
-            }
-        }
-
-        result.ok_or_eyre(
+        find_default_file(Path::new("")).ok_or_eyre(
             "a compose file was not provided and none of \
                 `compose.yaml`, `compose.yml`, `docker-compose.yaml`, or `docker-compose.yml` \
                 exist in the current directory or could not be read",
         )?
     };
 
-    options
-        .from_yaml_reader(compose_file)
+    let mut value: serde_yaml::Value = serde_yaml::from_reader(compose_file)
+        .wrap_err_with(|| format!("File `{}` is not valid YAML", path.display()))?;
+    value.apply_merge().wrap_err("error applying merge keys")?;
+
+    from_yaml_value(options, value, Some(&path))
         .wrap_err_with(|| format!("File `{}` is not a valid compose file", path.display()))
 }
 
+/// Open the first of `compose.yaml`, `compose.yml`, `docker-compose.yaml`, and
+/// `docker-compose.yml` which exists in `dir`.
+///
+/// Returns the opened file and its path, or [`None`] if none of the files could be opened.
+fn find_default_file(dir: &Path) -> Option<(fs::File, PathBuf)> {
+    const FILE_NAMES: [&str; 4] = [
+        "compose.yaml",
+        "compose.yml",
+        "docker-compose.yaml",
+        "docker-compose.yml",
+    ];
+
+    FILE_NAMES.into_iter().find_map(|file_name| {
+        let path = dir.join(file_name);
+        fs::File::open(&path)
+            .ok()
+            .map(|compose_file| (compose_file, path))
+    })
+}
+
 /// Read and deserialize [`compose_spec::Compose`] from stdin.
 ///
+/// If stdin contains multiple `---` separated YAML documents, they are
+/// [merged](merge::merge_documents()) in order.
+///
 /// # Errors
 ///
-/// Returns an error if stdin is a terminal or there was an error deserializing.
+/// Returns an error if stdin is a terminal or there was an error reading or deserializing.
 fn read_from_stdin(options: &Options) -> color_eyre::Result<compose_spec::Compose> {
-    let stdin = io::stdin();
+    let mut stdin = io::stdin();
     if stdin.is_terminal() {
         bail!("cannot read compose from stdin, stdin is a terminal");
     }
 
-    options
-        .from_yaml_reader(stdin)
+    let mut yaml = String::new();
+    stdin
+        .read_to_string(&mut yaml)
+        .wrap_err("error reading from stdin")?;
+
+    merge::merge_documents(&yaml)
+        .and_then(|value| from_yaml_value(options, value, None))
         .wrap_err("data from stdin is not a valid compose file")
 }
 
-/// Attempt to convert [`Service`]s, [`Networks`], and [`Volumes`] into [`File`]s.
+/// Deserialize [`compose_spec::Compose`] from a YAML `value`, after resolving the `extends` of
+/// each service, [splitting](restart::split_max_attempts()) `restart: on-failure:{max}`, and
+/// [resolving](labels::resolve_service_labels()) service labels.
+///
+/// `path` is the path of the compose file the `value` was read from, or [`None`] if it was read
+/// from stdin. It is used to resolve relative `extends.file` paths.
 ///
 /// # Errors
 ///
-/// Returns an error if a [`Service`], [`Network`], or [`Volume`](compose_spec::Volume) could not be
-/// converted into a [`quadlet::File`].
-fn parts_try_into_files(
-    services: IndexMap<Identifier, Service>,
+/// Returns an error if there was an error resolving `extends` or deserializing.
+fn from_yaml_value(
+    options: &Options,
+    mut value: serde_yaml::Value,
+    path: Option<&Path>,
+) -> color_eyre::Result<compose_spec::Compose> {
+    extends::resolve(&mut value, path).wrap_err("error resolving `extends`")?;
+    windows::normalize_volume_paths(&mut value)?;
+    subpath::reject_bind_subpaths(&value)?;
+    restart::split_max_attempts(&mut value)?;
+    chown::split_chown_option(&mut value);
+    lifecycle::move_hooks(&mut value);
+    host_gateway::move_entries(&mut value);
+    gpus::move_into_reservations(&mut value)?;
+    mode::parse_octal_modes(&mut value)?;
+    size::normalize(&mut value)?;
+    duration::healthcheck_seconds(&mut value);
+    labels::resolve_service_labels(&mut value, path)?;
+    options.from_yaml_value(value).map_err(Into::into)
+}
+
+/// Options, set from the command line, for converting a compose file into Quadlet files.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Default, Clone)]
+struct QuadletOptions {
+    /// Name of the pod to create and link each container to, if using `--pod`.
+    pod_name: Option<String>,
+
+    /// Name of the compose project to prefix each file with, if using `--use-project-name`.
+    project_name: Option<String>,
+
+    /// Whether to keep published ports on each container instead of moving them to the pod.
+    no_pod_ports: bool,
+
+    /// Whether to reference networks by their `.network` Quadlet file instead of by name.
+    link_networks: bool,
+
+    /// Separator between the pod name and container name of containers in a pod.
+    prefix_separator: char,
+
+    /// Prefix of the `ContainerName=` of containers without a `container_name`.
+    container_prefix: Option<String>,
+
+    /// Suffix added to the pod name for the pod's `PodName=`, if using `--pod`.
+    pod_suffix: Option<String>,
+
+    /// Options set from the command line for the pod, if using `--pod`.
+    ///
+    /// Options from the services are added to it.
+    pod_template: quadlet::Pod,
+
+    /// Default action to take once a container with a healthcheck becomes unhealthy.
+    health_on_failure: Option<HealthOnFailure>,
+
+    /// Whether to set Docker's defaults for unset healthcheck options.
+    healthcheck_defaults: bool,
+
+    /// Whether to describe each container's unit by its service name and image.
+    description_from_image: bool,
+
+    /// Whether each container sends a ready notification by default.
+    notify: bool,
+
+    /// Paths to mask in each container.
+    mask: Vec<String>,
+
+    /// Paths to unmask in each container.
+    unmask: Vec<String>,
+
+    /// Default timezone of each container.
+    timezone: Option<String>,
+}
+
+/// Attempt to convert [`Service`]s, [`Networks`], and [`Volumes`] into [`File`]s.
+///
+/// [`Configs`] are used by services and mounted into their containers.
+///
+/// # Errors
+///
+/// Returns an error if a [`Service`], [`Network`], or [`Volume`](compose_spec::Volume) could not be
+/// converted into a [`quadlet::File`].
+fn parts_try_into_files(
+    mut services: IndexMap<Identifier, Service>,
     networks: Networks,
     volumes: Volumes,
-    pod_name: Option<String>,
+    configs: Configs,
+    QuadletOptions {
+        pod_name,
+        project_name,
+        no_pod_ports,
+        link_networks,
+        prefix_separator,
+        container_prefix,
+        pod_suffix,
+        pod_template,
+        health_on_failure,
+        healthcheck_defaults,
+        description_from_image,
+        notify,
+        mask,
+        unmask,
+        timezone,
+    }: QuadletOptions,
     unit: Option<Unit>,
     install: Option<quadlet::Install>,
 ) -> color_eyre::Result<Vec<File>> {
@@ -258,33 +1267,85 @@ fn parts_try_into_files(
         })
         .collect();
 
-    let mut pod_ports = Vec::new();
-    let mut files = services_try_into_quadlet_files(
-        services,
-        unit.as_ref(),
-        install.as_ref(),
-        &volume_has_options,
-        pod_name.as_deref(),
-        &mut pod_ports,
-    )
-    .chain(networks_try_into_quadlet_files(
-        networks,
-        unit.as_ref(),
-        install.as_ref(),
-    ))
-    .chain(volumes_try_into_quadlet_files(
-        volumes,
-        unit.as_ref(),
-        install.as_ref(),
-    ))
-    .map(|result| result.map(Into::into))
-    .collect::<Result<Vec<File>, _>>()?;
+    let replicas = services
+        .iter_mut()
+        .filter_map(|(name, service)| {
+            scale::take_replicas(service)
+                .wrap_err_with(|| format!("error scaling service `{name}`"))
+                .map(|replicas| (replicas > 1).then(|| (name.clone(), replicas)))
+                .transpose()
+        })
+        .collect::<color_eyre::Result<_>>()?;
+
+    // Configs with inline `content` are written to files next to the Quadlet files.
+    let content_files = config::content_files(
+        &configs,
+        services.values().flat_map(|service| &service.configs),
+    )?;
+
+    let context = ServiceContext {
+        unit: unit.as_ref(),
+        install: install.as_ref(),
+        volume_has_options,
+        configs,
+        replicas,
+        pod_name: pod_name.as_deref(),
+        project_name: project_name.as_deref(),
+        no_pod_ports,
+        link_networks,
+        prefix_separator,
+        container_prefix: container_prefix.as_deref(),
+        health_on_failure,
+        healthcheck_defaults,
+        description_from_image,
+        notify,
+        mask: &mask,
+        unmask: &unmask,
+        timezone: timezone.as_deref(),
+    };
+
+    let networks = if link_networks {
+        networks
+    } else {
+        unlinked_networks(networks)
+    };
+
+    let mut pod = pod_template;
+    let networks_and_volumes =
+        networks_try_into_quadlet_files(networks, unit.as_ref(), install.as_ref())
+            .chain(volumes_try_into_quadlet_files(
+                volumes,
+                unit.as_ref(),
+                install.as_ref(),
+            ))
+            .map(|(part, result)| {
+                let result = result.map(|mut file| {
+                    if let Some(project_name) = &project_name {
+                        file.name = project::prefix(project_name, prefix_separator, &file.name);
+                    }
+                    file
+                });
+                (part, result)
+            });
+    let (mut files, errors) = services_try_into_quadlet_files(services, &context, &mut pod)
+        .chain(networks_and_volumes)
+        .fold(
+            (Vec::new(), IndexMap::<_, Vec<_>>::new()),
+            |(mut files, mut errors), (part, result)| {
+                match result {
+                    Ok(file) => files.push(File::from(file)),
+                    Err(error) => errors.entry(part).or_default().push(error),
+                }
+                (files, errors)
+            },
+        );
+    combine_errors(errors)?;
+    files.extend(content_files.into_iter().map(File::from));
 
     if let Some(name) = pod_name {
-        let pod = quadlet::Pod {
-            publish_port: pod_ports,
-            ..quadlet::Pod::default()
-        };
+        if let Some(suffix) = pod_suffix {
+            pod.pod_name = Some(format!("{name}{suffix}"));
+        }
         let pod = quadlet::File {
             name,
             unit,
@@ -299,14 +1360,125 @@ fn parts_try_into_files(
     Ok(files)
 }
 
-/// Attempt to convert Compose [`Service`]s into [`quadlet::File`]s.
+/// Combine the errors from converting the parts of a compose file, grouped by the service,
+/// network, or volume they are from, into a single [`Report`](color_eyre::Report).
 ///
-/// `volume_has_options` should be a map from volume [`Identifier`]s to whether the volume has any
-/// options set. It is used to determine whether to link to a [`quadlet::Volume`] in the created
-/// [`quadlet::Container`].
+/// A single error is returned as is. Otherwise, see [`CombinedErrors`].
 ///
-/// If `pod_name` is [`Some`] and a service has any published ports, they are taken from the
-/// created [`quadlet::Container`] and added to `pod_ports`.
+/// # Errors
+///
+/// Returns an error if `errors` is not empty.
+fn combine_errors(errors: IndexMap<String, Vec<color_eyre::Report>>) -> color_eyre::Result<()> {
+    match errors.values().map(Vec::len).sum() {
+        0 => Ok(()),
+        1 => {
+            let error = errors.into_values().flatten().next();
+            Err(error.expect("errors has one element"))
+        }
+        _ => Err(color_eyre::Report::msg(CombinedErrors(errors))),
+    }
+}
+
+/// Errors from converting the parts of a compose file, grouped by the service, network, or volume
+/// they are from.
+///
+/// Each error is displayed, with its chain of errors, indented under the part it is from. The
+/// errors are part of the message, rather than sections, so they are displayed regardless of the
+/// installed [`eyre`](color_eyre::eyre) handler.
+#[derive(Debug)]
+struct CombinedErrors(IndexMap<String, Vec<color_eyre::Report>>);
+
+impl Display for CombinedErrors {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let Self(errors) = self;
+        let len: usize = errors.values().map(Vec::len).sum();
+        write!(f, "{len} errors occurred converting ")?;
+        for (index, part) in errors.keys().enumerate() {
+            if index > 0 {
+                f.write_str(", ")?;
+            }
+            f.write_str(part)?;
+        }
+
+        for (part, errors) in errors {
+            write!(f, "\n    {part}:")?;
+            for error in errors {
+                write!(f, "\n        {error:#}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Context shared between the conversions of compose [`Service`]s into [`quadlet::File`]s.
+#[allow(clippy::struct_excessive_bools)]
+struct ServiceContext<'a> {
+    /// The `[Unit]` section added to each file.
+    unit: Option<&'a Unit>,
+
+    /// The `[Install]` section added to each file.
+    install: Option<&'a quadlet::Install>,
+
+    /// Map from volume [`Identifier`]s to whether the volume has any options set.
+    ///
+    /// Used to determine whether to link to a [`quadlet::Volume`] in the created
+    /// [`quadlet::Container`]s.
+    volume_has_options: HashMap<Identifier, bool>,
+
+    /// Top-level configs, referenced by services.
+    configs: Configs,
+
+    /// Map from service [`Identifier`]s to their number of replicas, if scaled.
+    ///
+    /// Used to create a container for each replica and to depend on each replica of a service.
+    replicas: HashMap<Identifier, u64>,
+
+    /// Name of the pod containers are a part of, if using `--pod`.
+    pod_name: Option<&'a str>,
+
+    /// Name of the compose project each file is prefixed with, if using `--use-project-name`.
+    project_name: Option<&'a str>,
+
+    /// Whether to keep published ports on each container instead of moving them to the pod.
+    no_pod_ports: bool,
+
+    /// Whether to reference networks by their `.network` Quadlet file instead of by name.
+    link_networks: bool,
+
+    /// Separator between the pod name and container name of containers in a pod.
+    prefix_separator: char,
+
+    /// Prefix of the `ContainerName=` of containers without a `container_name`.
+    container_prefix: Option<&'a str>,
+
+    /// Default action to take once a container with a healthcheck becomes unhealthy.
+    health_on_failure: Option<HealthOnFailure>,
+
+    /// Whether to set Docker's defaults for unset healthcheck options.
+    healthcheck_defaults: bool,
+
+    /// Whether to describe each container's unit by its service name and image.
+    description_from_image: bool,
+
+    /// Whether each container sends a ready notification by default.
+    notify: bool,
+
+    /// Paths to mask in each container.
+    mask: &'a [String],
+
+    /// Paths to unmask in each container.
+    unmask: &'a [String],
+
+    /// Default timezone of each container.
+    timezone: Option<&'a str>,
+}
+
+/// Attempt to convert Compose [`Service`]s into [`quadlet::File`]s.
+///
+/// If the `context` has a `pod_name`, and not `no_pod_ports`, and a service has any published
+/// ports, they are taken from the created [`quadlet::Container`] and added to the `pod`. DNS
+/// settings and extra hosts are also [lifted](pod_dns::lift()) to the `pod`.
 ///
 /// # Errors
 ///
@@ -316,135 +1488,398 @@ fn parts_try_into_files(
 /// the [`Service`] into a [`quadlet::Container`] file.
 fn services_try_into_quadlet_files<'a>(
     services: IndexMap<Identifier, Service>,
-    unit: Option<&'a Unit>,
-    install: Option<&'a quadlet::Install>,
-    volume_has_options: &'a HashMap<Identifier, bool>,
-    pod_name: Option<&'a str>,
-    pod_ports: &'a mut Vec<String>,
-) -> impl Iterator<Item = color_eyre::Result<quadlet::File>> + 'a {
-    services.into_iter().flat_map(move |(name, mut service)| {
-        if service.image.is_some() && service.build.is_some() {
-            return iter::once(Err(eyre!(
-                "error converting service `{name}`: `image` and `build` cannot both be set"
-            )))
-            .chain(None);
-        }
+    context: &'a ServiceContext<'a>,
+    pod: &'a mut quadlet::Pod,
+) -> impl Iterator<Item = (String, color_eyre::Result<quadlet::File>)> + 'a {
+    services.into_iter().flat_map(move |(name, service)| {
+        let part = format!("service `{name}`");
+        service_and_build_try_into_quadlet_files(&name, service, context, pod)
+            .map(move |result| (part.clone(), result))
+    })
+}
 
-        let build = service.build.take().map(|build| {
-            let build = Build::try_from(build.into_long()).wrap_err_with(|| {
-                format!(
-                    "error converting `build` for service `{name}` into a Quadlet `.build` file"
-                )
-            })?;
-            let image = format!("{}.build", build.name()).try_into()?;
-            service.image = Some(image);
-            Ok(quadlet::File {
-                name: build.name().to_owned(),
-                unit: unit.cloned(),
-                resource: build.into(),
-                globals: Globals::default(),
-                service: None,
-                install: install.cloned(),
-            })
-        });
-        if let Some(result @ Err(_)) = build {
-            return iter::once(result).chain(None);
-        }
+/// Attempt to convert a compose [`Service`] into [`quadlet::File`]s, a `.container` file for each
+/// replica and a `.build` file if the service has a `build` section.
+///
+/// See [`services_try_into_quadlet_files()`].
+fn service_and_build_try_into_quadlet_files(
+    name: &Identifier,
+    mut service: Service,
+    context: &ServiceContext,
+    pod: &mut quadlet::Pod,
+) -> impl Iterator<Item = color_eyre::Result<quadlet::File>> {
+    if service.image.is_some() && service.build.is_some() {
+        return vec![Err(eyre!(
+            "error converting service `{name}`: `image` and `build` cannot both be set"
+        ))]
+        .into_iter()
+        .chain(None);
+    }
 
-        let container = service_try_into_quadlet_file(
-            service,
-            name,
-            unit.cloned(),
-            install.cloned(),
-            volume_has_options,
-            pod_name,
-            pod_ports,
-        );
+    let build = service.build.take().map(|build| {
+        let build = Build::try_from(build.into_long()).wrap_err_with(|| {
+            format!("error converting `build` for service `{name}` into a Quadlet `.build` file")
+        })?;
+        let image = format!("{}.build", build.name()).try_into()?;
+        service.image = Some(image);
+        Ok(quadlet::File {
+            name: build.name().to_owned(),
+            unit: context.unit.cloned(),
+            resource: build.into(),
+            globals: Globals::default(),
+            service: None,
+            install: context.install.cloned(),
+        })
+    });
+    if let Some(result @ Err(_)) = build {
+        return vec![result].into_iter().chain(None);
+    }
 
-        iter::once(container).chain(build)
-    })
+    attach::attach_false_into_log_driver(&mut service, name.as_str());
+
+    let replicas = context.replicas.get(name).copied().unwrap_or(1);
+    let mut files = Vec::new();
+    for name in scale::replica_names(name, replicas) {
+        match service_try_into_quadlet_files(service.clone(), name, context, pod) {
+            Ok(replica_files) => files.extend(replica_files.into_iter().map(Ok)),
+            Err(error) => files.push(Err(error)),
+        }
+    }
+
+    files.into_iter().chain(build)
 }
 
-/// Attempt to convert a compose [`Service`] into a [`quadlet::File`].
+/// Attempt to convert a compose [`Service`] into a `.container` [`quadlet::File`].
 ///
-/// `volume_has_options` should be a map from volume [`Identifier`]s to whether the volume has any
-/// options set. It is used to determine whether to link to a [`quadlet::Volume`] in the created
-/// [`quadlet::Container`].
+/// If the `context` has a `pod_name`, and not `no_pod_ports`, and the `service` has any published
+/// ports, they are taken from the created [`quadlet::Container`] and added to the `pod`. DNS
+/// settings and extra hosts are also [lifted](pod_dns::lift()) to the `pod`.
 ///
-/// If `pod_name` is [`Some`] and the `service` has any published ports, they are taken from the
-/// created [`quadlet::Container`] and added to `pod_ports`.
+/// Anonymous volumes with options are converted into named volumes with a
+/// [deterministic name](anonymous_volume::name()), each with a `.volume` [`quadlet::File`] which
+/// is returned after the `.container` file. Otherwise, the volumes would be removed each time the
+/// container is stopped.
 ///
 /// # Errors
 ///
 /// Returns an error if there was an error [adding](Unit::add_dependency()) a service
-/// [`Dependency`](compose_spec::service::Dependency) to the [`Unit`] or converting the [`Service`]
-/// into a [`quadlet::Container`].
-fn service_try_into_quadlet_file(
+/// [`Dependency`](compose_spec::service::Dependency) to the [`Unit`], converting the service's
+/// configs into volumes, or converting the [`Service`] into a [`quadlet::Container`].
+#[allow(clippy::too_many_lines)]
+fn service_try_into_quadlet_files(
     mut service: Service,
     name: Identifier,
-    mut unit: Option<Unit>,
-    install: Option<quadlet::Install>,
-    volume_has_options: &HashMap<Identifier, bool>,
-    pod_name: Option<&str>,
-    pod_ports: &mut Vec<String>,
-) -> color_eyre::Result<quadlet::File> {
+    context: &ServiceContext,
+    pod: &mut quadlet::Pod,
+) -> color_eyre::Result<Vec<quadlet::File>> {
+    let ServiceContext {
+        unit,
+        install,
+        volume_has_options,
+        configs,
+        replicas,
+        pod_name,
+        project_name,
+        no_pod_ports,
+        link_networks,
+        prefix_separator,
+        container_prefix,
+        health_on_failure,
+        healthcheck_defaults,
+        description_from_image,
+        notify,
+        mask,
+        unmask,
+        timezone,
+    } = context;
+    let mut unit = unit.cloned();
+    let name_prefix = pod_name.or(*project_name);
+
     // Add any service dependencies to the [Unit] section of the Quadlet file.
     let dependencies = mem::take(&mut service.depends_on).into_long();
     if !dependencies.is_empty() {
         let unit = unit.get_or_insert_with(Unit::default);
         for (ident, dependency) in dependencies {
-            unit.add_dependency(
-                pod_name.map_or_else(
-                    || ident.to_string(),
-                    |pod_name| format!("{pod_name}-{ident}"),
-                ),
-                dependency,
-            )
-            .wrap_err_with(|| {
-                format!("error adding dependency on `{ident}` to service `{name}`")
-            })?;
+            // Depend on each replica of a scaled service.
+            let dependency_replicas = replicas.get(&ident).copied().unwrap_or(1);
+            for replica in scale::replica_names(&ident, dependency_replicas) {
+                unit.add_dependency(
+                    name_prefix.map_or_else(
+                        || replica.to_string(),
+                        |prefix| format!("{prefix}{prefix_separator}{replica}"),
+                    ),
+                    dependency,
+                )
+                .wrap_err_with(|| {
+                    format!("error adding dependency on `{ident}` to service `{name}`")
+                })?;
+            }
         }
     }
 
+    if pod_name.is_some() {
+        pod_dns::lift(&name, &mut service, pod);
+    }
+
+    let config_volumes =
+        config::service_configs_try_into_volumes(mem::take(&mut service.configs), configs)
+            .wrap_err_with(|| format!("error converting configs for service `{name}`"))?;
+
+    let ServiceExtension {
+        podman_args,
+        health_on_failure: service_health_on_failure,
+        timezone: service_timezone,
+        pod: service_pod,
+        read_only_tmpfs,
+        notify: service_notify,
+        mask: service_mask,
+        unmask: service_unmask,
+        service: service_section,
+    } = ServiceExtension::take_from(&mut service.extensions)
+        .wrap_err_with(|| format!("error reading extension for service `{name}`"))?;
+    ensure!(
+        service_pod.is_none() || pod_name.is_none(),
+        "`{}.pod` of service `{name}` cannot be used with `--pod`",
+        extension::KEY
+    );
+    let chown_paths = chown::take_paths(&mut service.extensions)
+        .wrap_err_with(|| format!("error reading volume options for service `{name}`"))?;
+    let host_gateway = host_gateway::take_from(&mut service.extensions)
+        .wrap_err_with(|| format!("error reading extra hosts of service `{name}`"))?;
+
+    let has_healthcheck = matches!(service.healthcheck, Some(Healthcheck::Command(_)));
+    if service_health_on_failure.is_some() && !has_healthcheck {
+        warning::warn(format_args!(
+            "service `{name}` does not have a `healthcheck`, \
+                ignoring `{}.health_on_failure`",
+            extension::KEY
+        ));
+    }
+    let health_on_failure = service_health_on_failure
+        .or(*health_on_failure)
+        .filter(|_| has_healthcheck);
+    if *healthcheck_defaults {
+        healthcheck::set_docker_defaults(&mut service.healthcheck);
+    }
+
+    let timezone = service_timezone
+        .or_else(|| timezone::from_environment(&service.environment))
+        .or_else(|| timezone.map(str::to_owned));
+
+    gpu::replace_nvidia_runtime(name.as_str(), &mut service);
     let global_args = GlobalArgs::from_compose(&mut service);
 
     let restart = service.restart;
+    if let Some(max_attempts) = service_section
+        .start_limit_burst
+        .or(restart::take_max_attempts(&mut service.deploy))
+    {
+        unit.get_or_insert_with(Unit::default)
+            .set_start_limit_burst(max_attempts);
+    }
+    if let Some(interval) = service_section.start_limit_interval {
+        let interval = compose_spec::duration::parse(&interval).wrap_err_with(|| {
+            format!(
+                "invalid `{}.service.start_limit_interval` of service `{name}`",
+                extension::KEY
+            )
+        })?;
+        unit.get_or_insert_with(Unit::default)
+            .set_start_limit_interval(interval);
+    }
 
     let mut container = Container::try_from(service)
         .map(quadlet::Container::from)
         .wrap_err_with(|| format!("error converting service `{name}` into a Quadlet container"))?;
 
-    // For each named volume, check to see if it has any options set.
-    // If it does, add `.volume` to the source to link this `.container` file to the generated
-    // `.volume` file.
-    for volume in &mut container.volume {
-        if let Some(Source::NamedVolume(source)) = &mut volume.source {
-            let volume_has_options = volume_has_options
-                .get(source.as_str())
-                .copied()
-                .unwrap_or_default();
-            if volume_has_options {
-                source.push_str(".volume");
+    if *description_from_image {
+        unit.get_or_insert_with(Unit::default)
+            .set_default_description(format!("{name} container ({})", container.image));
+    }
+
+    if let Some(prefix) = container_prefix.filter(|_| container.container_name.is_none()) {
+        container.container_name = Some(format!("{prefix}{name}"));
+    }
+    if let Some(health_on_failure) = health_on_failure {
+        container.health_on_failure = Some(health_on_failure.to_string());
+    }
+    container.timezone = timezone;
+    mask::set(
+        &mut container,
+        mask.iter().cloned().chain(service_mask).collect(),
+        unmask.iter().cloned().chain(service_unmask).collect(),
+    )
+    .wrap_err_with(|| format!("error converting service `{name}`"))?;
+    if container.read_only {
+        container.read_only_tmpfs = Some(read_only_tmpfs.unwrap_or(true));
+    } else if read_only_tmpfs.is_some() {
+        warning::warn(format_args!(
+            "service `{name}` is not `read_only`, ignoring `{}.read_only_tmpfs`",
+            extension::KEY
+        ));
+    }
+
+    for hostname in host_gateway {
+        let add_host = format!("{hostname}:{}", host_gateway::HOST_GATEWAY);
+        if pod_name.is_some() {
+            if !pod.add_host.contains(&add_host) {
+                pod.add_host.push(add_host);
             }
+        } else {
+            container.podman_args_push_str(&format!("--add-host {add_host}"));
         }
     }
 
-    let name = if let Some(pod_name) = pod_name {
+    // Extra Podman args from the `x-podlet` extension are added last so they can override
+    // generated args.
+    for arg in podman_args {
+        container.podman_args_push_str(&arg);
+    }
+
+    chown::set_volume_options(&mut container.volume, &chown_paths);
+    container.volume.extend(config_volumes);
+
+    if !link_networks {
+        unlink_networks(&mut container);
+    }
+    let mut anonymous_volumes = link_volumes(&mut container, &name, volume_has_options);
+    if let Some(project_name) = project_name {
+        project::prefix_references(&mut container, project_name, *prefix_separator);
+        for volume in &mut anonymous_volumes {
+            *volume = project::prefix(project_name, *prefix_separator, volume);
+        }
+    }
+
+    let mut service = restart.map(super::service::Service::from);
+    if let Some(stop_timeout) = container.stop_timeout {
+        service
+            .get_or_insert_with(super::service::Service::default)
+            .set_stop_timeout(stop_timeout);
+    }
+    if service_notify.unwrap_or(*notify) {
+        container.notify = quadlet::container::Notify::Container;
+    }
+    if !service_section.environment_file.is_empty() {
+        service
+            .get_or_insert_with(super::service::Service::default)
+            .extend_environment_files(service_section.environment_file);
+    }
+
+    if let Some(pod_name) = pod_name {
         container.pod = Some(format!("{pod_name}.pod"));
-        pod_ports.extend(mem::take(&mut container.publish_port));
-        format!("{pod_name}-{name}")
+        if !no_pod_ports {
+            pod.publish_port
+                .extend(mem::take(&mut container.publish_port));
+        }
+    } else {
+        container.pod = service_pod.map(|pod| {
+            if Path::new(&pod)
+                .extension()
+                .is_some_and(|extension| extension == "pod")
+            {
+                pod
+            } else {
+                format!("{pod}.pod")
+            }
+        });
+    }
+
+    let name = if let Some(prefix) = name_prefix {
+        format!("{prefix}{prefix_separator}{name}")
     } else {
         name.into()
     };
 
-    Ok(quadlet::File {
+    let volumes = anonymous_volumes.into_iter().map(|name| quadlet::File {
+        name,
+        unit: context.unit.cloned(),
+        resource: quadlet::Volume::default().into(),
+        globals: Globals::default(),
+        service: None,
+        install: install.cloned(),
+    });
+
+    let container = quadlet::File {
         name,
         unit,
         resource: container.into(),
         globals: global_args.into(),
-        service: restart.map(Into::into),
-        install,
-    })
+        service,
+        install: install.cloned(),
+    };
+
+    Ok(iter::once(container).chain(volumes).collect())
+}
+
+/// Link the volumes of a [`quadlet::Container`] created from the service `name` to their `.volume`
+/// Quadlet files.
+///
+/// For each named volume, check to see if it has any options set. If it does, add `.volume` to the
+/// source to link the `.container` file to the generated `.volume` file.
+///
+/// Anonymous volumes with options are given a [deterministic name](anonymous_volume::name()) and
+/// linked to a `.volume` file. Returns the names of the anonymous volumes which need a `.volume`
+/// file.
+fn link_volumes(
+    container: &mut quadlet::Container,
+    name: &Identifier,
+    volume_has_options: &HashMap<Identifier, bool>,
+) -> Vec<String> {
+    let mut anonymous_volumes = Vec::new();
+    for volume in &mut container.volume {
+        match &mut volume.source {
+            Some(Source::NamedVolume(source)) => {
+                let volume_has_options = volume_has_options
+                    .get(source.as_str())
+                    .copied()
+                    .unwrap_or_default();
+                if volume_has_options {
+                    source.push_str(".volume");
+                }
+            }
+            None if !volume.options.is_empty() => {
+                let volume_name = anonymous_volume::name(name.as_str(), &volume.container_path);
+                volume.source = Some(Source::NamedVolume(format!("{volume_name}.volume")));
+                anonymous_volumes.push(volume_name);
+            }
+            _ => {}
+        }
+    }
+    anonymous_volumes
+}
+
+/// Reference the networks of a [`quadlet::Container`] by name instead of by their `.network`
+/// Quadlet file, for `podlet compose --no-link-networks`.
+fn unlink_networks(container: &mut quadlet::Container) {
+    for network in &mut container.network {
+        let (name, options) = network
+            .split_once(':')
+            .map_or((network.as_str(), None), |(name, options)| {
+                (name, Some(options))
+            });
+        if let Some(name) = name.strip_suffix(".network") {
+            *network =
+                options.map_or_else(|| name.to_owned(), |options| format!("{name}:{options}"));
+        }
+    }
+}
+
+/// Remove all [`Networks`], as `.network` Quadlet files are not generated with
+/// `podlet compose --no-link-networks`, warning for each network with options that are ignored.
+fn unlinked_networks(networks: Networks) -> Networks {
+    for (name, network) in networks {
+        if network
+            .as_ref()
+            .and_then(Resource::as_compose)
+            .is_some_and(|network| !network.is_empty())
+        {
+            warning::warn(format_args!(
+                "network `{name}` is referenced by name with `--no-link-networks`, \
+                    ignoring its options"
+            ));
+        }
+    }
+    Networks::default()
 }
 
 /// Attempt to convert compose [`Networks`] into an [`Iterator`] of [`quadlet::File`]s.
@@ -457,27 +1892,41 @@ fn networks_try_into_quadlet_files<'a>(
     networks: Networks,
     unit: Option<&'a Unit>,
     install: Option<&'a quadlet::Install>,
-) -> impl Iterator<Item = color_eyre::Result<quadlet::File>> + 'a {
+) -> impl Iterator<Item = (String, color_eyre::Result<quadlet::File>)> + 'a {
     networks.into_iter().map(move |(name, network)| {
-        let network = match network {
-            Some(Resource::Compose(network)) => network,
-            None => Network::default(),
-            Some(Resource::External { .. }) => {
-                bail!("external networks (`{name}`) are not supported");
-            }
-        };
-        let network = quadlet::Network::try_from(network).wrap_err_with(|| {
-            format!("error converting network `{name}` into a Quadlet network")
-        })?;
+        (
+            format!("network `{name}`"),
+            network_try_into_quadlet_file(name, network, unit, install),
+        )
+    })
+}
 
-        Ok(quadlet::File {
-            name: name.into(),
-            unit: unit.cloned(),
-            resource: network.into(),
-            globals: Globals::default(),
-            service: None,
-            install: install.cloned(),
-        })
+/// Attempt to convert a compose [`Network`] into a [`quadlet::File`].
+///
+/// See [`networks_try_into_quadlet_files()`].
+fn network_try_into_quadlet_file(
+    name: Identifier,
+    network: Option<Resource<Network>>,
+    unit: Option<&Unit>,
+    install: Option<&quadlet::Install>,
+) -> color_eyre::Result<quadlet::File> {
+    let network = match network {
+        Some(Resource::Compose(network)) => network,
+        None => Network::default(),
+        Some(Resource::External { .. }) => {
+            bail!("external networks (`{name}`) are not supported");
+        }
+    };
+    let network = quadlet::Network::try_from(network)
+        .wrap_err_with(|| format!("error converting network `{name}` into a Quadlet network"))?;
+
+    Ok(quadlet::File {
+        name: name.into(),
+        unit: unit.cloned(),
+        resource: network.into(),
+        globals: Globals::default(),
+        service: None,
+        install: install.cloned(),
     })
 }
 
@@ -494,9 +1943,10 @@ fn volumes_try_into_quadlet_files<'a>(
     volumes: Volumes,
     unit: Option<&'a Unit>,
     install: Option<&'a quadlet::Install>,
-) -> impl Iterator<Item = color_eyre::Result<quadlet::File>> + 'a {
+) -> impl Iterator<Item = (String, color_eyre::Result<quadlet::File>)> + 'a {
     volumes.into_iter().filter_map(move |(name, volume)| {
-        volume.and_then(|volume| match volume {
+        let part = format!("volume `{name}`");
+        let result = volume.and_then(|volume| match volume {
             Resource::Compose(volume) => (!volume.is_empty()).then(|| {
                 quadlet::Volume::try_from(volume)
                     .wrap_err_with(|| {
@@ -514,6 +1964,1240 @@ fn volumes_try_into_quadlet_files<'a>(
             Resource::External { .. } => {
                 Some(Err(eyre!("external volumes (`{name}`) are not supported")))
             }
-        })
+        });
+        result.map(|result| (part, result))
     })
 }
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    /// Convert a compose file, given as YAML, into [`File`]s using the given `args`.
+    ///
+    /// The YAML is read like a compose file from stdin.
+    pub(in crate::cli) fn convert(args: Compose, yaml: &str) -> color_eyre::Result<Vec<File>> {
+        convert_with_unit(args, yaml, None)
+    }
+
+    /// Convert a compose file, given as YAML, into [`File`]s using the given `args` and `unit`.
+    pub(in crate::cli) fn convert_with_unit(
+        args: Compose,
+        yaml: &str,
+        unit: Option<Unit>,
+    ) -> color_eyre::Result<Vec<File>> {
+        let mut options = compose_spec::Compose::options();
+        options.apply_merge(true);
+        let compose = merge::merge_documents(yaml)
+            .and_then(|value| from_yaml_value(&options, value, None))?;
+        args.compose_try_into_files(compose, unit, None)
+    }
+
+    /// Convert a compose file, given as YAML, into [`File`]s and join their contents.
+    pub(super) fn convert_to_string(args: Compose, yaml: &str) -> String {
+        convert(args, yaml)
+            .expect("compose file converts")
+            .iter()
+            .map(ToString::to_string)
+            .collect()
+    }
+
+    /// Parse the arguments to `podlet compose`.
+    pub(super) fn parse_args(args: &[&str]) -> clap::error::Result<Compose> {
+        use clap::Parser;
+
+        let cli = super::super::Cli::try_parse_from(["podlet", "compose"].iter().chain(args))?;
+        let super::super::Commands::Compose(compose) = cli.command else {
+            return Err(clap::Error::new(clap::error::ErrorKind::InvalidSubcommand));
+        };
+        Ok(*compose)
+    }
+
+    #[test]
+    fn format_kube_matches_kube() {
+        let yaml = "
+            name: test
+            services:
+              test:
+                image: image
+                ports:
+                  - 8080:80
+        ";
+
+        let format = parse_args(&["--format", "kube"]).expect("args are valid");
+        let kube = parse_args(&["--kube"]).expect("args are valid");
+        assert_eq!(
+            convert_to_string(format, yaml),
+            convert_to_string(kube, yaml)
+        );
+    }
+
+    #[test]
+    fn format_conflicts_with_pod() {
+        assert!(parse_args(&["--pod", "--kube"]).is_err());
+        assert!(parse_args(&["--format", "quadlet", "--kube"]).is_err());
+
+        let yaml = "
+            name: test
+            services:
+              test:
+                image: image
+        ";
+        let args = parse_args(&["--pod", "--format", "kube"]).expect("args are valid");
+        assert!(convert(args, yaml).is_err());
+    }
+
+    #[test]
+    fn format_compose() {
+        let yaml = "
+            name: test
+            services:
+              test:
+                image: image
+        ";
+
+        let args = parse_args(&["--format", "compose"]).expect("args are valid");
+        let files = convert(args, yaml).expect("compose file converts");
+        assert_eq!(files.len(), 1);
+        let file = files.first().expect("one file");
+        assert_eq!(file.name(), "test-compose");
+        assert!(file.to_string().contains("image: image"), "{file}");
+    }
+
+    #[test]
+    fn kube_config_map() {
+        let yaml = "
+            name: test
+            services:
+              app:
+                image: image
+                configs:
+                  - source: config
+                    target: /etc/app.conf
+            configs:
+              config:
+                content: key=value
+        ";
+
+        let files = convert(
+            Compose {
+                kube: true,
+                ..Compose::default()
+            },
+            yaml,
+        )
+        .expect("compose file converts");
+        let names: Vec<_> = files.iter().map(File::name).collect();
+        assert_eq!(names, ["test", "test-kube", "test-configmap"]);
+        let [kube, pod, config_map] = files
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .try_into()
+            .expect("three files");
+
+        assert!(kube.contains("ConfigMap=test-configmap.yaml\n"), "{kube}");
+
+        assert!(pod.contains("mountPath: /etc/app.conf"), "{pod}");
+        assert!(pod.contains("subPath: config"), "{pod}");
+
+        assert!(config_map.contains("kind: ConfigMap"), "{config_map}");
+        assert!(config_map.contains("config: key=value"), "{config_map}");
+    }
+
+    #[test]
+    fn kube_file_sources() {
+        let secret = "
+                      - source: source
+                        x-podlet:
+                          type: env";
+        for (kind, resource, service) in [
+            ("config", "configs", " [source]"),
+            ("secret", "secrets", secret),
+        ] {
+            let yaml = format!(
+                "
+                name: test
+                services:
+                  app:
+                    image: image
+                    {resource}:{service}
+                {resource}:
+                  source:
+                    file: ./source.txt
+                "
+            );
+
+            let mut args = parse_args(&["--kube"]).expect("args are valid");
+            args.compose_file = Some("/podlet-test/app/compose.yaml".into());
+            let error = convert(args, &yaml).expect_err("file does not exist");
+            assert!(
+                format!("{error:?}").contains(&format!(
+                    "error reading {kind} file `/podlet-test/app/./source.txt`"
+                )),
+                "{error:?}"
+            );
+
+            let args = parse_args(&["--kube"]).expect("args are valid");
+            let error = convert(args, &yaml).expect_err("relative path from stdin is an error");
+            assert!(
+                format!("{error:?}")
+                    .contains("not supported when reading the compose file from stdin"),
+                "{error:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn kube_split() {
+        let yaml = "
+            name: test
+            services:
+              app:
+                image: image
+                volumes:
+                  - data:/data
+                  - cache:/cache
+            volumes:
+              data:
+                labels:
+                  volume: data
+              cache:
+                labels:
+                  volume: cache
+        ";
+
+        warning::take();
+        let args = parse_args(&["--kube", "--kube-split"]).expect("args are valid");
+        let files = convert(args, yaml).expect("compose file converts");
+        let names: Vec<_> = files
+            .iter()
+            .map(|file| format!("{}.{}", file.name(), file.extension()))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "test.kube",
+                "test-kube.yaml",
+                "test-pvc-data.yaml",
+                "test-pvc-cache.yaml"
+            ]
+        );
+        let [_, pod, data, cache] = files
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .try_into()
+            .expect("four files");
+
+        assert!(pod.starts_with("apiVersion: v1\nkind: Pod\n"), "{pod}");
+        assert!(!pod.contains("---"), "{pod}");
+        for (name, claim) in [("data", data), ("cache", cache)] {
+            assert!(claim.contains("kind: PersistentVolumeClaim\n"), "{claim}");
+            assert!(claim.contains(&format!("name: {name}\n")), "{claim}");
+            assert!(!claim.contains("---"), "{claim}");
+        }
+        assert_eq!(warning::take().len(), 1);
+
+        let args = parse_args(&["--kube-split"]).expect("args are valid");
+        assert!(convert(args, yaml).is_err(), "requires `--kube`");
+    }
+
+    #[test]
+    fn kube_config_mode() {
+        let yaml = "
+            name: test
+            services:
+              app:
+                image: image
+                configs:
+                  - source: config
+                    mode: 0400
+                    uid: '1000'
+            configs:
+              config:
+                content: key=value
+        ";
+
+        let args = parse_args(&["--kube"]).expect("args are valid");
+        let files = convert(args, yaml).expect("compose file converts");
+        let pod = files.get(1).expect("pod file").to_string();
+        assert!(pod.contains("mode: 256\n"), "{pod}");
+
+        let [warning]: [String; 1] = warning::take().try_into().expect("a warning for `uid`");
+        assert!(warning.contains("`uid: 1000`"), "{warning}");
+    }
+
+    #[test]
+    fn expose() {
+        let yaml = "
+            name: test
+            services:
+              app:
+                image: image
+                expose:
+                  - 9000
+                  - 9001/udp
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(
+            quadlet.contains("ExposeHostPort=9000\nExposeHostPort=9001/udp\n"),
+            "{quadlet}"
+        );
+
+        let kube = convert_to_string(
+            Compose {
+                kube: true,
+                ..Compose::default()
+            },
+            yaml,
+        );
+        assert!(
+            kube.contains("ports:\n    - containerPort: 9000\n    - containerPort: 9001\n      protocol: UDP\n"),
+            "{kube}"
+        );
+    }
+
+    #[test]
+    fn cgroup() {
+        for cgroup in ["host", "private"] {
+            let yaml = format!(
+                "
+                services:
+                  app:
+                    image: image
+                    cgroup: {cgroup}
+                "
+            );
+
+            let quadlet = convert_to_string(Compose::default(), &yaml);
+            assert!(
+                quadlet.contains(&format!("PodmanArgs=--cgroupns {cgroup}\n")),
+                "{quadlet}"
+            );
+        }
+
+        let invalid = compose_spec::Compose::options().from_yaml_str(
+            "
+            services:
+              app:
+                image: image
+                cgroup: other
+            ",
+        );
+        assert!(invalid.is_err(), "only `host` and `private` are valid");
+    }
+
+    #[test]
+    fn dump_resolved() {
+        let yaml = "
+            services:
+              web:
+                <<: &defaults
+                  restart: always
+                  environment:
+                    LOG_LEVEL: info
+                image: docker.io/library/nginx
+              api:
+                <<: *defaults
+                image: docker.io/library/alpine
+                environment:
+                  LOG_LEVEL: debug
+        ";
+        let mut options = compose_spec::Compose::options();
+        options.apply_merge(true);
+        let compose = merge::merge_documents(yaml)
+            .and_then(|value| from_yaml_value(&options, value, None))
+            .expect("merge keys are resolved when reading");
+
+        let dump = resolved_document(&compose).expect("compose file serializes");
+        let dump: serde_yaml::Value = serde_yaml::from_str(&dump).expect("dump is valid YAML");
+        let service = |name: &str, key: &str| {
+            dump.get("services")
+                .and_then(|services| services.get(name))
+                .and_then(|service| service.get(key))
+                .cloned()
+        };
+        for name in ["web", "api"] {
+            assert_eq!(
+                service(name, "restart"),
+                Some("always".into()),
+                "{name}: {dump:?}"
+            );
+        }
+        let log_level = |name: &str| {
+            service(name, "environment")
+                .and_then(|environment| environment.get("LOG_LEVEL").cloned())
+        };
+        assert_eq!(log_level("web"), Some("info".into()), "{dump:?}");
+        assert_eq!(log_level("api"), Some("debug".into()), "{dump:?}");
+
+        let args = parse_args(&["--dump-resolved"]).expect("args are valid");
+        args.compose_try_into_files(compose, None, None)
+            .expect("conversion continues after dumping");
+    }
+
+    #[test]
+    fn no_pod_ports() {
+        let yaml = "
+            name: test
+            services:
+              app:
+                image: image
+                ports:
+                  - 8080:80
+        ";
+
+        let args = parse_args(&["--pod", "--no-pod-ports"]).expect("args are valid");
+        let files = convert(args, yaml).expect("compose file converts");
+        let [container, pod]: [File; 2] = files.try_into().expect("container and pod files");
+
+        let container = container.to_string();
+        assert!(container.contains("PublishPort=8080:80\n"), "{container}");
+        let pod = pod.to_string();
+        assert!(!pod.contains("PublishPort="), "{pod}");
+
+        assert!(
+            parse_args(&["--no-pod-ports"]).is_err(),
+            "`--no-pod-ports` requires `--pod`"
+        );
+    }
+
+    #[test]
+    fn prefix_separator() {
+        let yaml = "
+            name: test
+            services:
+              app:
+                image: image
+                depends_on:
+                  - db
+              db:
+                image: image
+        ";
+
+        let args = parse_args(&["--pod", "--prefix-separator", "_"]).expect("args are valid");
+        let files = convert(args, yaml).expect("compose file converts");
+        let names: Vec<&str> = files.iter().map(File::name).collect();
+        assert_eq!(names, ["test_app", "test_db", "test"]);
+        let quadlet: String = files.iter().map(ToString::to_string).collect();
+        assert!(quadlet.contains("Requires=test_db.service\n"), "{quadlet}");
+
+        assert!(
+            parse_args(&["--pod", "--prefix-separator", "/"]).is_err(),
+            "`/` is not a valid separator"
+        );
+        assert!(
+            parse_args(&["--prefix-separator", "_"]).is_err(),
+            "`--prefix-separator` requires `--pod`"
+        );
+    }
+
+    #[test]
+    fn pod_hostnames() {
+        let yaml = "
+            name: pod
+            services:
+              frontend:
+                image: frontend
+                hostname: web.example.test
+              backend:
+                image: backend
+                hostname: api.example.test
+        ";
+        let args = parse_args(&["--pod"]).expect("args are valid");
+        let files = convert(args, yaml).expect("compose file converts");
+        let [frontend, backend, pod] = files
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .try_into()
+            .expect("three files");
+
+        assert!(
+            frontend.contains("HostName=web.example.test\n"),
+            "{frontend}"
+        );
+        assert!(backend.contains("HostName=api.example.test\n"), "{backend}");
+        assert!(!pod.contains("HostName="), "{pod}");
+    }
+
+    #[test]
+    fn pod_healthchecks() {
+        let yaml = "
+            name: test
+            services:
+              web:
+                image: web
+                healthcheck:
+                  test: [CMD, curl, -f, http://localhost]
+              db:
+                image: db
+                healthcheck:
+                  test: pg_isready
+                  interval: 5s
+        ";
+
+        let args =
+            parse_args(&["--pod", "--health-on-failure", "restart"]).expect("args are valid");
+        let files = convert(args, yaml).expect("compose file converts");
+        let names: Vec<&str> = files.iter().map(File::name).collect();
+        assert_eq!(names, ["test-web", "test-db", "test"]);
+        let [web, db, pod]: [File; 3] = files.try_into().expect("two containers and a pod");
+
+        let web = web.to_string();
+        assert!(
+            web.contains("HealthCmd=[\"curl\",\"-f\",\"http://localhost\"]\n"),
+            "{web}"
+        );
+        assert!(web.contains("HealthOnFailure=restart\n"), "{web}");
+
+        let db = db.to_string();
+        assert!(
+            db.contains("HealthCmd=[\"/bin/sh\",\"-c\",\"pg_isready\"]\nHealthInterval=5s\n"),
+            "{db}"
+        );
+        assert!(db.contains("HealthOnFailure=restart\n"), "{db}");
+
+        let pod = pod.to_string();
+        assert!(!pod.contains("Health"), "{pod}");
+    }
+
+    #[test]
+    fn healthcheck_start_interval() {
+        let yaml = "
+            name: test
+            services:
+              app:
+                image: image
+                healthcheck:
+                  test: [CMD, healthcheck]
+                  interval: 30s
+                  start_period: 1m
+                  start_interval: 5s
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(
+            quadlet.contains("HealthStartupCmd=[\"healthcheck\"]\n"),
+            "{quadlet}"
+        );
+        assert!(quadlet.contains("HealthStartupInterval=5s\n"), "{quadlet}");
+
+        let kube = convert_to_string(
+            Compose {
+                kube: true,
+                ..Compose::default()
+            },
+            yaml,
+        );
+        let (_, startup_probe) = kube
+            .split_once("startupProbe:")
+            .expect("kube has a startup probe");
+        assert!(startup_probe.contains("failureThreshold: 12\n"), "{kube}");
+        assert!(startup_probe.contains("periodSeconds: 5\n"), "{kube}");
+    }
+
+    #[test]
+    fn kube_startup_probe() {
+        let yaml = "
+            name: test
+            services:
+              app:
+                image: image
+                healthcheck:
+                  test: [CMD, healthcheck]
+                  interval: 10s
+                  start_period: 60s
+        ";
+
+        let kube = convert_to_string(
+            Compose {
+                kube: true,
+                ..Compose::default()
+            },
+            yaml,
+        );
+        let (liveness_probe, startup_probe) = kube
+            .split_once("startupProbe:")
+            .expect("kube has a startup probe");
+        assert!(startup_probe.contains("failureThreshold: 6\n"), "{kube}");
+        assert!(startup_probe.contains("periodSeconds: 10\n"), "{kube}");
+        assert!(!liveness_probe.contains("initialDelaySeconds"), "{kube}");
+    }
+
+    #[test]
+    fn network_priority() {
+        let yaml = "
+            name: priority
+            services:
+              app:
+                image: app
+                networks:
+                  unset:
+                  backend:
+                    priority: 100
+                    aliases:
+                      - api
+                  zero:
+                    priority: 0
+                  frontend:
+                    priority: 1000
+            networks:
+              backend:
+              frontend:
+              zero:
+              unset:
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(
+            quadlet.contains(
+                "Network=frontend.network\n\
+                    Network=backend.network:alias=api\n\
+                    Network=unset.network\n\
+                    Network=zero.network\n"
+            ),
+            "{quadlet}"
+        );
+
+        let negative = "
+            services:
+              app:
+                image: app
+                networks:
+                  backend:
+                    priority: -1
+            networks:
+              backend:
+        ";
+        assert!(
+            convert(Compose::default(), negative).is_err(),
+            "`priority` must be a non-negative integer"
+        );
+    }
+
+    #[test]
+    fn read_only_volumes() {
+        let yaml = "
+            name: app
+            services:
+              web:
+                image: docker.io/library/nginx
+                volumes:
+                  - type: volume
+                    source: content
+                    target: /usr/share/nginx/html
+                    read_only: true
+                  - ./nginx.conf:/etc/nginx/nginx.conf:ro
+                  - cache:/var/cache/nginx:ro
+            volumes:
+              content:
+                labels:
+                  backup: \"true\"
+              cache:
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        for volume in [
+            "Volume=content.volume:/usr/share/nginx/html:ro\n",
+            "Volume=./nginx.conf:/etc/nginx/nginx.conf:ro\n",
+            "Volume=cache:/var/cache/nginx:ro\n",
+        ] {
+            assert!(quadlet.contains(volume), "{volume}: {quadlet}");
+        }
+
+        let args = parse_args(&["--kube"]).expect("args are valid");
+        let files = convert(args, yaml).expect("compose file converts");
+        let kube = files
+            .iter()
+            .find(|file| matches!(file, File::Kubernetes(_)))
+            .expect("a Kubernetes YAML file")
+            .to_string();
+        assert_eq!(kube.matches("readOnly: true").count(), 3, "{kube}");
+    }
+
+    #[test]
+    fn kube_read_only_tmpfs() {
+        let yaml = "
+            name: test
+            services:
+              app:
+                image: image
+                read_only: true
+                tmpfs: /tmp
+        ";
+
+        let kube = convert_to_string(
+            Compose {
+                kube: true,
+                ..Compose::default()
+            },
+            yaml,
+        );
+        assert!(kube.contains("readOnlyRootFilesystem: true"), "{kube}");
+        assert!(
+            kube.contains(
+                "    volumeMounts:
+    - mountPath: /tmp
+      name: app-tmp
+  volumes:
+  - emptyDir:
+      medium: Memory
+    name: app-tmp
+"
+            ),
+            "{kube}"
+        );
+    }
+
+    #[test]
+    fn health_on_failure() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                restart: always
+                healthcheck:
+                  test: [CMD, healthcheck]
+              override:
+                image: image
+                healthcheck:
+                  test: [CMD, healthcheck]
+                x-podlet:
+                  health_on_failure: kill
+              no-healthcheck:
+                image: image
+        ";
+
+        let args = parse_args(&["--health-on-failure", "restart"]).expect("args are valid");
+        let files = convert(args, yaml).expect("compose file converts");
+        let [app, override_, no_healthcheck] = files
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .try_into()
+            .expect("three files");
+
+        assert!(app.contains("HealthOnFailure=restart\n"), "{app}");
+        assert!(app.contains("Restart=always\n"), "{app}");
+        assert!(override_.contains("HealthOnFailure=kill\n"), "{override_}");
+        assert!(
+            !no_healthcheck.contains("HealthOnFailure"),
+            "{no_healthcheck}"
+        );
+        assert!(warning::take().is_empty());
+    }
+
+    #[test]
+    fn healthcheck_defaults() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                healthcheck:
+                  test: [CMD, healthcheck]
+                  retries: 5
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(!quadlet.contains("HealthInterval="), "{quadlet}");
+        assert!(!quadlet.contains("HealthTimeout="), "{quadlet}");
+        assert!(quadlet.contains("HealthRetries=5\n"), "{quadlet}");
+
+        let args = parse_args(&["--healthcheck-defaults"]).expect("args are valid");
+        let quadlet = convert_to_string(args, yaml);
+        assert!(quadlet.contains("HealthInterval=30s\n"), "{quadlet}");
+        assert!(quadlet.contains("HealthTimeout=30s\n"), "{quadlet}");
+        assert!(quadlet.contains("HealthRetries=5\n"), "{quadlet}");
+
+        let args = parse_args(&["--healthcheck-defaults", "--kube"]).expect("args are valid");
+        assert!(convert(args, yaml).is_err());
+    }
+
+    #[test]
+    fn unit_description_from_image() {
+        let yaml = "
+            services:
+              nginx:
+                image: nginx:latest
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(!quadlet.contains("Description="), "{quadlet}");
+
+        let args = parse_args(&["--add-unit-description-from-image"]).expect("args are valid");
+        let quadlet = convert_to_string(args, yaml);
+        assert!(
+            quadlet.contains("[Unit]\nDescription=nginx container (nginx:latest)\n"),
+            "{quadlet}"
+        );
+    }
+
+    #[test]
+    fn prefer_newer() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                pull_policy: always
+              local:
+                image: local
+                pull_policy: never
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(quadlet.contains("Pull=always\n"), "{quadlet}");
+
+        let args = parse_args(&["--prefer-newer"]).expect("args are valid");
+        let quadlet = convert_to_string(args, yaml);
+        assert!(quadlet.contains("Pull=newer\n"), "{quadlet}");
+        assert!(quadlet.contains("Pull=never\n"), "{quadlet}");
+        assert!(!quadlet.contains("Pull=always"), "{quadlet}");
+    }
+
+    #[test]
+    fn notify() {
+        let yaml = "
+            services:
+              app:
+                image: image
+              opt-out:
+                image: image
+                x-podlet:
+                  notify: false
+        ";
+
+        let args = parse_args(&["--notify"]).expect("args are valid");
+        let files = convert(args, yaml).expect("compose file converts");
+        let [app, opt_out] = files
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .try_into()
+            .expect("two files");
+
+        assert!(
+            app.contains("[Container]\nImage=image\nNotify=true\n"),
+            "{app}"
+        );
+        assert!(!app.contains("[Service]"), "{app}");
+        assert!(!opt_out.contains("Notify"), "{opt_out}");
+        assert!(!opt_out.contains("[Service]"), "{opt_out}");
+    }
+
+    #[test]
+    fn health_on_failure_without_healthcheck() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                x-podlet:
+                  health_on_failure: stop
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(!quadlet.contains("HealthOnFailure"), "{quadlet}");
+        assert_eq!(warning::take().len(), 1);
+    }
+
+    #[test]
+    fn stop_grace_period() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                stop_grace_period: 1m
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(quadlet.contains("StopTimeout=60\n"), "{quadlet}");
+        assert!(
+            quadlet.contains(&format!(
+                "[Service]\nTimeoutStopSec={}\n",
+                60 + super::super::service::TIMEOUT_STOP_MARGIN
+            )),
+            "{quadlet}"
+        );
+    }
+
+    #[test]
+    fn kube_pod_options() {
+        let yaml = "
+            name: test
+            services:
+              app:
+                image: image
+        ";
+
+        let args = parse_args(&[
+            "--kube",
+            "--service-account",
+            "account",
+            "--automount-service-account-token=false",
+            "--fs-group",
+            "1000",
+        ])
+        .expect("args are valid");
+        let kube = convert_to_string(args, yaml);
+        assert!(
+            kube.contains(
+                "spec:
+  automountServiceAccountToken: false
+  containers:
+  - image: image
+    name: app
+  securityContext:
+    fsGroup: 1000
+  serviceAccountName: account
+"
+            ),
+            "{kube}"
+        );
+
+        let args = parse_args(&["--service-account", "account"]).expect("args are valid");
+        assert!(convert(args, yaml).is_err());
+    }
+
+    #[test]
+    fn kube_run_as_non_root() {
+        let args = parse_args(&["--kube", "--run-as-non-root"]).expect("args are valid");
+        let yaml = "
+            name: test
+            services:
+              app:
+                image: image
+                user: '1000'
+        ";
+        let kube = convert_to_string(args.clone(), yaml);
+        assert!(kube.contains("runAsNonRoot: true"), "{kube}");
+
+        let yaml = "
+            name: test
+            services:
+              app:
+                image: image
+                user: '0'
+        ";
+        assert!(convert(args, yaml).is_err());
+    }
+
+    #[test]
+    fn healthcheck_test_markers() {
+        let yaml = "
+            name: test
+            services:
+              exec:
+                image: image
+                healthcheck:
+                  test: [CMD, curl, -f, http://localhost]
+              shell:
+                image: image
+                healthcheck:
+                  test: [CMD-SHELL, curl -f http://localhost || exit 1]
+              none:
+                image: image
+                healthcheck:
+                  test: [NONE]
+        ";
+
+        let [exec, shell, none] = convert(Compose::default(), yaml)
+            .expect("compose file converts")
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .try_into()
+            .expect("three files");
+        assert!(
+            exec.contains("HealthCmd=[\"curl\",\"-f\",\"http://localhost\"]\n"),
+            "{exec}"
+        );
+        assert!(
+            shell.contains(
+                "HealthCmd=[\"/bin/sh\",\"-c\",\"curl -f http://localhost || exit 1\"]\n"
+            ),
+            "{shell}"
+        );
+        assert!(none.contains("HealthCmd=none\n"), "{none}");
+
+        let kube = convert_to_string(
+            Compose {
+                kube: true,
+                ..Compose::default()
+            },
+            yaml,
+        );
+        assert!(!kube.contains("CMD"), "{kube}");
+        assert!(
+            kube.contains(
+                "- /bin/sh\n        - -c\n        - curl -f http://localhost || exit 1\n"
+            ),
+            "{kube}"
+        );
+        assert_eq!(kube.matches("livenessProbe").count(), 2, "{kube}");
+    }
+
+    #[test]
+    fn container_prefix() {
+        let yaml = "
+            name: test
+            services:
+              web:
+                image: web
+              db:
+                image: db
+                container_name: database
+        ";
+
+        let args = parse_args(&[
+            "--pod",
+            "--container-prefix",
+            "{project}-",
+            "--pod-suffix",
+            "-pod",
+        ])
+        .expect("args are valid");
+        let [web, db, pod]: [String; 3] = convert(args, yaml)
+            .expect("compose file converts")
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .try_into()
+            .expect("two containers and a pod");
+        assert!(web.contains("ContainerName=test-web\n"), "{web}");
+        assert!(db.contains("ContainerName=database\n"), "{db}");
+        assert!(pod.contains("PodName=test-pod\n"), "{pod}");
+
+        let args = parse_args(&["--container-prefix", "app-"]).expect("args are valid");
+        let web = convert_to_string(args, yaml);
+        assert!(web.contains("ContainerName=app-web\n"), "{web}");
+
+        let args = parse_args(&["--container-prefix", "{project}-"]).expect("args are valid");
+        assert!(convert(args, "services: {web: {image: web}}").is_err());
+        assert!(parse_args(&["--pod-suffix", "-pod"]).is_err());
+    }
+
+    #[test]
+    fn pod_options() {
+        let yaml = "
+            name: test
+            services:
+              app:
+                image: app
+        ";
+
+        let args = parse_args(&[
+            "--pod",
+            "--pod-name",
+            "app-pod",
+            "--pod-dns",
+            "1.1.1.1",
+            "--pod-infra-image",
+            "registry.k8s.io/pause:3.9",
+            "--pod-infra-name",
+            "app-infra",
+        ])
+        .expect("args are valid");
+        let pod = convert(args, yaml)
+            .expect("compose file converts")
+            .last()
+            .expect("pod file")
+            .to_string();
+        assert_eq!(
+            pod,
+            "[Pod]\n\
+                DNS=1.1.1.1\n\
+                PodmanArgs=--infra-image registry.k8s.io/pause:3.9 --infra-name app-infra\n\
+                PodName=app-pod\n"
+        );
+
+        assert!(parse_args(&["--pod-infra-image", "pause"]).is_err());
+        assert!(parse_args(&["--pod", "--pod-name", "app", "--pod-suffix", "-pod"]).is_err());
+    }
+
+    #[test]
+    fn healthcheck_shell_pipe() {
+        let yaml = "
+            name: test
+            services:
+              app:
+                image: image
+                healthcheck:
+                  test: curl -f localhost | grep ok
+        ";
+
+        let app = convert_to_string(Compose::default(), yaml);
+        assert!(
+            app.contains("HealthCmd=[\"/bin/sh\",\"-c\",\"curl -f localhost | grep ok\"]\n"),
+            "{app}"
+        );
+
+        let kube = convert_to_string(
+            Compose {
+                kube: true,
+                ..Compose::default()
+            },
+            yaml,
+        );
+        assert!(
+            kube.contains("- /bin/sh\n        - -c\n        - curl -f localhost | grep ok\n"),
+            "{kube}"
+        );
+    }
+
+    #[test]
+    fn depends_on_short_and_long() {
+        let yaml = "
+            services:
+              web:
+                image: web
+                depends_on: [db, cache]
+              worker:
+                image: worker
+                depends_on:
+                  db:
+                    condition: service_started
+                    required: false
+                  cache:
+                    condition: service_started
+                    restart: true
+              db:
+                image: db
+              cache:
+                image: cache
+        ";
+
+        let files = convert(Compose::default(), yaml).expect("compose file converts");
+        let [web, worker, db, cache] = files
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .try_into()
+            .expect("four files");
+
+        assert!(
+            web.contains(
+                "[Unit]\n\
+                Requires=db.service cache.service\n\
+                After=db.service cache.service\n"
+            ),
+            "{web}"
+        );
+        assert!(
+            worker.contains(
+                "[Unit]\n\
+                Wants=db.service\n\
+                BindsTo=cache.service\n\
+                After=db.service cache.service\n"
+            ),
+            "{worker}"
+        );
+        assert!(!db.contains("[Unit]"), "{db}");
+        assert!(!cache.contains("[Unit]"), "{cache}");
+    }
+
+    #[test]
+    fn tmpfs_size_and_mode() {
+        let yaml = "
+            name: test
+            services:
+              test:
+                image: image
+                volumes:
+                  - type: tmpfs
+                    target: /run
+                    tmpfs:
+                      size: 100000000
+                      mode: 0o1777
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(
+            quadlet.contains("Tmpfs=/run:size=100000000,mode=1777\n"),
+            "{quadlet}"
+        );
+
+        let kube = convert_to_string(
+            Compose {
+                kube: true,
+                ..Compose::default()
+            },
+            yaml,
+        );
+        assert!(kube.contains("sizeLimit: '100000000'"), "{kube}");
+    }
+
+    #[test]
+    fn kube_api_level() {
+        let yaml = "
+            name: test
+            services:
+              test:
+                image: image
+                tmpfs: /run
+                volumes:
+                  - type: tmpfs
+                    target: /tmp
+                    tmpfs:
+                      size: 1000
+        ";
+
+        let latest = convert_to_string(
+            parse_args(&["--kube", "--kube-api-level", "1.10"]).expect("args are valid"),
+            yaml,
+        );
+        assert!(latest.contains("apiVersion: v1\n"), "{latest}");
+        assert!(latest.contains("sizeLimit: '1000'"), "{latest}");
+        assert!(warning::take().is_empty());
+
+        let old = convert_to_string(
+            parse_args(&["--kube", "--kube-api-level", "1.9"]).expect("args are valid"),
+            yaml,
+        );
+        assert!(old.contains("apiVersion: v1\n"), "{old}");
+        assert!(!old.contains("sizeLimit"), "{old}");
+        assert!(old.contains("medium: Memory"), "{old}");
+        let warnings = warning::take();
+        assert!(
+            warnings
+                .iter()
+                .any(|warning| warning.contains("not supported before Kubernetes 1.10")),
+            "{warnings:?}"
+        );
+
+        assert!(parse_args(&["--kube-api-level", "2.0"]).is_err());
+        assert!(parse_args(&["--kube-api-level", "1.x"]).is_err());
+        assert!(convert(
+            parse_args(&["--kube-api-level", "1.9"]).expect("args are valid"),
+            yaml
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn multiple_service_errors() {
+        let yaml = "
+            services:
+              one:
+                image: image
+                cpu_count: 2
+              two:
+                image: image
+                cpu_percent: 50
+        ";
+
+        let error = convert(Compose::default(), yaml).expect_err("services are invalid");
+        assert!(
+            error
+                .root_cause()
+                .to_string()
+                .starts_with("2 errors occurred converting service `one`, service `two`\n"),
+            "{error:?}"
+        );
+
+        let error = format!("{error:?}");
+        assert!(error.contains("`cpu_count` is not supported"), "{error}");
+        assert!(error.contains("`cpu_percent` is not supported"), "{error}");
+    }
+}
@@ -0,0 +1,100 @@
+//! Deterministic names for anonymous volumes which are converted into named volumes.
+
+use std::path::Path;
+
+/// FNV-1a 64-bit offset basis.
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// FNV-1a 64-bit prime.
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// Create a name for an anonymous volume of a service mounted at `target` in its container.
+///
+/// The name is `{service}-{hash}`, where `hash` is derived from the service name and `target`, so
+/// it is the same each time a compose file is converted and unique across services.
+pub fn name(service: &str, target: &Path) -> String {
+    // FNV-1a is used instead of `std::hash` as its output is stable across Rust versions.
+    let hash = service
+        .as_bytes()
+        .iter()
+        .chain(&[0])
+        .chain(target.as_os_str().as_encoded_bytes())
+        .fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+        });
+
+    format!("{service}-{:08x}", hash >> 32)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::File;
+
+    use super::super::{
+        tests::{convert, convert_to_string},
+        Compose,
+    };
+    use super::*;
+
+    #[test]
+    fn unique_per_service_and_target() {
+        let name = name("app", Path::new("/data"));
+        assert!(name.starts_with("app-"), "{name}");
+        assert_eq!(name.len(), "app-".len() + 8);
+
+        assert_eq!(name, super::name("app", Path::new("/data")));
+        assert_ne!(name, super::name("app", Path::new("/cache")));
+        assert_ne!(
+            super::name("ap", Path::new("p/data")),
+            super::name("app", Path::new("/data")),
+        );
+    }
+
+    #[test]
+    fn anonymous_volume_with_options() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                volumes:
+                  - type: volume
+                    target: /data
+                    volume:
+                      nocopy: true
+                  - /cache
+              other:
+                image: image
+                volumes:
+                  - type: volume
+                    target: /data
+                    read_only: true
+        ";
+
+        let names = |files: Vec<File>| -> Vec<String> {
+            files.iter().map(|file| file.name().to_owned()).collect()
+        };
+        let first = names(convert(Compose::default(), yaml).expect("compose file converts"));
+        let second = names(convert(Compose::default(), yaml).expect("compose file converts"));
+        assert_eq!(first, second);
+
+        let [app, app_volume, other, other_volume] =
+            first.try_into().expect("two containers and two volumes");
+        assert_eq!(app, "app");
+        assert_eq!(other, "other");
+        assert_ne!(app_volume, other_volume);
+        assert!(app_volume.starts_with("app-"), "{app_volume}");
+        assert!(other_volume.starts_with("other-"), "{other_volume}");
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(
+            quadlet.contains(&format!(
+                "Volume={app_volume}.volume:/data:nocopy\nVolume=/cache\n"
+            )),
+            "{quadlet}"
+        );
+        assert!(
+            quadlet.contains(&format!("Volume={other_volume}.volume:/data:ro\n")),
+            "{quadlet}"
+        );
+    }
+}
@@ -0,0 +1,63 @@
+//! Conversion of compose service `attach: false` into a log driver.
+
+use compose_spec::{service::Logging, Service};
+
+use crate::warning;
+
+/// Convert `attach: false` of a compose [`Service`] into the `none` log driver.
+///
+/// `attach: false` means `docker compose up` does not follow the service's logs. Quadlet has no
+/// direct equivalent, so the closest option, `LogDriver=none`, is used with a warning, as it also
+/// stops the logs from being recorded. If the service sets a `logging.driver`, it is kept and
+/// `attach` is ignored.
+pub fn attach_false_into_log_driver(service: &mut Service, name: &str) {
+    if service.attach {
+        return;
+    }
+    service.attach = true;
+
+    let logging = service.logging.get_or_insert_with(Logging::default);
+    if let Some(driver) = &logging.driver {
+        warning::warn(format_args!(
+            "service `{name}` sets `attach: false` and `logging.driver: {driver}`, \
+                ignoring `attach`"
+        ));
+    } else {
+        warning::warn(format_args!(
+            "service `{name}` sets `attach: false`, converting to `LogDriver=none` \
+                which also stops the container's logs from being recorded"
+        ));
+        logging.driver = Some("none".to_owned());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{tests::convert_to_string, Compose};
+    use super::*;
+
+    #[test]
+    fn attach_false() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                attach: false
+        ";
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(quadlet.contains("LogDriver=none\n"), "{quadlet}");
+        assert_eq!(warning::take().len(), 1);
+
+        let yaml = "
+            services:
+              app:
+                image: image
+                attach: false
+                logging:
+                  driver: journald
+        ";
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(quadlet.contains("LogDriver=journald\n"), "{quadlet}");
+        assert_eq!(warning::take().len(), 1);
+    }
+}
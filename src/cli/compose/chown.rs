@@ -0,0 +1,165 @@
+//! Support for the `U` option of compose service short syntax `volumes`.
+//!
+//! [`compose_spec`] does not accept the `U` option, which makes Podman change the owner of the
+//! volume's source to match the UID and GID of the container. So, before the compose file is
+//! deserialized, the option is removed from each volume and the volume's container path is
+//! recorded in the service's `x-podlet-chown` extension.
+
+use std::path::PathBuf;
+
+use color_eyre::eyre::WrapErr;
+use compose_spec::{Extensions, Identifier, Service};
+use indexmap::IndexMap;
+use serde_yaml::Value;
+
+use crate::{quadlet::container::Volume, warning};
+
+/// Key of the compose extension used to record the container paths of volumes with the `U`
+/// option.
+const KEY: &str = "x-podlet-chown";
+
+/// Remove the `U` option from the short syntax `volumes` of each service in a compose file's YAML
+/// `value`, recording each volume's container path in the service's `x-podlet-chown` extension.
+///
+/// A warning is emitted if `U` is combined with the `z` or `Z` SELinux relabeling options, as
+/// both modify the volume's source on the host.
+pub fn split_chown_option(value: &mut Value) {
+    let Some(Value::Mapping(services)) = value.get_mut("services") else {
+        return;
+    };
+
+    for (name, service) in services {
+        let Some(service) = service.as_mapping_mut() else {
+            continue;
+        };
+        let Some(Value::Sequence(volumes)) = service.get_mut("volumes") else {
+            continue;
+        };
+
+        let mut chown = Vec::new();
+        for volume in volumes {
+            let Value::String(short) = volume else {
+                continue;
+            };
+            let Some(container_path) = remove_chown_option(short) else {
+                continue;
+            };
+
+            if let Some(relabel) = selinux_relabel(short) {
+                warning::warn(format_args!(
+                    "volume `{short}` of service `{}` combines the `U` and `{relabel}` options, \
+                        both of which modify the volume's source on the host",
+                    name.as_str().unwrap_or_default(),
+                ));
+            }
+            chown.push(Value::String(container_path));
+        }
+
+        if !chown.is_empty() {
+            service.insert(KEY.into(), chown.into());
+        }
+    }
+}
+
+/// Remove the `U` option from a short syntax volume of the form `source:target:options`.
+///
+/// Returns the volume's container path if the option was present.
+fn remove_chown_option(short: &mut String) -> Option<String> {
+    let mut split = short.splitn(3, ':');
+    let source = split.next()?;
+    let target = split.next()?;
+    let options = split.next()?;
+
+    if !options.split(',').any(|option| option == "U") {
+        return None;
+    }
+
+    let options: Vec<&str> = options.split(',').filter(|option| *option != "U").collect();
+    let target = target.to_owned();
+    *short = if options.is_empty() {
+        format!("{source}:{target}")
+    } else {
+        format!("{source}:{target}:{}", options.join(","))
+    };
+
+    Some(target)
+}
+
+/// Get the SELinux relabeling option, `z` or `Z`, of a short syntax volume, if it has one.
+fn selinux_relabel(short: &str) -> Option<&str> {
+    short
+        .splitn(3, ':')
+        .nth(2)?
+        .split(',')
+        .find(|option| matches!(*option, "z" | "Z"))
+}
+
+/// Take the container paths of volumes with the `U` option from a service's [`Extensions`].
+///
+/// # Errors
+///
+/// Returns an error if the `x-podlet-chown` extension is not a list of paths.
+pub fn take_paths(extensions: &mut Extensions) -> color_eyre::Result<Vec<PathBuf>> {
+    extensions
+        .shift_remove(KEY)
+        .map(|paths| serde_yaml::from_value(paths).wrap_err_with(|| format!("invalid `{KEY}`")))
+        .transpose()
+        .map(Option::unwrap_or_default)
+}
+
+/// Set the `U` option of each of the `volumes` mounted at one of the container `paths`.
+pub fn set_volume_options(volumes: &mut [Volume], paths: &[PathBuf]) {
+    for volume in volumes {
+        if paths.iter().any(|path| path == &volume.container_path) {
+            volume.options.chown = true;
+        }
+    }
+}
+
+/// Remove the recorded container paths of volumes with the `U` option from each service, warning
+/// that the option is only supported when converting to Quadlet files.
+pub fn remove(services: &mut IndexMap<Identifier, Service>) {
+    for (name, service) in services {
+        if let Some(paths) = service.extensions.shift_remove(KEY) {
+            let paths: Vec<PathBuf> = serde_yaml::from_value(paths).unwrap_or_default();
+            for path in paths {
+                warning::warn(format_args!(
+                    "the `U` option of the volume mounted at `{}` in service `{name}` is only \
+                        supported with `--format quadlet`, ignoring it",
+                    path.display(),
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{tests::convert_to_string, Compose};
+    use super::*;
+
+    #[test]
+    fn volume_chown_option() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                volumes:
+                  - ./data:/data:U
+                  - ./config:/config:z,U
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(quadlet.contains("Volume=./data:/data:U\n"), "{quadlet}");
+        assert!(
+            quadlet.contains("Volume=./config:/config:z,U\n"),
+            "{quadlet}"
+        );
+
+        let warnings = warning::take();
+        assert!(
+            matches!(warnings.as_slice(), [warning] if warning.contains("`U` and `z`")),
+            "{warnings:?}"
+        );
+    }
+}
@@ -0,0 +1,515 @@
+//! Conversion of compose [`Configs`] used by a [`Service`](compose_spec::Service) into
+//! [`Volume`]s for a [`quadlet::Container`](crate::quadlet::Container).
+
+use std::{
+    env,
+    fmt::{self, Display, Formatter},
+    path::{Path, PathBuf},
+};
+
+use color_eyre::{
+    eyre::{bail, ensure, eyre, OptionExt, WrapErr},
+    Section,
+};
+use compose_spec::{
+    config::Source, service::ConfigOrSecret, Configs, Identifier, Resource, ShortOrLong,
+};
+
+use crate::{
+    quadlet::container::volume::{self, Volume},
+    warning,
+};
+
+/// Default file mode of configs mounted into a container.
+const DEFAULT_MODE: u32 = 0o444;
+
+/// Directory, relative to the generated Quadlet files, [`ContentFile`]s are written to.
+const CONTENT_DIRECTORY: &str = "configs";
+
+/// Resolve configs with an `environment` source into configs with inline `content`.
+///
+/// The content is the value of the environment variable at the time podlet is run.
+///
+/// # Errors
+///
+/// Returns an error if the environment variable is not set or is not valid unicode.
+pub fn resolve_environment_sources(configs: &mut Configs) -> color_eyre::Result<()> {
+    resolve_environment_sources_with(configs, &|variable| env::var(variable).ok())
+}
+
+/// Resolve configs with an `environment` source, getting the values of variables with `lookup`.
+fn resolve_environment_sources_with(
+    configs: &mut Configs,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> color_eyre::Result<()> {
+    for (name, config) in configs {
+        if let Resource::Compose(config) = config {
+            if let Source::Environment(variable) = &config.source {
+                let content = lookup(variable).ok_or_else(|| {
+                    eyre!(
+                        "environment variable `{variable}` for config `{name}` is not set or is \
+                            not valid unicode"
+                    )
+                })?;
+                config.source = Source::Content(content);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Warn for each config with inline `content` which contains variables, like `${VAR}`.
+///
+/// Compose interpolates variables in a config's `content`, but podlet uses it verbatim.
+pub fn warn_content_interpolation(configs: &Configs) {
+    for (name, config) in configs {
+        if let Resource::Compose(compose_spec::Config {
+            source: Source::Content(content),
+            ..
+        }) = config
+        {
+            if has_variable(content) {
+                warning::warn(format_args!(
+                    "`content` of config `{name}` contains a variable, \
+                        podlet does not interpolate it so the content is used verbatim"
+                ));
+            }
+        }
+    }
+}
+
+/// Returns `true` if `content` contains a variable, like `$VAR` or `${VAR}`, which compose would
+/// interpolate.
+fn has_variable(content: &str) -> bool {
+    let mut chars = content.chars();
+    while let Some(char) = chars.next() {
+        // Taking the next char also skips an escaped `$$`.
+        if char == '$' && matches!(chars.next(), Some('{' | '_' | 'a'..='z' | 'A'..='Z')) {
+            return true;
+        }
+    }
+    false
+}
+
+/// A file containing the inline `content` of a config, created so it can be bind mounted into a
+/// container.
+#[derive(Debug)]
+pub struct ContentFile {
+    /// The name of the file, the name of the config.
+    pub name: String,
+
+    /// The file's content, written verbatim.
+    pub content: String,
+
+    /// The file's permissions, set from the `mode` of the services' config.
+    pub mode: Option<u32>,
+}
+
+impl ContentFile {
+    /// The path of the file relative to the directory the Quadlet files are written to.
+    pub fn relative_path(&self) -> PathBuf {
+        content_file_path(&self.name)
+    }
+}
+
+impl Display for ContentFile {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(&self.content)
+    }
+}
+
+/// Path, relative to the generated Quadlet files, of the [`ContentFile`] of the config `name`.
+///
+/// Content files are written to the [`CONTENT_DIRECTORY`] so they cannot collide with generated
+/// files. Quadlet also reads files in subdirectories, so `.content` is appended to names with a
+/// Quadlet file extension, e.g. `app.container`.
+fn content_file_path(name: &str) -> PathBuf {
+    let mut path = Path::new(CONTENT_DIRECTORY).join(name);
+    if path.extension().is_some_and(|extension| {
+        matches!(
+            extension.to_str(),
+            Some("container" | "pod" | "kube" | "network" | "volume" | "build" | "image")
+        )
+    }) {
+        path.as_mut_os_string().push(".content");
+    }
+    path
+}
+
+/// Create a [`ContentFile`] for each config with inline `content` used by a service.
+///
+/// # Errors
+///
+/// Returns an error if services use the same config with different `mode`s.
+pub fn content_files<'a>(
+    configs: &Configs,
+    used: impl IntoIterator<Item = &'a ShortOrLong<Identifier, ConfigOrSecret>>,
+) -> color_eyre::Result<Vec<ContentFile>> {
+    let mut files: Vec<ContentFile> = Vec::new();
+    for config in used {
+        let (name, mode) = match config {
+            ShortOrLong::Short(name) => (name, None),
+            ShortOrLong::Long(config) => (&config.source, config.mode),
+        };
+        let mode = mode.filter(|mode| *mode != DEFAULT_MODE);
+        if let Some(Resource::Compose(compose_spec::Config {
+            source: Source::Content(content),
+            ..
+        })) = configs.get(name)
+        {
+            if let Some(file) = files.iter().find(|file| file.name == name.as_str()) {
+                ensure!(
+                    file.mode == mode,
+                    "config `{name}` is used with different `mode`s, \
+                        its content is written to a single file"
+                );
+            } else {
+                files.push(ContentFile {
+                    name: name.to_string(),
+                    content: content.clone(),
+                    mode,
+                });
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Attempt to convert a service's `configs` into read-only bind mount [`Volume`]s.
+///
+/// `configs` are the top-level configs defined in the compose file.
+///
+/// # Errors
+///
+/// Returns an error if a config is not defined, is external, is not sourced from a file or
+/// `content`, or uses an unsupported option.
+pub fn service_configs_try_into_volumes(
+    service_configs: Vec<ShortOrLong<Identifier, ConfigOrSecret>>,
+    configs: &Configs,
+) -> color_eyre::Result<Vec<Volume>> {
+    service_configs
+        .into_iter()
+        .map(|config| {
+            let config = config.into_long();
+            let source = config.source.clone();
+            config_try_into_volume(config, configs)
+                .wrap_err_with(|| format!("error converting config `{source}`"))
+        })
+        .collect()
+}
+
+/// Attempt to convert a service's [`ConfigOrSecret`] into a read-only bind mount [`Volume`].
+///
+/// If not set, `target` defaults to `/<config-name>`. A config with inline `content` is mounted
+/// from its [`ContentFile`], which is given the config's `mode`.
+///
+/// # Errors
+///
+/// Returns an error if the config is not defined, is external, is not sourced from a file or
+/// `content`, or uses an unsupported option. As the config is bind mounted, a `mode` other than the
+/// default is not supported for configs with a `file` source.
+fn config_try_into_volume(
+    ConfigOrSecret {
+        source,
+        target,
+        uid,
+        gid,
+        mode,
+        extensions,
+    }: ConfigOrSecret,
+    configs: &Configs,
+) -> color_eyre::Result<Volume> {
+    ensure!(
+        uid.is_none() && gid.is_none(),
+        "`uid` and `gid` are not supported for configs"
+    );
+    ensure!(
+        extensions.is_empty(),
+        "compose extensions are not supported"
+    );
+
+    let config = match configs.get(&source).ok_or_eyre("config is not defined")? {
+        Resource::Compose(config) => config,
+        Resource::External { .. } => bail!("external configs are not supported"),
+    };
+    ensure!(
+        config.labels.is_empty(),
+        "config `labels` are not supported"
+    );
+    ensure!(
+        config.extensions.is_empty(),
+        "compose extensions are not supported"
+    );
+    let path = match &config.source {
+        Source::File(path) => {
+            if let Some(mode) = mode.filter(|mode| *mode != DEFAULT_MODE) {
+                return Err(eyre!(
+                    "`mode` {mode:o} is not supported for configs with a `file` source"
+                ))
+                .note("The file is bind mounted into the container, so its permissions are used.")
+                .suggestion(format!(
+                    "Remove `mode` and set the permissions of `{}` instead.",
+                    path.display()
+                ));
+            }
+            path.clone()
+        }
+        // A `ContentFile` is created relative to the Quadlet file.
+        Source::Content(_) => content_file_path(source.as_str()),
+        Source::Environment(_) => {
+            bail!("only configs with a `file` or `content` source are supported")
+        }
+    };
+    // Podman treats sources which do not start with `/` or `.` as named volumes.
+    let path = if path.is_relative() && !path.starts_with(".") && !path.starts_with("..") {
+        Path::new(".").join(path)
+    } else {
+        path
+    };
+
+    // Relative targets are relative to the container's root.
+    let container_path = target.map_or_else(
+        || Path::new("/").join(source.as_str()),
+        |target| Path::new("/").join(target),
+    );
+
+    Ok(Volume {
+        source: Some(volume::Source::HostPath(path)),
+        container_path,
+        options: volume::Options {
+            read_only: true,
+            ..volume::Options::default()
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::File;
+
+    use super::super::{
+        tests::{convert, convert_to_string, parse_args},
+        Compose,
+    };
+    use super::*;
+
+    #[test]
+    fn default_target() {
+        let configs = [(
+            Identifier::new("config").expect("valid identifier"),
+            Resource::Compose(Source::File("config.txt".into()).into()),
+        )]
+        .into();
+        let service_configs = vec![ShortOrLong::Short(
+            Identifier::new("config").expect("valid identifier"),
+        )];
+
+        let volumes =
+            service_configs_try_into_volumes(service_configs, &configs).expect("configs convert");
+        let volumes: Vec<String> = volumes.iter().map(ToString::to_string).collect();
+        assert_eq!(volumes, ["./config.txt:/config:ro"]);
+        assert!(warning::take().is_empty());
+    }
+
+    #[test]
+    fn content_mode() {
+        let name = Identifier::new("config").expect("valid identifier");
+        let configs = [(
+            name.clone(),
+            Resource::Compose(Source::Content("content".into()).into()),
+        )]
+        .into();
+        let with_mode = |mode| {
+            ShortOrLong::Long(ConfigOrSecret {
+                mode: Some(mode),
+                ..ConfigOrSecret::from(name.clone())
+            })
+        };
+
+        let files = content_files(
+            &configs,
+            &[ShortOrLong::Short(name.clone()), with_mode(0o444)],
+        )
+        .expect("default mode matches");
+        assert!(matches!(files.as_slice(), [ContentFile { mode: None, .. }]));
+
+        let files = content_files(&configs, &[with_mode(0o400)]).expect("config converts");
+        assert!(matches!(
+            files.as_slice(),
+            [ContentFile {
+                mode: Some(0o400),
+                ..
+            }]
+        ));
+
+        assert!(content_files(&configs, &[with_mode(0o400), with_mode(0o440)]).is_err());
+    }
+
+    #[test]
+    fn config_content() {
+        let yaml = "
+            name: test
+            services:
+              app:
+                image: image
+                configs:
+                  - source: app.conf
+                    target: /etc/app.conf
+                  - source: greeting
+            configs:
+              app.conf:
+                content: |
+                  [server]
+                    port = 8080
+
+                  # Trailing spaces and blank lines are kept.\x20\x20
+                  path = \"$${HOME}/data\"
+              greeting:
+                content: \"hello ${USER}\"
+        ";
+        let content = "[server]\n  port = 8080\n\n# Trailing spaces and blank lines are kept.  \n\
+            path = \"$${HOME}/data\"\n";
+
+        warning::take();
+        let files = convert(Compose::default(), yaml).expect("compose file converts");
+        let [container, app_conf, greeting]: &[File; 3] =
+            files.as_slice().try_into().expect("three files");
+        let container = container.to_string();
+        assert!(
+            container.contains(
+                "Volume=./configs/app.conf:/etc/app.conf:ro\n\
+                        Volume=./configs/greeting:/greeting:ro\n"
+            ),
+            "{container}"
+        );
+        assert_eq!(app_conf.relative_path(), Path::new("configs/app.conf"));
+        assert_eq!(app_conf.to_string(), content);
+        assert_eq!(greeting.to_string(), "hello ${USER}");
+        assert_eq!(
+            warning::take(),
+            ["`content` of config `greeting` contains a variable, \
+                podlet does not interpolate it so the content is used verbatim"]
+        );
+
+        let args = parse_args(&["--kube"]).expect("args are valid");
+        let files = convert(args, yaml).expect("compose file converts");
+        let config_maps = files.last().expect("config map file").to_string();
+        let config_map: serde_yaml::Value = config_maps
+            .split("---\n")
+            .next()
+            .map(serde_yaml::from_str)
+            .expect("config map document")
+            .expect("config map is valid YAML");
+        assert_eq!(
+            config_map.get("data").and_then(|data| data.get("app.conf")),
+            Some(&serde_yaml::Value::from(content))
+        );
+    }
+
+    #[test]
+    fn content_file_names() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                configs:
+                  - app.container
+                  - app.conf
+            configs:
+              app.container:
+                content: content
+              app.conf:
+                content: content
+        ";
+
+        let files = convert(Compose::default(), yaml).expect("compose file converts");
+        let paths: Vec<_> = files.iter().map(File::relative_path).collect();
+        assert_eq!(
+            paths,
+            [
+                "app.container",
+                "configs/app.container.content",
+                "configs/app.conf"
+            ]
+            .map(PathBuf::from)
+        );
+
+        let container = files.first().expect("container file").to_string();
+        assert!(
+            container.contains(
+                "Volume=./configs/app.container.content:/app.container:ro\n\
+                    Volume=./configs/app.conf:/app.conf:ro\n"
+            ),
+            "{container}"
+        );
+    }
+
+    #[test]
+    fn config_target_and_mode() {
+        let yaml = "
+            services:
+              test:
+                image: image
+                configs:
+                  - source: config
+                    target: /etc/app/config.toml
+                    mode: 0o440
+            configs:
+              config:
+                file: ./config.toml
+        ";
+
+        let error = convert(Compose::default(), yaml).expect_err("`mode` is not supported");
+        assert_eq!(
+            error.root_cause().to_string(),
+            "`mode` 440 is not supported for configs with a `file` source"
+        );
+
+        let yaml = yaml.replace("mode: 0o440", "mode: 0o444");
+        let quadlet = convert_to_string(Compose::default(), &yaml);
+        assert!(
+            quadlet.contains("Volume=./config.toml:/etc/app/config.toml:ro\n"),
+            "{quadlet}"
+        );
+    }
+
+    #[test]
+    fn kube_config_from_environment() {
+        let yaml = "
+            name: test
+            services:
+              app:
+                image: image
+                configs:
+                  - config
+            configs:
+              config:
+                environment: PODLET_TEST_CONFIG
+        ";
+        let compose = Compose {
+            kube: true,
+            ..Compose::default()
+        };
+
+        let error = convert(compose.clone(), yaml).expect_err("variable is unset");
+        assert!(
+            format!("{error:?}").contains("PODLET_TEST_CONFIG"),
+            "{error:?}"
+        );
+
+        let mut configs: Configs = [(
+            Identifier::new("config").expect("valid identifier"),
+            Resource::Compose(Source::Environment("PODLET_TEST_CONFIG".to_owned()).into()),
+        )]
+        .into();
+        let lookup = |name: &str| (name == "PODLET_TEST_CONFIG").then(|| "key=value".to_owned());
+        resolve_environment_sources_with(&mut configs, &lookup).expect("variable is set");
+        assert_eq!(
+            configs.values().next(),
+            Some(&Resource::Compose(
+                Source::Content("key=value".to_owned()).into()
+            ))
+        );
+    }
+}
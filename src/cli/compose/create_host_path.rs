@@ -0,0 +1,119 @@
+//! Support for the `bind.create_host_path` option of compose service long syntax `volumes`.
+//!
+//! Docker creates the source of a bind mount with `create_host_path: true` if it does not exist.
+//! Podman does not, the container fails to start. So, a warning is emitted for each such bind
+//! mount whose source does not exist when podlet is run.
+
+use std::path::{Path, PathBuf};
+
+use compose_spec::{
+    service::{
+        volumes::{mount::Bind, Mount},
+        Volumes,
+    },
+    Identifier, Service,
+};
+use indexmap::IndexMap;
+
+use crate::warning;
+
+/// Warn for each bind mount with `create_host_path: true` in the `volumes` of `services` whose
+/// source does not exist.
+///
+/// Relative sources are resolved relative to the directory of the compose file at
+/// `compose_file`. They are not checked if the compose file was read from stdin.
+pub fn warn_missing_sources(services: &IndexMap<Identifier, Service>, compose_file: Option<&Path>) {
+    for (name, service) in services {
+        for source in missing_sources(&service.volumes, compose_file) {
+            warning::warn(format_args!(
+                "bind mount source `{}` of service `{name}` does not exist, Podman does not \
+                    create it like Docker does for `create_host_path: true`",
+                source.display()
+            ));
+        }
+    }
+}
+
+/// Sources of the bind mounts with `create_host_path: true` in `volumes` which do not exist,
+/// resolved relative to the directory of the compose file at `compose_file`.
+fn missing_sources<'a>(
+    volumes: &'a Volumes,
+    compose_file: Option<&'a Path>,
+) -> impl Iterator<Item = PathBuf> + 'a {
+    volumes
+        .iter()
+        .filter_map(move |volume| match volume.as_long()? {
+            Mount::Bind(Bind {
+                source,
+                bind: Some(bind),
+                ..
+            }) if bind.create_host_path => {
+                let source = source.as_path();
+                if source.is_absolute() {
+                    Some(source.to_owned())
+                } else {
+                    compose_file.map(|compose_file| {
+                        compose_file.parent().unwrap_or(Path::new("")).join(source)
+                    })
+                }
+            }
+            _ => None,
+        })
+        .filter(|source| !source.exists())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        tests::{convert, convert_to_string},
+        Compose,
+    };
+    use super::*;
+
+    #[test]
+    fn create_host_path() {
+        let yaml = "
+            name: uploads
+            services:
+              app:
+                image: app
+                volumes:
+                  - type: bind
+                    source: ./uploads
+                    target: /srv/uploads
+                    bind:
+                      create_host_path: true
+                  - type: bind
+                    source: ./config
+                    target: /etc/app
+                    read_only: true
+                    bind:
+                      create_host_path: true
+        ";
+
+        warning::take();
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        for volume in [
+            "Volume=./uploads:/srv/uploads\n",
+            "Volume=./config:/etc/app:ro\n",
+        ] {
+            assert!(quadlet.contains(volume), "{volume}: {quadlet}");
+        }
+        // Relative sources are not checked when reading from stdin.
+        assert!(warning::take().is_empty());
+
+        let args = Compose {
+            compose_file: Some("/podlet-test/app/compose.yaml".into()),
+            ..Compose::default()
+        };
+        convert(args, yaml).expect("compose file converts");
+        let warnings = warning::take();
+        assert_eq!(warnings.len(), 2, "{warnings:?}");
+        assert!(
+            warnings
+                .iter()
+                .any(|warning| warning.contains("`/podlet-test/app/./uploads` of service `app`")),
+            "{warnings:?}"
+        );
+    }
+}
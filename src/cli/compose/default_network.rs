@@ -0,0 +1,178 @@
+//! The compose project's default network, for `podlet compose --default-network`.
+
+use compose_spec::{
+    service::{network_config::Networks, NetworkConfig},
+    Compose, Identifier, ShortOrLong,
+};
+use indexmap::IndexSet;
+
+/// Key of the compose default network.
+const DEFAULT: &str = "default";
+
+/// Attach each service without `networks` or a `network_mode` to the project's default network,
+/// named `network`, as `docker compose` does.
+///
+/// The network is added to the top-level `networks` if any service is attached to it. If the
+/// compose file configures the `default` network, it is renamed to `network`, along with any
+/// references to it from services.
+pub fn attach(compose: &mut Compose, network: &Identifier) {
+    if network.as_str() != DEFAULT {
+        if let Some(index) = compose.networks.get_index_of(DEFAULT) {
+            let (_, config) = compose
+                .networks
+                .shift_remove_index(index)
+                .expect("index is valid");
+            compose
+                .networks
+                .shift_insert(index, network.clone(), config);
+
+            for service in compose.services.values_mut() {
+                if let Some(NetworkConfig::Networks(networks)) = &mut service.network_config {
+                    rename_default(networks, network);
+                }
+            }
+        }
+    }
+
+    let mut attached = false;
+    for service in compose.services.values_mut() {
+        if service.network_config.is_none() {
+            service.network_config = Some(NetworkConfig::Networks(ShortOrLong::Short(
+                IndexSet::from([network.clone()]),
+            )));
+            attached = true;
+        }
+    }
+
+    if attached {
+        compose.networks.entry(network.clone()).or_default();
+    }
+}
+
+/// Rename a service's reference to the `default` network to `network`, keeping its position.
+fn rename_default(networks: &mut Networks, network: &Identifier) {
+    match networks {
+        ShortOrLong::Short(networks) => {
+            if let Some(index) = networks.get_index_of(DEFAULT) {
+                networks.shift_remove_index(index);
+                networks.shift_insert(index, network.clone());
+            }
+        }
+        ShortOrLong::Long(networks) => {
+            if let Some(index) = networks.get_index_of(DEFAULT) {
+                let (_, config) = networks.shift_remove_index(index).expect("index is valid");
+                networks.shift_insert(index, network.clone(), config);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        tests::{convert, convert_to_string, parse_args},
+        Compose,
+    };
+
+    const YAML: &str = "
+        name: app
+        services:
+          web:
+            image: web
+            labels:
+              com.docker.compose.project: app
+          db:
+            image: db
+            networks:
+              - backend
+          monitor:
+            image: monitor
+            network_mode: host
+        networks:
+          backend: {}
+    ";
+
+    #[test]
+    fn default_network() {
+        let args = parse_args(&["--default-network"]).expect("args are valid");
+        let files = convert(args, YAML).expect("compose file converts");
+
+        let names: Vec<_> = files
+            .iter()
+            .map(|file| format!("{}.{}", file.name(), file.extension()))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "web.container",
+                "db.container",
+                "monitor.container",
+                "backend.network",
+                "app_default.network",
+            ]
+        );
+
+        let [web, db, monitor] = [0, 1, 2].map(|index| {
+            files
+                .get(index)
+                .map(ToString::to_string)
+                .expect("container file exists")
+        });
+        assert!(web.contains("Network=app_default.network\n"), "{web}");
+        assert!(db.contains("Network=backend.network\n"), "{db}");
+        assert!(!db.contains("app_default"), "{db}");
+        assert!(monitor.contains("Network=host\n"), "{monitor}");
+
+        let quadlet = convert_to_string(Compose::default(), YAML);
+        assert!(!quadlet.contains("app_default"), "opt-in: {quadlet}");
+    }
+
+    #[test]
+    fn docker_compat() {
+        let args = parse_args(&["--docker-compat"]).expect("args are valid");
+        let files = convert(args, YAML).expect("compose file converts");
+
+        let names: Vec<_> = files
+            .iter()
+            .map(|file| format!("{}.{}", file.name(), file.extension()))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "app_web.container",
+                "app_db.container",
+                "app_monitor.container",
+                "app_backend.network",
+                "app_default.network",
+            ]
+        );
+
+        let quadlet: String = files.iter().map(ToString::to_string).collect();
+        assert!(
+            quadlet.contains("Network=app_default.network\n"),
+            "{quadlet}"
+        );
+        assert!(
+            quadlet.contains("Network=app_backend.network\n"),
+            "{quadlet}"
+        );
+        assert!(!quadlet.contains("com.docker.compose"), "{quadlet}");
+
+        let args =
+            parse_args(&["--docker-compat", "--prefix-separator", "-"]).expect("args are valid");
+        let files = convert(args, YAML).expect("compose file converts");
+        assert!(files.iter().any(|file| file.name() == "app-default"));
+
+        for conflict in [
+            "--pod",
+            "--use-project-name",
+            "--keep-docker-labels",
+            "--kube",
+        ] {
+            assert!(
+                parse_args(&["--docker-compat", conflict]).is_err(),
+                "{conflict}"
+            );
+        }
+    }
+}
@@ -0,0 +1,171 @@
+//! Removal of the `develop` section from compose [`Service`](compose_spec::Service)s.
+
+use std::path::{Component, Path, PathBuf};
+
+use color_eyre::eyre::WrapErr;
+use compose_spec::{
+    service::{
+        develop::{Action, WatchRule},
+        volumes::{HostPath, ShortOptions, ShortVolume, Source},
+    },
+    Compose, ShortOrLong,
+};
+
+use crate::warning;
+
+/// Remove the `develop` section from each service of a [`Compose`] file, warning once for each
+/// `watch` rule.
+///
+/// Watch rules are used by `docker compose watch` to synchronize files into containers or rebuild
+/// them as source files change. Neither Quadlet nor Kubernetes have an equivalent, so the rules
+/// are skipped with a warning instead of causing an error.
+///
+/// If `watch_as_mounts` is `true`, `sync` and `sync+restart` rules with a `target` are instead
+/// converted into read-write bind mounts of the `path` to the `target`, so local edits are
+/// reflected in the container.
+///
+/// # Errors
+///
+/// Returns an error if the `path` of a rule converted into a bind mount is not a valid host path.
+pub fn remove_develop(compose: &mut Compose, watch_as_mounts: bool) -> color_eyre::Result<()> {
+    for (name, service) in &mut compose.services {
+        let Some(develop) = service.develop.take() else {
+            continue;
+        };
+
+        for rule in develop.watch {
+            match rule {
+                WatchRule {
+                    action: Action::Sync | Action::SyncAndRestart,
+                    ignore,
+                    path,
+                    target: Some(target),
+                    ..
+                } if watch_as_mounts => {
+                    if !ignore.is_empty() {
+                        warning::warn(format_args!(
+                            "service `{name}` has a `develop.watch` rule with `ignore` patterns, \
+                                ignored files in `{}` are still mounted",
+                            path.display()
+                        ));
+                    }
+                    let source = HostPath::new(host_path(path)).wrap_err_with(|| {
+                        format!("invalid `develop.watch` path of service `{name}`")
+                    })?;
+                    service.volumes.insert(ShortOrLong::Short(ShortVolume {
+                        container_path: target,
+                        options: Some(ShortOptions::new(Source::HostPath(source))),
+                    }));
+                }
+                rule => warning::warn(format_args!(
+                    "service `{name}` has a `develop.watch` rule to {}, \
+                        which is not supported by Podman, skipping it",
+                    describe_rule(&rule),
+                )),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Prefix a relative `path` with `./`, as compose resolves watch paths from the project directory.
+fn host_path(path: PathBuf) -> PathBuf {
+    if path.is_absolute()
+        || matches!(
+            path.components().next(),
+            Some(Component::CurDir | Component::ParentDir)
+        )
+    {
+        path
+    } else {
+        Path::new(".").join(path)
+    }
+}
+
+/// Describe what a [`WatchRule`] would do.
+fn describe_rule(
+    WatchRule {
+        action,
+        path,
+        target,
+        ..
+    }: &WatchRule,
+) -> String {
+    let path = path.display();
+    match (action, target) {
+        (Action::Rebuild, _) => format!("rebuild the image when `{path}` changes"),
+        (Action::Sync, Some(target)) => {
+            format!("sync `{path}` to `{}`", target.as_path().display())
+        }
+        (Action::SyncAndRestart, Some(target)) => format!(
+            "sync `{path}` to `{}` and restart the container",
+            target.as_path().display()
+        ),
+        (Action::Sync | Action::SyncAndRestart, None) => format!("{action} `{path}`"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        tests::{convert, convert_to_string, parse_args},
+        Compose,
+    };
+    use super::*;
+
+    #[test]
+    fn develop_watch() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                develop:
+                  watch:
+                    - action: sync
+                      path: ./src
+                      target: /app/src
+                    - action: rebuild
+                      path: package.json
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(quadlet.contains("Image=image"), "{quadlet}");
+
+        let [sync, rebuild]: [String; 2] = warning::take()
+            .try_into()
+            .expect("a warning for each watch rule");
+        assert!(sync.contains("sync `./src` to `/app/src`"), "{sync}");
+        assert!(rebuild.contains("rebuild the image"), "{rebuild}");
+    }
+
+    #[test]
+    fn watch_as_mounts() {
+        let yaml = "
+            services:
+              app:
+                image: docker.io/library/node
+                develop:
+                  watch:
+                    - action: sync
+                      path: src
+                      target: /app/src
+                    - action: rebuild
+                      path: package.json
+        ";
+
+        warning::take();
+        let args = parse_args(&["--watch-as-mounts"]).expect("args are valid");
+        let quadlet = convert_to_string(args, yaml);
+        assert!(quadlet.contains("Volume=./src:/app/src\n"), "{quadlet}");
+
+        let [rebuild]: [String; 1] = warning::take()
+            .try_into()
+            .expect("a warning for the rebuild rule");
+        assert!(rebuild.contains("rebuild the image"), "{rebuild}");
+
+        let args =
+            parse_args(&["--watch-as-mounts", "--format", "compose"]).expect("args are valid");
+        assert!(convert(args, yaml).is_err());
+    }
+}
@@ -0,0 +1,61 @@
+//! Support for compose service `healthcheck` durations given as a number of seconds.
+
+use serde_yaml::Value;
+
+/// Duration options of a service's `healthcheck`.
+const HEALTHCHECK_DURATIONS: [&str; 4] = ["interval", "timeout", "start_period", "start_interval"];
+
+/// Convert the durations of each service's `healthcheck` in a compose file's YAML `value` from an
+/// integer number of seconds, like `30`, into a duration string, like `30s`.
+///
+/// [`compose_spec`] reads integer durations as microseconds, but they are commonly written as
+/// seconds.
+pub fn healthcheck_seconds(value: &mut Value) {
+    let Some(Value::Mapping(services)) = value.get_mut("services") else {
+        return;
+    };
+
+    for service in services.values_mut() {
+        let Some(Value::Mapping(healthcheck)) = service.get_mut("healthcheck") else {
+            continue;
+        };
+
+        for key in HEALTHCHECK_DURATIONS {
+            if let Some(duration) = healthcheck.get_mut(key) {
+                if let Some(seconds) = duration.as_u64() {
+                    *duration = format!("{seconds}s").into();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{tests::convert_to_string, Compose};
+
+    #[test]
+    fn healthcheck_integer_seconds() {
+        let convert = |interval: &str| {
+            let yaml = format!(
+                "
+                services:
+                  app:
+                    image: image
+                    healthcheck:
+                      test: healthcheck
+                      interval: {interval}
+                      timeout: 5
+                "
+            );
+            convert_to_string(Compose::default(), &yaml)
+        };
+
+        let integer = convert("30");
+        assert_eq!(integer, convert("30s"));
+        assert!(
+            integer.contains("HealthInterval=30s\nHealthTimeout=5s\n"),
+            "{integer}"
+        );
+    }
+}
@@ -0,0 +1,295 @@
+//! Environment variables added to every compose [`Service`] with `podlet compose --env` and
+//! `--env-file`.
+//!
+//! Variables set with `--env` take precedence over a service's `environment`, which takes
+//! precedence over its `env_file`, which takes precedence over `--env-file`. Use
+//! [`EnvMerge::EnvFile`] to give `--env-file` precedence over the service instead.
+
+use std::{
+    fs,
+    path::{self, PathBuf},
+};
+
+use clap::ValueEnum;
+use color_eyre::eyre::{OptionExt, WrapErr};
+use compose_spec::{service::EnvFile, Identifier, ListOrMap, MapKey, Service, ShortOrLong, Value};
+use indexmap::{IndexMap, IndexSet};
+
+/// Precedence of variables set in an `--env-file` and by a service.
+#[derive(ValueEnum, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EnvMerge {
+    /// A service's `environment` and `env_file` take precedence over `--env-file`
+    #[default]
+    Compose,
+
+    /// `--env-file` takes precedence over a service's `environment` and `env_file`
+    EnvFile,
+}
+
+/// Add each of the `env_files` to the `env_file` of each of the `services`.
+///
+/// The files are added before the service's own `env_file`s, so variables set by the service take
+/// precedence. If `merge` is [`EnvMerge::EnvFile`], the files are added after them instead and
+/// variables set in the files are removed from the service's `environment`.
+///
+/// Relative paths are resolved from the current working directory.
+///
+/// # Errors
+///
+/// Returns an error if a path could not be made absolute or, if `merge` is
+/// [`EnvMerge::EnvFile`], a file could not be read.
+pub fn add_env_files(
+    services: &mut IndexMap<Identifier, Service>,
+    env_files: &[PathBuf],
+    merge: EnvMerge,
+) -> color_eyre::Result<()> {
+    if env_files.is_empty() {
+        return Ok(());
+    }
+
+    let env_files = env_files
+        .iter()
+        .map(|path| {
+            path::absolute(path)
+                .wrap_err_with(|| format!("error resolving path `{}`", path.display()))
+        })
+        .collect::<color_eyre::Result<Vec<_>>>()?;
+
+    let keys = match merge {
+        EnvMerge::Compose => Vec::new(),
+        EnvMerge::EnvFile => {
+            let mut keys = Vec::new();
+            for path in &env_files {
+                let contents = fs::read_to_string(path)
+                    .wrap_err_with(|| format!("error reading `{}`", path.display()))?;
+                keys.extend(file_keys(&contents).map(str::to_owned));
+            }
+            keys
+        }
+    };
+
+    insert_env_files(services, &env_files, &keys, merge);
+
+    Ok(())
+}
+
+/// Insert the absolute `env_files` into the `env_file` of each of the `services`, according to
+/// `merge`, and remove the variables with the given `keys` from their `environment`.
+fn insert_env_files(
+    services: &mut IndexMap<Identifier, Service>,
+    env_files: &[PathBuf],
+    keys: &[String],
+    merge: EnvMerge,
+) {
+    for service in services.values_mut() {
+        let service_env_files = service
+            .env_file
+            .take()
+            .map(EnvFile::into_list)
+            .unwrap_or_default();
+        let cli_env_files = env_files.iter().cloned().map(ShortOrLong::Short);
+        service.env_file = Some(EnvFile::List(match merge {
+            EnvMerge::Compose => cli_env_files.chain(service_env_files).collect(),
+            EnvMerge::EnvFile => service_env_files.into_iter().chain(cli_env_files).collect(),
+        }));
+
+        for key in keys {
+            remove(&mut service.environment, key);
+        }
+    }
+}
+
+/// The keys of the variables set in the `contents` of an environment file.
+///
+/// Empty lines and comments starting with `#` are skipped.
+fn file_keys(contents: &str) -> impl Iterator<Item = &str> {
+    contents.lines().filter_map(|line| {
+        let line = line.trim_start();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let key = line.split_once('=').map_or(line, |(key, _)| key).trim();
+        (!key.is_empty()).then_some(key)
+    })
+}
+
+/// Add each `KEY=VALUE` environment variable in `environment` to each of the `services`,
+/// overriding any variable of the same name set by the service.
+///
+/// Only the first `=` separates the key from the value, so values may contain `=`.
+///
+/// # Errors
+///
+/// Returns an error if a variable is not in the form `KEY=VALUE` or the key is invalid.
+pub fn add_to_services(
+    services: &mut IndexMap<Identifier, Service>,
+    environment: &[String],
+) -> color_eyre::Result<()> {
+    let environment = environment
+        .iter()
+        .map(|variable| {
+            let (key, value) = variable
+                .split_once('=')
+                .ok_or_eyre("environment variables must be in the form `KEY=VALUE`")?;
+            let key = MapKey::new(key)
+                .wrap_err_with(|| format!("invalid environment variable name `{key}`"))?;
+            Ok((key, value))
+        })
+        .collect::<color_eyre::Result<Vec<_>>>()?;
+
+    for service in services.values_mut() {
+        for (key, value) in &environment {
+            set(&mut service.environment, key, value);
+        }
+    }
+
+    Ok(())
+}
+
+/// Set the environment variable `key` to `value`, replacing any existing value.
+fn set(environment: &mut ListOrMap, key: &MapKey, value: &str) {
+    match environment {
+        ListOrMap::List(environment) => {
+            remove_from_list(environment, key.as_str());
+            environment.insert(format!("{key}={value}"));
+        }
+        ListOrMap::Map(environment) => {
+            environment.insert(key.clone(), Some(Value::String(value.to_owned())));
+        }
+    }
+}
+
+/// Remove the environment variable `key`, if set.
+fn remove(environment: &mut ListOrMap, key: &str) {
+    match environment {
+        ListOrMap::List(environment) => remove_from_list(environment, key),
+        ListOrMap::Map(environment) => {
+            environment.retain(|variable, _| variable.as_str() != key);
+        }
+    }
+}
+
+/// Remove the `KEY=VALUE` environment variable `key` from `environment`, if set.
+fn remove_from_list(environment: &mut IndexSet<String>, key: &str) {
+    environment.retain(|variable| {
+        variable
+            .split_once('=')
+            .map_or(variable.as_str(), |(key, _)| key)
+            != key
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::slice;
+
+    use super::super::tests::{convert, convert_to_string, parse_args};
+    use super::*;
+
+    #[test]
+    fn env() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                environment:
+                  A: compose
+        ";
+
+        let args = parse_args(&["--env", "A=a", "-e", "B=b=c"]).expect("args are valid");
+        let quadlet = convert_to_string(args, yaml);
+        assert!(quadlet.contains("Environment=A=a B=b=c\n"), "{quadlet}");
+
+        let args = parse_args(&["--env", "A"]).expect("args are valid");
+        assert!(convert(args, yaml).is_err(), "`--env` requires `=`");
+    }
+
+    #[test]
+    fn env_merge() {
+        let yaml = "
+            services:
+              app:
+                image: app
+                env_file: ./service.env
+                environment:
+                  LOG_LEVEL: compose
+                  PORT: 8080
+        ";
+        let external = "/podlet-test/external.env";
+
+        // `--env` > `environment` > `env_file` > `--env-file`, Podman gives later
+        // `EnvironmentFile=` precedence and `Environment=` precedence over all files.
+        let args = parse_args(&["--env-file", external]).expect("args are valid");
+        let quadlet = convert_to_string(args, yaml);
+        assert!(
+            quadlet.contains(&format!(
+                "EnvironmentFile={external}\nEnvironmentFile=./service.env\n"
+            )),
+            "{quadlet}"
+        );
+        assert!(
+            quadlet.contains("Environment=LOG_LEVEL=compose PORT=8080\n"),
+            "{quadlet}"
+        );
+
+        // With `--env-merge env-file`, the `--env-file`s are read for their keys.
+        let args = parse_args(&["--env-file", external, "--env-merge", "env-file"])
+            .expect("args are valid");
+        let error = convert(args, yaml).expect_err("file does not exist");
+        assert!(format!("{error:?}").contains(external), "{error:?}");
+
+        assert!(parse_args(&["--env-merge", "env-file"]).is_err());
+    }
+
+    #[test]
+    fn env_file_precedence() {
+        let contents = "
+            # Shared settings
+            export LOG_LEVEL=external
+            REGION=eu
+        ";
+        let keys: Vec<String> = file_keys(contents).map(str::to_owned).collect();
+        assert_eq!(keys, ["LOG_LEVEL", "REGION"]);
+
+        let mut services: IndexMap<Identifier, Service> = serde_yaml::from_str(
+            "
+            app:
+              image: app
+              env_file: ./service.env
+              environment:
+                LOG_LEVEL: compose
+                PORT: '8080'
+            ",
+        )
+        .expect("services are valid");
+        let external = PathBuf::from("/podlet-test/external.env");
+
+        // `--env-file` > `environment` > `env_file`.
+        insert_env_files(
+            &mut services,
+            slice::from_ref(&external),
+            &keys,
+            EnvMerge::EnvFile,
+        );
+        let app = services.first().map(|(_, app)| app).expect("one service");
+        assert_eq!(
+            app.env_file,
+            Some(EnvFile::List(vec![
+                ShortOrLong::Short("./service.env".into()),
+                ShortOrLong::Short(external),
+            ]))
+        );
+        assert_eq!(
+            app.environment,
+            ListOrMap::Map(
+                [(
+                    MapKey::new("PORT").expect("valid key"),
+                    Some(Value::String("8080".into()))
+                )]
+                .into_iter()
+                .collect()
+            )
+        );
+    }
+}
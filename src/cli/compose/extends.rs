@@ -0,0 +1,287 @@
+//! Resolution of compose service `extends`.
+
+use std::{
+    fs, mem,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::eyre::{bail, eyre, OptionExt, WrapErr};
+use serde_yaml::{Mapping, Value};
+
+use super::merge;
+
+/// Resolve the `extends` of each service in a compose file's YAML `value`.
+///
+/// Each service which extends another is replaced by the referenced base service merged with it,
+/// following the same merge rules as multiple compose files. The base service may be in the same
+/// compose file or, with `extends.file`, in another compose file. Relative `extends.file` paths
+/// are resolved from the directory of the compose file that contains them, not the current
+/// working directory.
+///
+/// `path` is the path of the compose file the `value` was read from, or [`None`] if it was read
+/// from stdin.
+///
+/// # Errors
+///
+/// Returns an error if an `extends` is invalid, refers to a service or file which does not exist,
+/// forms a cycle, or uses a relative `extends.file` path and `path` is [`None`].
+pub fn resolve(value: &mut Value, path: Option<&Path>) -> color_eyre::Result<()> {
+    let Some(Value::Mapping(services)) = value.get_mut("services") else {
+        return Ok(());
+    };
+
+    let original = services.clone();
+    for (name, service) in services {
+        let name = name.as_str().ok_or_eyre("service names must be strings")?;
+        *service = resolve_service(&original, name, path, &mut Vec::new())
+            .wrap_err_with(|| format!("error resolving `extends` of service `{name}`"))?;
+    }
+
+    Ok(())
+}
+
+/// Resolve the `extends` of the service `name` from `services`, recursively.
+///
+/// `path` is the path of the compose file containing `services`, if any. `stack` contains the
+/// services currently being resolved, used to detect cycles.
+fn resolve_service(
+    services: &Mapping,
+    name: &str,
+    path: Option<&Path>,
+    stack: &mut Vec<(Option<PathBuf>, String)>,
+) -> color_eyre::Result<Value> {
+    let entry = (path.map(Path::to_owned), name.to_owned());
+    if stack.contains(&entry) {
+        bail!("`extends` of service `{name}` forms a cycle");
+    }
+
+    let mut service = services
+        .get(name)
+        .cloned()
+        .ok_or_else(|| eyre!("service `{name}` is not defined"))?;
+    let Some(extends) = service
+        .as_mapping_mut()
+        .and_then(|service| service.remove("extends"))
+    else {
+        return Ok(service);
+    };
+
+    let (base_name, file) = match &extends {
+        Value::String(base_name) => (base_name.as_str(), None),
+        Value::Mapping(extends) => (
+            extends
+                .get("service")
+                .and_then(Value::as_str)
+                .ok_or_eyre("`extends.service` must be a string")?,
+            extends
+                .get("file")
+                .map(|file| file.as_str().ok_or_eyre("`extends.file` must be a string"))
+                .transpose()?,
+        ),
+        _ => bail!("`extends` must be a string or a mapping"),
+    };
+
+    stack.push(entry);
+    let mut base = if let Some(file) = file {
+        let file = resolve_path(Path::new(file), path)?;
+        let services = read_services(&file)?;
+        resolve_service(&services, base_name, Some(&file), stack)
+    } else {
+        resolve_service(services, base_name, path, stack)
+    }
+    .wrap_err_with(|| format!("error resolving base service `{base_name}`"))?;
+    stack.pop();
+
+    merge::merge(&mut base, service, None);
+    Ok(base)
+}
+
+/// Resolve an `extends.file` path relative to the directory of the compose file at `path`.
+///
+/// # Errors
+///
+/// Returns an error if `file` is relative and `path` is [`None`], or if the resolved file does
+/// not exist.
+fn resolve_path(file: &Path, path: Option<&Path>) -> color_eyre::Result<PathBuf> {
+    let file = if file.is_relative() {
+        let path = path.ok_or_else(|| {
+            eyre!(
+                "relative `extends.file` path `{}` is not supported when reading the compose \
+                    file from stdin",
+                file.display()
+            )
+        })?;
+        path.parent().unwrap_or(Path::new("")).join(file)
+    } else {
+        file.to_owned()
+    };
+
+    // Canonicalize so that cycles through differently written paths are detected.
+    file.canonicalize()
+        .wrap_err_with(|| format!("error resolving `extends.file` path `{}`", file.display()))
+}
+
+/// Read the `services` of the compose file at `path`.
+///
+/// # Errors
+///
+/// Returns an error if the file could not be read or is not valid YAML.
+fn read_services(path: &Path) -> color_eyre::Result<Mapping> {
+    let compose =
+        fs::read_to_string(path).wrap_err_with(|| format!("error reading `{}`", path.display()))?;
+    parse_services(&compose, path)
+}
+
+/// Parse the `services` of the `compose` file read from `path`.
+///
+/// # Errors
+///
+/// Returns an error if `compose` is not valid YAML, or its `services` are not a mapping.
+fn parse_services(compose: &str, path: &Path) -> color_eyre::Result<Mapping> {
+    let mut compose: Value = serde_yaml::from_str(compose)
+        .wrap_err_with(|| format!("`{}` is not valid YAML", path.display()))?;
+    compose
+        .apply_merge()
+        .wrap_err_with(|| format!("error applying merge keys in `{}`", path.display()))?;
+
+    match compose.get_mut("services").map(mem::take) {
+        Some(Value::Mapping(services)) => Ok(services),
+        None | Some(Value::Null) => Ok(Mapping::new()),
+        Some(_) => bail!("`services` in `{}` must be a mapping", path.display()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_file() {
+        let mut value: Value = serde_yaml::from_str(
+            "
+            services:
+              base:
+                image: image
+                environment:
+                  A: a
+              app:
+                extends: base
+                environment:
+                  B: b
+              other:
+                extends:
+                  service: app
+                image: other
+            ",
+        )
+        .expect("valid YAML");
+        resolve(&mut value, None).expect("extends resolve");
+
+        let expected: Value = serde_yaml::from_str(
+            "
+            services:
+              base:
+                image: image
+                environment:
+                  A: a
+              app:
+                image: image
+                environment:
+                  A: a
+                  B: b
+              other:
+                image: other
+                environment:
+                  A: a
+                  B: b
+            ",
+        )
+        .expect("valid YAML");
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn cycle() {
+        let mut value: Value = serde_yaml::from_str(
+            "
+            services:
+              one:
+                extends: two
+              two:
+                extends: one
+            ",
+        )
+        .expect("valid YAML");
+        let error = resolve(&mut value, None).expect_err("cycle is an error");
+        assert!(format!("{error:?}").contains("forms a cycle"), "{error:?}");
+    }
+
+    #[test]
+    fn relative_file_from_stdin() {
+        let mut value: Value = serde_yaml::from_str(
+            "
+            services:
+              app:
+                extends:
+                  file: ../common.yaml
+                  service: base
+            ",
+        )
+        .expect("valid YAML");
+        let error = resolve(&mut value, None).expect_err("relative file from stdin is an error");
+        assert!(
+            format!("{error:?}").contains("not supported when reading the compose file from stdin"),
+            "{error:?}"
+        );
+    }
+
+    #[test]
+    fn relative_file() {
+        let mut value: Value = serde_yaml::from_str(
+            "
+            services:
+              app:
+                extends:
+                  file: ../common.yaml
+                  service: base
+            ",
+        )
+        .expect("valid YAML");
+        let error = resolve(&mut value, Some(Path::new("/podlet-test/app/compose.yaml")))
+            .expect_err("file does not exist");
+        assert!(
+            format!("{error:?}").contains("`/podlet-test/app/../common.yaml`"),
+            "{error:?}"
+        );
+    }
+
+    #[test]
+    fn other_file_services() {
+        let path = Path::new("common.yaml");
+        let services = parse_services(
+            "
+            x-base: &base
+              image: docker.io/library/alpine
+            services:
+              base:
+                <<: *base
+                environment:
+                  LOG_LEVEL: info
+            ",
+            path,
+        )
+        .expect("services are valid");
+        let base = resolve_service(&services, "base", Some(path), &mut Vec::new())
+            .expect("service is defined");
+        assert_eq!(
+            base,
+            serde_yaml::from_str::<Value>(
+                "{image: docker.io/library/alpine, environment: {LOG_LEVEL: info}}"
+            )
+            .expect("valid YAML")
+        );
+
+        assert!(parse_services("", path).expect("empty file").is_empty());
+        assert!(parse_services("services: [base]", path).is_err());
+    }
+}
@@ -0,0 +1,292 @@
+//! The `x-podlet` compose extension for setting Podlet specific options on a service.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    path::PathBuf,
+};
+
+use clap::ValueEnum;
+use color_eyre::eyre::WrapErr;
+use compose_spec::Extensions;
+use serde::Deserialize;
+
+/// Key of the Podlet compose extension.
+pub const KEY: &str = "x-podlet";
+
+/// Podlet specific options for a compose [`Service`](compose_spec::Service), set with the
+/// `x-podlet` extension.
+#[derive(Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ServiceExtension {
+    /// Extra arguments appended, verbatim, to the end of `PodmanArgs=`.
+    #[serde(default)]
+    pub podman_args: Vec<String>,
+
+    /// Action to take once the container becomes unhealthy, converts to `HealthOnFailure=`.
+    ///
+    /// Only used if the service has a `healthcheck`.
+    pub health_on_failure: Option<HealthOnFailure>,
+
+    /// Timezone of the container, converts to `Timezone=`.
+    ///
+    /// Takes precedence over the service's `TZ` environment variable.
+    pub timezone: Option<String>,
+
+    /// Existing Quadlet pod to link the container to, converts to `Pod=`.
+    ///
+    /// The pod is not generated, it must be defined separately. Cannot be used with `--pod`.
+    pub pod: Option<String>,
+
+    /// Whether to mount a read-write tmpfs on `/dev`, `/dev/shm`, `/run`, `/tmp`, and `/var/tmp`
+    /// of a `read_only` container, converts to `ReadOnlyTmpfs=`.
+    ///
+    /// Defaults to `true`, matching Podman. Only used if the service is `read_only`.
+    pub read_only_tmpfs: Option<bool>,
+
+    /// Whether the container sends a ready notification with sd-notify, converts to `Notify=true`.
+    ///
+    /// Overrides `--notify`. The application in the container must support sd-notify.
+    pub notify: Option<bool>,
+
+    /// Absolute paths to mask in the container, converts to `Mask=`.
+    ///
+    /// Added to the paths given with `--mask`.
+    #[serde(default)]
+    pub mask: Vec<String>,
+
+    /// Absolute paths to unmask in the container, or `ALL`, converts to `Unmask=`.
+    ///
+    /// Added to the paths given with `--unmask`.
+    #[serde(default)]
+    pub unmask: Vec<String>,
+
+    /// Options for the `[Service]` section of the generated Quadlet file.
+    #[serde(default)]
+    pub service: ServiceSection,
+}
+
+/// Options for the systemd `[Service]` section, set with the `service` option of the `x-podlet`
+/// extension.
+#[derive(Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ServiceSection {
+    /// Files systemd reads environment variables for the service from, converts to
+    /// `EnvironmentFile=`.
+    ///
+    /// Unlike the service's `env_file`, the variables are not set in the container.
+    #[serde(default)]
+    pub environment_file: Vec<PathBuf>,
+
+    /// Interval in which the service may only be started `start_limit_burst` times, converts to
+    /// `StartLimitIntervalSec=` in the `[Unit]` section.
+    ///
+    /// Given in the same format as other compose durations, e.g. `1m30s`.
+    pub start_limit_interval: Option<String>,
+
+    /// Maximum number of times the service may be started within the `start_limit_interval`,
+    /// converts to `StartLimitBurst=` in the `[Unit]` section.
+    ///
+    /// Overrides the `max_attempts` of the service's restart policy.
+    pub start_limit_burst: Option<u64>,
+}
+
+impl ServiceExtension {
+    /// Take and deserialize the `x-podlet` extension from a service's [`Extensions`].
+    ///
+    /// Returns the default if the extension is not present.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the extension could not be deserialized.
+    pub fn take_from(extensions: &mut Extensions) -> color_eyre::Result<Self> {
+        extensions
+            .shift_remove(KEY)
+            .map(|extension| {
+                serde_yaml::from_value(extension).wrap_err_with(|| format!("invalid `{KEY}`"))
+            })
+            .transpose()
+            .map(Option::unwrap_or_default)
+    }
+}
+
+/// Actions to take once a container transitions to an unhealthy state.
+///
+/// See the `--health-on-failure` [section](https://docs.podman.io/en/stable/markdown/podman-run.1.html#health-on-failure-action)
+/// of the `podman run` documentation.
+#[derive(ValueEnum, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthOnFailure {
+    /// Take no action.
+    None,
+    /// Kill the container.
+    Kill,
+    /// Restart the container.
+    Restart,
+    /// Stop the container.
+    Stop,
+}
+
+impl AsRef<str> for HealthOnFailure {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::None => "none",
+            Self::Kill => "kill",
+            Self::Restart => "restart",
+            Self::Stop => "stop",
+        }
+    }
+}
+
+impl Display for HealthOnFailure {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        tests::{convert, convert_to_string, parse_args},
+        Compose,
+    };
+
+    #[test]
+    fn service_environment_file_extension() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                restart: always
+                x-podlet:
+                  service:
+                    environment_file:
+                      - /etc/app/systemd.env
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        let (container, service) = quadlet
+            .split_once("[Service]\n")
+            .expect("quadlet file has a [Service] section");
+        assert!(!container.contains("EnvironmentFile="), "{quadlet}");
+        assert_eq!(
+            service,
+            "Restart=always\nEnvironmentFile=/etc/app/systemd.env\n"
+        );
+    }
+
+    #[test]
+    fn podman_args_extension() {
+        let yaml = "
+            services:
+              test:
+                image: image
+                init: true
+                privileged: true
+                x-podlet:
+                  podman_args:
+                    - --tz Europe/Paris
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(
+            quadlet.contains("PodmanArgs=--privileged --tz Europe/Paris\n"),
+            "{quadlet}"
+        );
+    }
+
+    #[test]
+    fn existing_pod_extension() {
+        let yaml = "
+            name: test
+            services:
+              app:
+                image: image
+                x-podlet:
+                  pod: existing.pod
+        ";
+
+        let files = convert(Compose::default(), yaml).expect("compose file converts");
+        assert_eq!(
+            files.len(),
+            1,
+            "only a container file is created: {files:?}"
+        );
+        let quadlet: String = files.iter().map(ToString::to_string).collect();
+        assert!(quadlet.contains("Pod=existing.pod\n"), "{quadlet}");
+
+        let error = convert(parse_args(&["--pod"]).expect("valid args"), yaml)
+            .expect_err("`--pod` conflicts");
+        assert!(
+            format!("{error:?}").contains("cannot be used with `--pod`"),
+            "{error:?}"
+        );
+    }
+
+    #[test]
+    fn unknown_extension_option() {
+        let yaml = "
+            services:
+              test:
+                image: image
+                x-podlet:
+                  unknown: true
+        ";
+
+        assert!(convert(Compose::default(), yaml).is_err());
+    }
+
+    #[test]
+    fn read_only_tmpfs() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                read_only: true
+              no-tmpfs:
+                image: image
+                read_only: true
+                x-podlet:
+                  read_only_tmpfs: false
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(
+            quadlet.contains("ReadOnly=true\nReadOnlyTmpfs=true\n"),
+            "{quadlet}"
+        );
+        assert!(
+            quadlet.contains("ReadOnly=true\nReadOnlyTmpfs=false\n"),
+            "{quadlet}"
+        );
+    }
+
+    #[test]
+    fn start_limit() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                restart: on-failure:3
+                x-podlet:
+                  service:
+                    start_limit_interval: 1m30s
+                    start_limit_burst: 5
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(
+            quadlet.contains("[Unit]\nStartLimitIntervalSec=1m30s\nStartLimitBurst=5\n"),
+            "{quadlet}"
+        );
+
+        let invalid = "
+            services:
+              app:
+                image: image
+                x-podlet:
+                  service:
+                    start_limit_interval: soon
+        ";
+        assert!(convert(Compose::default(), invalid).is_err());
+    }
+}
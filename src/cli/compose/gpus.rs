@@ -0,0 +1,233 @@
+//! Support for the `gpus` shorthand of compose services.
+//!
+//! [`compose_spec`] does not accept the `gpus` field, added in Docker Compose v2.30. So, before the
+//! compose file is deserialized, each GPU is moved into the service's
+//! `deploy.resources.reservations.devices`, from which GPUs are already converted.
+
+use color_eyre::{eyre::eyre, Section};
+use serde_yaml::{Mapping, Value};
+
+/// Move the `gpus` of each service in a compose file's YAML `value` into the service's
+/// `deploy.resources.reservations.devices`.
+///
+/// `gpus: all` becomes a device reserving all GPUs. Each device in the list form is given the
+/// `gpu` capability if it does not already have capabilities.
+///
+/// # Errors
+///
+/// Returns an error if `gpus` is not `all` or a list of devices, or the service's
+/// `deploy.resources.reservations.devices` is not a list.
+pub fn move_into_reservations(value: &mut Value) -> color_eyre::Result<()> {
+    let Some(Value::Mapping(services)) = value.get_mut("services") else {
+        return Ok(());
+    };
+
+    for (name, service) in services {
+        let Some(service) = service.as_mapping_mut() else {
+            continue;
+        };
+        let Some(gpus) = service.remove("gpus") else {
+            continue;
+        };
+        let name = name.as_str().unwrap_or_default();
+
+        let devices = match gpus {
+            Value::String(gpus) if gpus == "all" => vec![gpu_device(Mapping::from_iter([(
+                "count".into(),
+                "all".into(),
+            )]))],
+            Value::Sequence(devices) => devices
+                .into_iter()
+                .map(|device| match device {
+                    Value::Mapping(device) => Ok(gpu_device(device)),
+                    _ => Err(eyre!(
+                        "each device in `gpus` of service `{name}` must be a mapping"
+                    )),
+                })
+                .collect::<color_eyre::Result<_>>()?,
+            _ => {
+                return Err(eyre!("invalid `gpus` for service `{name}`")
+                    .suggestion("use `all` or a list of devices"));
+            }
+        };
+
+        let reservations = ["deploy", "resources", "reservations"]
+            .into_iter()
+            .try_fold(&mut *service, |mapping, key| {
+                mapping
+                    .entry(key.into())
+                    .or_insert_with(|| Mapping::new().into())
+                    .as_mapping_mut()
+                    .ok_or_else(|| {
+                        eyre!("`{key}` of service `{name}` must be a mapping to use `gpus`")
+                    })
+            })?;
+        let Value::Sequence(reserved_devices) = reservations
+            .entry("devices".into())
+            .or_insert_with(|| Value::Sequence(Vec::new()))
+        else {
+            return Err(eyre!(
+                "`deploy.resources.reservations.devices` of service `{name}` must be a list \
+                    to use `gpus`"
+            ));
+        };
+        reserved_devices.extend(devices);
+    }
+
+    Ok(())
+}
+
+/// Add the `gpu` capability to a `device` if it does not have capabilities.
+fn gpu_device(mut device: Mapping) -> Value {
+    if !device.contains_key("capabilities") {
+        device.insert("capabilities".into(), vec![Value::from("gpu")].into());
+    }
+    device.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::warning;
+
+    use super::super::{
+        tests::{convert, convert_to_string, parse_args},
+        Compose,
+    };
+
+    #[test]
+    fn gpu_reservation() {
+        let yaml = "
+            name: test
+            services:
+              gpu:
+                image: image
+                deploy:
+                  resources:
+                    reservations:
+                      devices:
+                        - driver: nvidia
+                          count: 1
+                          capabilities: [gpu]
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(
+            quadlet.contains("PodmanArgs=--device 'nvidia.com/gpu=0'\n"),
+            "{quadlet}"
+        );
+
+        let kube = convert_to_string(
+            Compose {
+                kube: true,
+                ..Compose::default()
+            },
+            yaml,
+        );
+        assert!(kube.contains("nvidia.com/gpu: '1'"), "{kube}");
+    }
+
+    #[test]
+    fn gpu_reservation_all() {
+        let yaml = "
+            services:
+              gpu:
+                image: image
+                deploy:
+                  resources:
+                    reservations:
+                      devices:
+                        - capabilities: [gpu]
+                          count: all
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(
+            quadlet.contains("PodmanArgs=--device 'nvidia.com/gpu=all'\n"),
+            "{quadlet}"
+        );
+    }
+
+    #[test]
+    fn gpus() {
+        let kube = parse_args(&["--kube"]).expect("args are valid");
+
+        let yaml = "
+            name: gpus
+            services:
+              trainer:
+                image: trainer
+                gpus: all
+        ";
+        let trainer = convert_to_string(Compose::default(), yaml);
+        assert!(
+            trainer.contains("PodmanArgs=--device 'nvidia.com/gpu=all'\n"),
+            "{trainer}"
+        );
+        // Kubernetes GPU limits must be a number.
+        assert!(convert(kube.clone(), yaml).is_err());
+
+        let yaml = "
+            name: gpus
+            services:
+              inference:
+                image: inference
+                gpus:
+                  - count: 2
+                    driver: nvidia
+              pinned:
+                image: pinned
+                gpus:
+                  - device_ids: [GPU-1234]
+        ";
+        let files = convert_to_string(Compose::default(), yaml);
+        assert!(
+            files.contains("PodmanArgs=--device 'nvidia.com/gpu=0' --device 'nvidia.com/gpu=1'\n"),
+            "{files}"
+        );
+        assert!(
+            files.contains("PodmanArgs=--device 'nvidia.com/gpu=GPU-1234'\n"),
+            "{files}"
+        );
+
+        let yaml = "
+            name: gpus
+            services:
+              inference:
+                image: inference
+                gpus:
+                  - count: 2
+                    driver: nvidia
+        ";
+        let pod = convert_to_string(kube, yaml);
+        assert!(pod.contains("nvidia.com/gpu: '2'"), "{pod}");
+    }
+
+    #[test]
+    fn nvidia_runtime() {
+        let yaml = "
+            services:
+              inference:
+                image: inference
+                runtime: nvidia
+                environment:
+                  NVIDIA_VISIBLE_DEVICES: all
+        ";
+
+        warning::take();
+        let inference = convert_to_string(Compose::default(), yaml);
+        assert!(
+            inference.contains("Environment=NVIDIA_VISIBLE_DEVICES=all\n"),
+            "{inference}"
+        );
+        assert!(
+            inference.contains("PodmanArgs=--device 'nvidia.com/gpu=all'\n"),
+            "{inference}"
+        );
+        assert!(!inference.contains("runtime"), "{inference}");
+
+        let warnings = warning::take();
+        assert_eq!(warnings.len(), 1, "{warnings:?}");
+        let warning = warnings.first().expect("one warning");
+        assert!(warning.contains("legacy `nvidia` runtime"), "{warning}");
+    }
+}
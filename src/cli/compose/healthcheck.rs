@@ -0,0 +1,29 @@
+//! Docker's defaults for compose [`Healthcheck`] options.
+
+use std::time::Duration;
+
+use compose_spec::service::{healthcheck::Command, Healthcheck};
+
+/// Docker's default `interval` and `timeout` of a healthcheck.
+const DEFAULT_DURATION: Duration = Duration::from_secs(30);
+
+/// Docker's default `retries` of a healthcheck.
+const DEFAULT_RETRIES: u64 = 3;
+
+/// Set Docker's defaults for the `interval`, `timeout`, and `retries` of the `healthcheck`,
+/// unless they are already set.
+///
+/// Podman's defaults are used when the options are not set, which may differ from Docker's.
+pub fn set_docker_defaults(healthcheck: &mut Option<Healthcheck>) {
+    if let Some(Healthcheck::Command(Command {
+        interval,
+        timeout,
+        retries,
+        ..
+    })) = healthcheck
+    {
+        interval.get_or_insert(DEFAULT_DURATION);
+        timeout.get_or_insert(DEFAULT_DURATION);
+        retries.get_or_insert(DEFAULT_RETRIES);
+    }
+}
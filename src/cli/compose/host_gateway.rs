@@ -0,0 +1,154 @@
+//! Support for the `host-gateway` special value of compose service `extra_hosts`.
+//!
+//! [`compose_spec`] only accepts IP addresses in `extra_hosts`. So, before the compose file is
+//! deserialized, hostnames mapped to `host-gateway` are moved into the service's
+//! `x-podlet-host-gateway` extension.
+
+use color_eyre::eyre::WrapErr;
+use compose_spec::{Extensions, Identifier, Service};
+use indexmap::IndexMap;
+use serde_yaml::Value;
+
+use crate::warning;
+
+/// Extension key the hostnames mapped to `host-gateway` are moved to.
+const KEY: &str = "x-podlet-host-gateway";
+
+/// Special `extra_hosts` value which resolves to the IP address of the host.
+pub const HOST_GATEWAY: &str = "host-gateway";
+
+/// Move the hostnames mapped to `host-gateway` in the `extra_hosts` of each service in a compose
+/// file's YAML `value` into the service's `x-podlet-host-gateway` extension.
+pub fn move_entries(value: &mut Value) {
+    let Some(Value::Mapping(services)) = value.get_mut("services") else {
+        return;
+    };
+
+    for service in services.values_mut() {
+        let Some(service) = service.as_mapping_mut() else {
+            continue;
+        };
+        let Some(extra_hosts) = service.get_mut("extra_hosts") else {
+            continue;
+        };
+
+        let mut hostnames = Vec::new();
+        match extra_hosts {
+            Value::Sequence(extra_hosts) => extra_hosts.retain(|extra_host| {
+                let hostname = extra_host.as_str().and_then(|extra_host| {
+                    extra_host
+                        .split_once(['=', ':'])
+                        .filter(|(_, ip)| *ip == HOST_GATEWAY)
+                        .map(|(hostname, _)| hostname)
+                });
+                if let Some(hostname) = hostname {
+                    hostnames.push(Value::from(hostname));
+                }
+                hostname.is_none()
+            }),
+            Value::Mapping(extra_hosts) => extra_hosts.retain(|hostname, ip| {
+                let is_host_gateway = ip.as_str() == Some(HOST_GATEWAY);
+                if is_host_gateway {
+                    hostnames.push(hostname.clone());
+                }
+                !is_host_gateway
+            }),
+            _ => {}
+        }
+
+        if !hostnames.is_empty() {
+            service.insert(KEY.into(), hostnames.into());
+        }
+    }
+}
+
+/// Take the hostnames mapped to `host-gateway` from a service's [`Extensions`].
+///
+/// # Errors
+///
+/// Returns an error if the hostnames could not be deserialized.
+pub fn take_from(extensions: &mut Extensions) -> color_eyre::Result<Vec<String>> {
+    extensions
+        .shift_remove(KEY)
+        .map(|hostnames| {
+            serde_yaml::from_value(hostnames)
+                .wrap_err("invalid `extra_hosts` mapped to `host-gateway`")
+        })
+        .transpose()
+        .map(Option::unwrap_or_default)
+}
+
+/// Remove the hostnames mapped to `host-gateway` from each service, warning that they are only
+/// supported when converting to Quadlet files.
+pub fn remove(services: &mut IndexMap<Identifier, Service>) {
+    for (name, service) in services {
+        let Some(Value::Sequence(hostnames)) = service.extensions.shift_remove(KEY) else {
+            continue;
+        };
+        for hostname in hostnames.iter().filter_map(Value::as_str) {
+            warning::warn(format_args!(
+                "`extra_hosts` entry `{hostname}:{HOST_GATEWAY}` of service `{name}` is only \
+                    supported with `--format quadlet`, ignoring it"
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::warning;
+
+    use super::super::{
+        tests::{convert_to_string, parse_args},
+        Compose,
+    };
+
+    #[test]
+    fn host_aliases() {
+        let yaml = "
+            name: hosts
+            services:
+              app:
+                image: app
+                extra_hosts:
+                  - db.internal:10.0.0.5
+                  - cache.internal=10.0.0.5
+                  - host.docker.internal:host-gateway
+              worker:
+                image: worker
+                extra_hosts:
+                  metrics.internal: 10.0.0.6
+        ";
+
+        warning::take();
+        let kube = parse_args(&["--kube"]).expect("args are valid");
+        let pod = convert_to_string(kube, yaml);
+        assert!(
+            pod.contains(
+                "  hostAliases:
+  - hostnames:
+    - db.internal
+    - cache.internal
+    ip: 10.0.0.5
+  - hostnames:
+    - metrics.internal
+    ip: 10.0.0.6
+"
+            ),
+            "{pod}"
+        );
+        assert!(!pod.contains("host-gateway"), "{pod}");
+        let warnings = warning::take();
+        assert!(
+            matches!(warnings.as_slice(), [warning] if warning.contains("host.docker.internal")),
+            "{warnings:?}"
+        );
+
+        let app = convert_to_string(Compose::default(), yaml);
+        assert!(
+            app.contains("--add-host host.docker.internal:host-gateway"),
+            "{app}"
+        );
+        assert!(app.contains("--add-host db.internal:10.0.0.5"), "{app}");
+    }
+}
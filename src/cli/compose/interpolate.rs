@@ -0,0 +1,160 @@
+//! Interpolation of variables in compose file values.
+
+use std::{iter::Peekable, str::Chars};
+
+use color_eyre::eyre::{bail, eyre};
+
+use crate::warning;
+
+/// Interpolate variables in `string`, following the compose specification, getting their values
+/// with `lookup`.
+///
+/// Supports `$VAR`, `${VAR}`, `${VAR:-default}`, `${VAR-default}`, `${VAR:?error}`,
+/// `${VAR?error}`, `${VAR:+replacement}`, and `${VAR+replacement}`. Defaults and replacements
+/// are themselves interpolated. `$$` is an escaped `$`. Unset variables are replaced with an empty
+/// string and a warning is emitted.
+///
+/// # Errors
+///
+/// Returns an error if a `${` is not closed, a variable has an invalid name or modifier, or a
+/// required variable is not set.
+pub fn interpolate_with(
+    string: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> color_eyre::Result<String> {
+    let mut output = String::with_capacity(string.len());
+    let mut chars = string.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        if char != '$' {
+            output.push(char);
+            continue;
+        }
+
+        match chars.peek() {
+            Some('$') => {
+                chars.next();
+                output.push('$');
+            }
+            Some('{') => {
+                chars.next();
+                let braced =
+                    take_braced(&mut chars).ok_or_else(|| eyre!("unclosed `${{` in `{string}`"))?;
+                output.push_str(&interpolate_braced(&braced, lookup)?);
+            }
+            Some(char) if is_name_start(*char) => {
+                let mut name = String::new();
+                while let Some(char) = chars.next_if(|char| is_name_char(*char)) {
+                    name.push(char);
+                }
+                output.push_str(&lookup_or_warn(&name, lookup));
+            }
+            _ => output.push('$'),
+        }
+    }
+
+    Ok(output)
+}
+
+/// Take the contents of a `${...}` after the opening `${`, up to the matching `}`.
+///
+/// Returns [`None`] if there is no matching `}`.
+fn take_braced(chars: &mut Peekable<Chars>) -> Option<String> {
+    let mut braced = String::new();
+    let mut depth = 0_usize;
+    for char in chars {
+        match char {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(braced),
+            '}' => depth -= 1,
+            _ => {}
+        }
+        braced.push(char);
+    }
+    None
+}
+
+/// Interpolate the contents of a `${...}`, i.e. a variable name and an optional modifier.
+fn interpolate_braced(
+    braced: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> color_eyre::Result<String> {
+    let name_len = braced
+        .find(|char| !is_name_char(char))
+        .unwrap_or(braced.len());
+    let (name, modifier) = braced.split_at(name_len);
+    if !name.starts_with(is_name_start) {
+        bail!("invalid variable name in `${{{braced}}}`");
+    }
+
+    let value = lookup(name);
+    let (unset, argument) = if let Some(argument) = modifier.strip_prefix(':') {
+        (value.as_deref().is_none_or(str::is_empty), argument)
+    } else {
+        (value.is_none(), modifier)
+    };
+
+    let mut argument_chars = argument.chars();
+    match argument_chars.next() {
+        None => Ok(lookup_or_warn(name, lookup)),
+        Some('-') if unset => interpolate_with(argument_chars.as_str(), lookup),
+        Some('?') if unset => Err(eyre!(
+            "required variable `{name}` is not set: {}",
+            interpolate_with(argument_chars.as_str(), lookup)?
+        )),
+        Some('-' | '?') => Ok(value.unwrap_or_default()),
+        Some('+') if unset => Ok(String::new()),
+        Some('+') => interpolate_with(argument_chars.as_str(), lookup),
+        Some(_) => bail!("invalid modifier in `${{{braced}}}`"),
+    }
+}
+
+/// Get the value of the variable `name`, warning and returning an empty string if it is not set.
+fn lookup_or_warn(name: &str, lookup: &dyn Fn(&str) -> Option<String>) -> String {
+    lookup(name).unwrap_or_else(|| {
+        warning::warn(format_args!(
+            "variable `{name}` is not set, using an empty string"
+        ));
+        String::new()
+    })
+}
+
+/// Returns `true` if `char` can start a variable name.
+fn is_name_start(char: char) -> bool {
+    char.is_ascii_alphabetic() || char == '_'
+}
+
+/// Returns `true` if `char` can be part of a variable name.
+fn is_name_char(char: char) -> bool {
+    char.is_ascii_alphanumeric() || char == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modifiers() {
+        let lookup = |name: &str| match name {
+            "SET" => Some("set".to_owned()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        };
+        let interpolate = |string| interpolate_with(string, &lookup).expect("valid interpolation");
+
+        assert_eq!(interpolate("$SET ${SET} $$SET"), "set set $SET");
+        assert_eq!(
+            interpolate("${UNSET:-default} ${EMPTY:-default}"),
+            "default default"
+        );
+        assert_eq!(interpolate("${UNSET-default} ${EMPTY-default}"), "default ");
+        assert_eq!(
+            interpolate("${SET:+alt} ${EMPTY:+alt} ${EMPTY+alt}"),
+            "alt  alt"
+        );
+        assert_eq!(interpolate("${UNSET:-${SET}}"), "set");
+
+        assert!(interpolate_with("${UNSET:?required}", &lookup).is_err());
+        assert!(interpolate_with("${SET", &lookup).is_err());
+    }
+}
@@ -0,0 +1,440 @@
+//! Handling of labels in a [`Compose`] file.
+//!
+//! Service labels are interpolated and merged with their `label_file`s before deserialization, and
+//! Docker Compose specific labels, and `deploy.labels`, are removed after.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use color_eyre::eyre::{bail, eyre, WrapErr};
+use compose_spec::{Compose, ListOrMap, Resource};
+use indexmap::IndexMap;
+use serde_yaml::{Mapping, Value};
+
+use crate::warning;
+
+use super::interpolate::interpolate_with;
+
+/// Prefix of labels Docker Compose adds to the objects it creates, e.g.
+/// `com.docker.compose.project`.
+const DOCKER_LABEL_PREFIX: &str = "com.docker.compose.";
+
+/// Remove Docker Compose specific labels from the services, networks, volumes, and configs of a
+/// [`Compose`] file.
+///
+/// These labels are used by Docker Compose to track the objects it creates and are meaningless to
+/// Podman.
+pub fn remove_docker_labels(compose: &mut Compose) {
+    for service in compose.services.values_mut() {
+        retain_non_docker_labels(&mut service.labels);
+    }
+    for network in compose.networks.values_mut().flatten() {
+        if let Resource::Compose(network) = network {
+            retain_non_docker_labels(&mut network.labels);
+        }
+    }
+    for volume in compose.volumes.values_mut().flatten() {
+        if let Resource::Compose(volume) = volume {
+            retain_non_docker_labels(&mut volume.labels);
+        }
+    }
+    for config in compose.configs.values_mut() {
+        if let Resource::Compose(config) = config {
+            retain_non_docker_labels(&mut config.labels);
+        }
+    }
+}
+
+/// Remove `deploy.labels` from each service of a [`Compose`] file, warning for each service they
+/// are removed from.
+///
+/// Unlike a service's `labels`, which are set on its container, `deploy.labels` are set on the
+/// service by an orchestrator. Podlet does not create orchestrated services, so there is nothing
+/// to set them on.
+pub fn remove_deploy_labels(compose: &mut Compose) {
+    for (name, service) in &mut compose.services {
+        let Some(deploy) = service.deploy.as_mut() else {
+            continue;
+        };
+        if deploy.labels.is_empty() {
+            continue;
+        }
+
+        deploy.labels = ListOrMap::default();
+        warning::warn(format_args!(
+            "service `{name}` sets `deploy.labels`, which are not container labels, \
+                podlet does not create orchestrated services so they are ignored"
+        ));
+
+        if deploy.is_empty() {
+            service.deploy = None;
+        }
+    }
+}
+
+/// Remove labels starting with [`DOCKER_LABEL_PREFIX`].
+fn retain_non_docker_labels(labels: &mut ListOrMap) {
+    match labels {
+        ListOrMap::List(labels) => labels.retain(|label| !label.starts_with(DOCKER_LABEL_PREFIX)),
+        ListOrMap::Map(labels) => {
+            labels.retain(|key, _| !key.as_str().starts_with(DOCKER_LABEL_PREFIX));
+        }
+    }
+}
+
+/// Interpolate the `labels` of each service in a compose file's YAML `value` and merge in the
+/// labels read from their `label_file`s.
+///
+/// Inline `labels` take precedence over labels from a `label_file`. Relative `label_file` paths are
+/// resolved from the directory of the compose file at `path`, which is [`None`] if the compose file
+/// was read from stdin.
+///
+/// # Errors
+///
+/// Returns an error if a label could not be interpolated, or a `label_file` is invalid or could
+/// not be read.
+pub fn resolve_service_labels(value: &mut Value, path: Option<&Path>) -> color_eyre::Result<()> {
+    resolve_service_labels_with(value, path, &|name| env::var(name).ok())
+}
+
+/// Interpolate and merge service labels, like [`resolve_service_labels()`], getting the values of
+/// variables with `lookup`.
+fn resolve_service_labels_with(
+    value: &mut Value,
+    path: Option<&Path>,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> color_eyre::Result<()> {
+    let Some(Value::Mapping(services)) = value.get_mut("services") else {
+        return Ok(());
+    };
+
+    for (name, service) in services {
+        let Some(service) = service.as_mapping_mut() else {
+            continue;
+        };
+        let name = name.as_str().unwrap_or_default();
+
+        if let Some(labels) = service.get_mut("labels") {
+            interpolate_labels(labels, lookup)
+                .wrap_err_with(|| format!("error interpolating `labels` of service `{name}`"))?;
+        }
+
+        if let Some(label_files) = service.remove("label_file") {
+            let file_labels = read_label_files(label_files, path)
+                .wrap_err_with(|| format!("error reading `label_file` of service `{name}`"))?;
+            let labels = service
+                .entry("labels".into())
+                .or_insert_with(|| Mapping::new().into());
+            merge_file_labels(labels, file_labels)
+                .wrap_err_with(|| format!("error merging `label_file` of service `{name}`"))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Interpolate label values, or whole `key=value` labels in list form.
+fn interpolate_labels(
+    labels: &mut Value,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> color_eyre::Result<()> {
+    let values: Box<dyn Iterator<Item = &mut Value>> = match labels {
+        Value::Mapping(labels) => Box::new(labels.values_mut()),
+        Value::Sequence(labels) => Box::new(labels.iter_mut()),
+        _ => return Ok(()),
+    };
+    for value in values {
+        if let Value::String(string) = value {
+            *string = interpolate_with(string, lookup)?;
+        }
+    }
+    Ok(())
+}
+
+/// Read the labels from a service's `label_file`, a single path or a list of paths.
+///
+/// Later files take precedence over earlier ones.
+fn read_label_files(
+    label_files: Value,
+    path: Option<&Path>,
+) -> color_eyre::Result<IndexMap<String, String>> {
+    let label_files = match label_files {
+        Value::String(label_file) => vec![label_file],
+        Value::Sequence(label_files) => label_files
+            .into_iter()
+            .map(|label_file| match label_file {
+                Value::String(label_file) => Ok(label_file),
+                _ => Err(eyre!("`label_file` entries must be strings")),
+            })
+            .collect::<Result<_, _>>()?,
+        _ => bail!("`label_file` must be a string or a list of strings"),
+    };
+
+    let mut labels = IndexMap::new();
+    for label_file in label_files {
+        let label_file = resolve_path(label_file.as_ref(), path)?;
+        let contents = fs::read_to_string(&label_file)
+            .wrap_err_with(|| format!("error reading `{}`", label_file.display()))?;
+        labels.extend(
+            parse_label_file(&contents).map(|(key, value)| (key.to_owned(), value.to_owned())),
+        );
+    }
+
+    Ok(labels)
+}
+
+/// Parse the `key=value` lines of a `label_file`'s `contents`, skipping blank lines and comments.
+///
+/// A line without a `=` is a label with an empty value.
+fn parse_label_file(contents: &str) -> impl Iterator<Item = (&str, &str)> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| line.split_once('=').unwrap_or((line, "")))
+}
+
+/// Resolve a `label_file` path relative to the directory of the compose file at `path`.
+///
+/// # Errors
+///
+/// Returns an error if `label_file` is relative and `path` is [`None`].
+fn resolve_path(label_file: &Path, path: Option<&Path>) -> color_eyre::Result<PathBuf> {
+    if label_file.is_absolute() {
+        return Ok(label_file.to_owned());
+    }
+
+    let path = path.ok_or_else(|| {
+        eyre!(
+            "relative `label_file` path `{}` is not supported when reading the compose file \
+                from stdin",
+            label_file.display()
+        )
+    })?;
+    Ok(path.parent().unwrap_or(Path::new("")).join(label_file))
+}
+
+/// Merge `file_labels` into a service's `labels`, without overriding existing labels.
+fn merge_file_labels(
+    labels: &mut Value,
+    file_labels: IndexMap<String, String>,
+) -> color_eyre::Result<()> {
+    if labels.is_null() {
+        *labels = Mapping::new().into();
+    }
+
+    match labels {
+        Value::Mapping(labels) => {
+            for (key, value) in file_labels {
+                if !labels.contains_key(key.as_str()) {
+                    labels.insert(key.into(), value.into());
+                }
+            }
+        }
+        Value::Sequence(labels) => {
+            for (key, value) in file_labels {
+                let exists = labels
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .any(|label| label.split_once('=').map_or(label, |(label, _)| label) == key);
+                if !exists {
+                    labels.push(format!("{key}={value}").into());
+                }
+            }
+        }
+        _ => bail!("`labels` must be a mapping or a list"),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        tests::{convert, convert_to_string, parse_args},
+        Compose,
+    };
+    use super::*;
+
+    #[test]
+    fn deploy_labels() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                labels:
+                  app.role: web
+                deploy:
+                  labels:
+                    app.tier: frontend
+              worker:
+                image: image
+                deploy:
+                  labels:
+                    - app.tier=backend
+        ";
+
+        warning::take();
+        let files = convert(Compose::default(), yaml).expect("compose file converts");
+        let [app, worker] = files
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .try_into()
+            .expect("two files");
+
+        assert!(app.contains("Label=app.role=web\n"), "{app}");
+        assert!(!app.contains("app.tier"), "{app}");
+        assert!(!worker.contains("Label="), "{worker}");
+
+        let warnings = warning::take();
+        assert_eq!(warnings.len(), 2, "{warnings:?}");
+        assert!(
+            warnings
+                .iter()
+                .all(|warning| warning.contains("`deploy.labels`")),
+            "{warnings:?}"
+        );
+    }
+
+    #[test]
+    fn docker_labels() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                labels:
+                  com.docker.compose.project: project
+                  com.docker.compose.service: app
+                  com.docker.compose.version: 2.0.0
+                  label: value
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(!quadlet.contains("com.docker.compose"), "{quadlet}");
+        assert!(quadlet.contains("Label=label=value\n"), "{quadlet}");
+
+        let args = parse_args(&["--keep-docker-labels"]).expect("args are valid");
+        let quadlet = convert_to_string(args, yaml);
+        assert!(
+            quadlet.contains(
+                "Label=com.docker.compose.project=project com.docker.compose.service=app \
+                    com.docker.compose.version=2.0.0 label=value\n"
+            ),
+            "{quadlet}"
+        );
+    }
+
+    #[test]
+    fn label_interpolation() {
+        let mut value: Value = serde_yaml::from_str(
+            "
+            services:
+              app:
+                image: image
+                labels:
+                  version: ${VERSION}
+              list:
+                image: image
+                labels:
+                  - version=${VERSION}
+            ",
+        )
+        .expect("valid YAML");
+        let lookup = |name: &str| (name == "VERSION").then(|| "1.0".to_owned());
+        resolve_service_labels_with(&mut value, None, &lookup).expect("labels interpolate");
+
+        let quadlet = convert_to_string(
+            Compose::default(),
+            &serde_yaml::to_string(&value).expect("serializable"),
+        );
+        assert_eq!(
+            quadlet.matches("Label=version=1.0\n").count(),
+            2,
+            "{quadlet}"
+        );
+    }
+
+    #[test]
+    fn label_file() {
+        let contents = "
+            # Labels for the app service
+            version=file
+
+            team=podlet
+            empty
+        ";
+        assert_eq!(
+            parse_label_file(contents).collect::<Vec<_>>(),
+            [("version", "file"), ("team", "podlet"), ("empty", "")]
+        );
+
+        let file_labels: IndexMap<String, String> = parse_label_file(contents)
+            .map(|(key, value)| (key.to_owned(), value.to_owned()))
+            .collect();
+        let mut labels = serde_yaml::from_str("version: '1.0'").expect("valid YAML");
+        merge_file_labels(&mut labels, file_labels.clone()).expect("labels are a mapping");
+        assert_eq!(
+            labels,
+            serde_yaml::from_str::<Value>("{version: '1.0', team: podlet, empty: ''}")
+                .expect("valid YAML")
+        );
+
+        let mut labels = Value::Sequence(vec!["team=core".into()]);
+        merge_file_labels(&mut labels, file_labels).expect("labels are a list");
+        assert_eq!(
+            labels,
+            Value::Sequence(vec![
+                "team=core".into(),
+                "version=file".into(),
+                "empty=".into()
+            ])
+        );
+
+        let yaml = "
+            services:
+              app:
+                image: image
+                label_file: ./app.labels
+        ";
+        let error = convert(Compose::default(), yaml).expect_err("compose file is from stdin");
+        assert!(
+            format!("{error:?}").contains("relative `label_file` path `./app.labels`"),
+            "{error:?}"
+        );
+    }
+
+    #[test]
+    fn network_and_volume_labels() {
+        let yaml = "
+            services:
+              test:
+                image: image
+                networks: [network]
+                volumes:
+                  - volume:/data
+            networks:
+              network:
+                labels:
+                  one: 1
+                  two: 2
+            volumes:
+              volume:
+                labels:
+                  - three=3
+                  - four=4
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(
+            quadlet.contains("[Network]\nLabel=one=1 two=2\n"),
+            "{quadlet}"
+        );
+        assert!(
+            quadlet.contains("[Volume]\nLabel=three=3 four=4\n"),
+            "{quadlet}"
+        );
+    }
+}
@@ -0,0 +1,158 @@
+//! Support for the `post_start` and `pre_stop` lifecycle hooks of compose services.
+//!
+//! [`compose_spec`] does not accept the `post_start` and `pre_stop` fields, added in Docker
+//! Compose v2.30. So, before the compose file is deserialized, they are moved into the service's
+//! `x-podlet-post-start` and `x-podlet-pre-stop` extensions.
+
+use color_eyre::eyre::WrapErr;
+use compose_spec::{service::Command, Extensions, Identifier, ListOrMap, Service};
+use indexmap::IndexMap;
+use serde::Deserialize;
+use serde_yaml::Value;
+
+use crate::warning;
+
+/// Compose service fields for lifecycle hooks and the extension keys they are moved to.
+const FIELDS: [(&str, &str); 2] = [
+    ("post_start", "x-podlet-post-start"),
+    ("pre_stop", "x-podlet-pre-stop"),
+];
+
+/// Lifecycle hooks of a compose service.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Hooks {
+    /// Commands run after the container has started.
+    pub post_start: Vec<Hook>,
+
+    /// Commands run before the container is stopped.
+    pub pre_stop: Vec<Hook>,
+}
+
+/// A lifecycle hook command of a compose service.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Hook {
+    /// Command to run in the container.
+    pub command: Command,
+
+    /// User to run the command as.
+    pub user: Option<String>,
+
+    /// Whether to run the command with privileged access.
+    #[serde(default)]
+    pub privileged: bool,
+
+    /// Working directory to run the command in.
+    pub working_dir: Option<String>,
+
+    /// Environment variables set for the command.
+    #[serde(default)]
+    pub environment: ListOrMap,
+}
+
+impl Hooks {
+    /// Take and deserialize the lifecycle hooks from a service's [`Extensions`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a hook could not be deserialized.
+    pub fn take_from(extensions: &mut Extensions) -> color_eyre::Result<Self> {
+        let [post_start, pre_stop] = FIELDS.map(|(field, key)| {
+            extensions
+                .shift_remove(key)
+                .map(|hooks| {
+                    serde_yaml::from_value(hooks).wrap_err_with(|| format!("invalid `{field}`"))
+                })
+                .transpose()
+                .map(Option::unwrap_or_default)
+        });
+        Ok(Self {
+            post_start: post_start?,
+            pre_stop: pre_stop?,
+        })
+    }
+}
+
+/// Move the `post_start` and `pre_stop` fields of each service in a compose file's YAML `value`
+/// into the service's `x-podlet-post-start` and `x-podlet-pre-stop` extensions.
+pub fn move_hooks(value: &mut Value) {
+    let Some(Value::Mapping(services)) = value.get_mut("services") else {
+        return;
+    };
+
+    for service in services.values_mut() {
+        let Some(service) = service.as_mapping_mut() else {
+            continue;
+        };
+        for (field, key) in FIELDS {
+            if let Some(hooks) = service.remove(field) {
+                service.insert(key.into(), hooks);
+            }
+        }
+    }
+}
+
+/// Remove the lifecycle hooks from each service, warning that they are only supported when
+/// converting to Kubernetes YAML.
+pub fn remove(services: &mut IndexMap<Identifier, Service>) {
+    for (name, service) in services {
+        for (field, key) in FIELDS {
+            if service.extensions.shift_remove(key).is_some() {
+                warning::warn(format_args!(
+                    "`{field}` of service `{name}` is only supported with `--format kube`, \
+                        ignoring it"
+                ));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::warning;
+
+    use super::super::{
+        tests::{convert_to_string, parse_args},
+        Compose,
+    };
+
+    #[test]
+    fn lifecycle_hooks() {
+        let yaml = "
+            name: hooks
+            services:
+              app:
+                image: app
+                post_start:
+                  - command: ./warm-cache.sh --all
+                pre_stop:
+                  - command: [./drain.sh, --timeout, \"30\"]
+        ";
+
+        let kube = parse_args(&["--kube"]).expect("args are valid");
+        let pod = convert_to_string(kube, yaml);
+        assert!(
+            pod.contains(
+                "    lifecycle:
+      postStart:
+        exec:
+          command:
+          - ./warm-cache.sh
+          - --all
+      preStop:
+        exec:
+          command:
+          - ./drain.sh
+          - --timeout
+          - '30'
+"
+            ),
+            "{pod}"
+        );
+
+        warning::take();
+        let app = convert_to_string(Compose::default(), yaml);
+        assert!(!app.contains("drain"), "{app}");
+        assert_eq!(warning::take().len(), 2);
+    }
+}
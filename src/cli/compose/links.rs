@@ -0,0 +1,180 @@
+//! Conversion of legacy compose service `links` into network aliases.
+
+use std::mem;
+
+use color_eyre::eyre::{eyre, WrapErr};
+use compose_spec::{
+    service::{Hostname, Link, NetworkConfig},
+    Identifier, Service,
+};
+use indexmap::{IndexMap, IndexSet};
+
+use crate::warning;
+
+/// Convert the `links` of each service into network aliases of the linked services.
+///
+/// A link like `db:database` adds `database` as an alias of the `db` service on each of its
+/// networks. Links without an alias are unnecessary, as services on a shared network can already
+/// reach each other by name, so a warning is emitted instead.
+///
+/// # Errors
+///
+/// Returns an error if a link is to a service which is not defined or an alias is not a valid
+/// hostname.
+pub fn links_into_aliases(services: &mut IndexMap<Identifier, Service>) -> color_eyre::Result<()> {
+    let mut aliases: IndexMap<Identifier, IndexSet<Hostname>> = IndexMap::new();
+    for (name, service) in &mut *services {
+        for Link {
+            service: linked,
+            alias,
+        } in mem::take(&mut service.links)
+        {
+            match alias.filter(|alias| alias != linked.as_str()) {
+                Some(alias) => {
+                    let alias = Hostname::new(alias).wrap_err_with(|| {
+                        format!("invalid alias in link from service `{name}` to `{linked}`")
+                    })?;
+                    aliases.entry(linked).or_default().insert(alias);
+                }
+                None => warning::warn(format_args!(
+                    "service `{name}` links to `{linked}` without an alias, which is unnecessary \
+                        as services on a shared network can reach each other by name, ignoring it"
+                )),
+            }
+        }
+    }
+
+    for (linked, aliases) in aliases {
+        let service = services
+            .get_mut(&linked)
+            .ok_or_else(|| eyre!("linked service `{linked}` is not defined"))?;
+
+        let Some(NetworkConfig::Networks(networks)) = &mut service.network_config else {
+            warning::warn(format_args!(
+                "service `{linked}` is not attached to any `networks`, \
+                    ignoring its link aliases: {}",
+                join_aliases(&aliases),
+            ));
+            continue;
+        };
+
+        let mut long: IndexMap<_, _> = mem::take(networks).into_long();
+        for network in long.values_mut() {
+            network
+                .get_or_insert_with(Default::default)
+                .aliases
+                .extend(aliases.iter().cloned());
+        }
+        *networks = long.into();
+    }
+
+    Ok(())
+}
+
+/// Remove the `links` of each service, warning that they are not needed as the services'
+/// containers share the network namespace of a pod.
+pub fn remove_links_in_pod(services: &mut IndexMap<Identifier, Service>) {
+    for (name, service) in services {
+        for Link {
+            service: linked, ..
+        } in mem::take(&mut service.links)
+        {
+            warning::warn(format_args!(
+                "service `{name}` links to `{linked}`, links are not supported in a pod \
+                    as its containers share a network namespace, use `localhost` to reach \
+                    `{linked}` instead"
+            ));
+        }
+    }
+}
+
+/// Join `aliases` into a comma separated list for use in a warning.
+fn join_aliases(aliases: &IndexSet<Hostname>) -> String {
+    aliases
+        .iter()
+        .map(|alias| format!("`{alias}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::File;
+
+    use super::super::{
+        tests::{convert, convert_to_string, parse_args},
+        Compose,
+    };
+    use super::*;
+
+    #[test]
+    fn links() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                links:
+                  - db:database
+                  - cache
+                networks:
+                  - backend
+              db:
+                image: image
+                networks:
+                  - backend
+              cache:
+                image: image
+                networks:
+                  - backend
+            networks:
+              backend: {}
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(
+            quadlet.contains("Network=backend.network:alias=database\n"),
+            "{quadlet}"
+        );
+        assert!(!quadlet.contains("alias=cache"), "{quadlet}");
+
+        let [warning]: [String; 1] = warning::take().try_into().expect("one warning");
+        assert!(
+            warning.contains("links to `cache` without an alias"),
+            "{warning}"
+        );
+    }
+
+    #[test]
+    fn link_networks() {
+        let yaml = "
+            services:
+              web:
+                image: web
+                networks:
+                  frontend:
+                    aliases: [www]
+            networks:
+              frontend:
+                driver: bridge
+        ";
+
+        let args = parse_args(&["--link-networks"]).expect("args are valid");
+        let files = convert(args, yaml).expect("compose file converts");
+        let names: Vec<&str> = files.iter().map(File::name).collect();
+        assert_eq!(names, ["web", "frontend"]);
+        let web = files.first().expect("container file").to_string();
+        assert!(
+            web.contains("Network=frontend.network:alias=www\n"),
+            "{web}"
+        );
+
+        warning::take();
+        let args = parse_args(&["--link-networks", "--no-link-networks"]).expect("args are valid");
+        let files = convert(args, yaml).expect("compose file converts");
+        let names: Vec<&str> = files.iter().map(File::name).collect();
+        assert_eq!(names, ["web"]);
+        let web = files.first().expect("container file").to_string();
+        assert!(web.contains("Network=frontend:alias=www\n"), "{web}");
+        assert_eq!(warning::take().len(), 1);
+    }
+}
@@ -0,0 +1,79 @@
+//! Masking and unmasking of paths in a container, set with `--mask`/`--unmask` or the `mask` and
+//! `unmask` options of the `x-podlet` extension.
+
+use color_eyre::eyre::{ensure, WrapErr};
+
+use crate::quadlet::{self, container::Unmask};
+
+/// Ensure each path is absolute.
+///
+/// If `allow_all` is `true`, `ALL` is also accepted, for unmasking all paths.
+///
+/// # Errors
+///
+/// Returns an error if a path is not absolute.
+pub fn validate<'a>(
+    paths: impl IntoIterator<Item = &'a String>,
+    allow_all: bool,
+) -> color_eyre::Result<()> {
+    for path in paths {
+        ensure!(
+            path.starts_with('/') || (allow_all && path.eq_ignore_ascii_case("all")),
+            "path `{path}` is not absolute"
+        );
+    }
+    Ok(())
+}
+
+/// Add `mask` and `unmask` paths to the [`quadlet::Container`], converts to `Mask=` and `Unmask=`.
+///
+/// # Errors
+///
+/// Returns an error if a path is not absolute.
+pub fn set(
+    container: &mut quadlet::Container,
+    mask: Vec<String>,
+    unmask: Vec<String>,
+) -> color_eyre::Result<()> {
+    validate(&mask, false).wrap_err("invalid `mask` path")?;
+    validate(&unmask, true).wrap_err("invalid `unmask` path")?;
+
+    for path in mask {
+        if !container.mask.contains(&path) {
+            container.mask.push(path);
+        }
+    }
+    if !unmask.is_empty() {
+        container
+            .unmask
+            .get_or_insert_with(Unmask::new)
+            .extend(unmask);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{convert, convert_to_string, parse_args};
+
+    #[test]
+    fn mask() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                x-podlet:
+                  unmask:
+                    - /sys/firmware
+        ";
+
+        let args = parse_args(&["--mask", "/proc/kcore"]).expect("args are valid");
+        let app = convert_to_string(args, yaml);
+        assert!(app.contains("Mask=/proc/kcore\n"), "{app}");
+        assert!(app.contains("Unmask=/sys/firmware\n"), "{app}");
+
+        let args = parse_args(&["--mask", "proc/kcore"]).expect("args are valid");
+        assert!(convert(args, yaml).is_err());
+    }
+}
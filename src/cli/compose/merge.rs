@@ -0,0 +1,325 @@
+//! Merging of multiple YAML documents into a single compose file.
+
+use std::{net::IpAddr, path::PathBuf};
+
+use color_eyre::eyre::WrapErr;
+use compose_spec::{
+    service::{
+        ports::{Port, Protocol, Range, ShortPort},
+        volumes::{Mount, ShortVolume},
+        ConfigOrSecret,
+    },
+    Identifier, ShortOrLong,
+};
+use serde::Deserialize;
+use serde_yaml::{Deserializer, Mapping, Value};
+
+/// Keys whose sequence values replace, instead of extend, the sequence in an earlier document.
+const OVERRIDE_SEQUENCE_KEYS: [&str; 3] = ["command", "entrypoint", "test"];
+
+/// Keys whose values may be a list of `KEY=VALUE` strings or a mapping, and are merged by `KEY`.
+const KEY_VALUE_KEYS: [&str; 5] = ["annotations", "args", "environment", "labels", "sysctls"];
+
+/// Merge YAML which may contain multiple `---` separated documents into a single YAML [`Value`].
+///
+/// Documents are merged in order, with later documents overriding earlier ones, following compose
+/// merge semantics. Mappings are merged recursively, and `environment`, `labels`, and other lists
+/// of `KEY=VALUE` strings are merged by key. `command`, `entrypoint`, and `healthcheck.test` are
+/// replaced. Other sequences are appended to, with an item replacing an earlier item of the same
+/// [`SequenceKey`] and duplicate items skipped. All other values are replaced.
+///
+/// # Errors
+///
+/// Returns an error if a document is not valid YAML.
+pub fn merge_documents(yaml: &str) -> color_eyre::Result<Value> {
+    let mut merged: Option<Value> = None;
+    for (index, document) in Deserializer::from_str(yaml).enumerate() {
+        let mut value = Value::deserialize(document)
+            .wrap_err_with(|| format!("document {} is not valid YAML", index + 1))?;
+        // YAML merge keys must be applied before merging documents.
+        value
+            .apply_merge()
+            .wrap_err_with(|| format!("error applying merge keys in document {}", index + 1))?;
+
+        if let Some(merged) = &mut merged {
+            merge(merged, value, None);
+        } else {
+            merged = Some(value);
+        }
+    }
+
+    Ok(merged.unwrap_or_default())
+}
+
+/// Merge `value` into `base`, with `value` taking precedence.
+///
+/// `key` is the key of the values in their parent mapping, if any.
+pub fn merge(base: &mut Value, mut value: Value, key: Option<&str>) {
+    if key.is_some_and(|key| KEY_VALUE_KEYS.contains(&key)) {
+        key_value_list_into_mapping(base);
+        key_value_list_into_mapping(&mut value);
+    }
+
+    match (base, value) {
+        (Value::Mapping(base), Value::Mapping(value)) => {
+            for (key, value) in value {
+                if let Some(base) = base.get_mut(&key) {
+                    merge(base, value, key.as_str());
+                } else {
+                    base.insert(key, value);
+                }
+            }
+        }
+        (Value::Sequence(base), Value::Sequence(value))
+            if !key.is_some_and(|key| OVERRIDE_SEQUENCE_KEYS.contains(&key)) =>
+        {
+            for item in value {
+                let item_key = key.and_then(|key| SequenceKey::from_item(key, &item));
+                let existing = base.iter_mut().find(|existing| {
+                    let same_key = || {
+                        item_key.is_some()
+                            && key.and_then(|key| SequenceKey::from_item(key, existing)) == item_key
+                    };
+                    **existing == item || same_key()
+                });
+                if let Some(existing) = existing {
+                    *existing = item;
+                } else {
+                    base.push(item);
+                }
+            }
+        }
+        (base, value) => *base = value,
+    }
+}
+
+/// Convert a list of `KEY=VALUE` or `KEY` strings into a mapping.
+///
+/// The `value` is left as is if it is not a sequence of strings.
+fn key_value_list_into_mapping(value: &mut Value) {
+    let Value::Sequence(list) = value else {
+        return;
+    };
+    let mapping = list
+        .iter()
+        .map(|item| {
+            item.as_str().map(|item| {
+                item.split_once('=').map_or_else(
+                    || (item.into(), Value::Null),
+                    |(key, value)| (key.into(), value.into()),
+                )
+            })
+        })
+        .collect::<Option<Mapping>>();
+    if let Some(mapping) = mapping {
+        *value = Value::Mapping(mapping);
+    }
+}
+
+/// What makes an item of a sequence unique, an item replaces an earlier item with the same key.
+#[derive(Debug, PartialEq, Eq)]
+enum SequenceKey {
+    /// A port of `ports`, unique by its host IP, published port(s), target, and protocol.
+    Port {
+        host_ip: Option<IpAddr>,
+        published: Option<Range>,
+        target: u16,
+        protocol: Protocol,
+    },
+
+    /// An item of `volumes`, `configs`, or `secrets`, unique by its target in the container.
+    Target(PathBuf),
+}
+
+impl SequenceKey {
+    /// Determine the key of an `item` of the sequence under `key`.
+    ///
+    /// Returns [`None`] if items of the sequence are only unique by their value, or if the item
+    /// could not be parsed.
+    fn from_item(key: &str, item: &Value) -> Option<Self> {
+        match key {
+            "ports" => {
+                let port: ShortOrLong<ShortPort, Port> =
+                    serde_yaml::from_value(item.clone()).ok()?;
+                let mut ports = match port {
+                    ShortOrLong::Short(port) => port.into_long_iter().collect(),
+                    ShortOrLong::Long(port) => vec![port],
+                };
+                // Port ranges are only unique by their value.
+                let port = ports.pop().filter(|_| ports.is_empty())?;
+                Some(Self::Port {
+                    host_ip: port.host_ip,
+                    published: port.published,
+                    target: port.target,
+                    protocol: port.protocol.unwrap_or_default(),
+                })
+            }
+            "volumes" => {
+                let volume: ShortOrLong<ShortVolume, Mount> =
+                    serde_yaml::from_value(item.clone()).ok()?;
+                let target = Mount::from(volume).common().target.as_path().to_owned();
+                Some(Self::Target(target))
+            }
+            "configs" | "secrets" => {
+                let item: ShortOrLong<Identifier, ConfigOrSecret> =
+                    serde_yaml::from_value(item.clone()).ok()?;
+                let target = match item {
+                    ShortOrLong::Short(source)
+                    | ShortOrLong::Long(ConfigOrSecret {
+                        source,
+                        target: None,
+                        ..
+                    }) => PathBuf::from(source.as_str()),
+                    ShortOrLong::Long(ConfigOrSecret {
+                        target: Some(target),
+                        ..
+                    }) => target,
+                };
+                Some(Self::Target(target))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multiple_documents() {
+        let yaml = "
+services:
+  app:
+    image: image
+    command: [one]
+    ports:
+      - 8080:80
+    environment:
+      A: a
+---
+services:
+  app:
+    image: image2
+    command: [two]
+    ports:
+      - 8080:80
+      - 8443:443
+    environment:
+      B: b
+";
+
+        let mut options = compose_spec::Compose::options();
+        options.apply_merge(true);
+        let compose = options
+            .from_yaml_value(merge_documents(yaml).expect("documents merge"))
+            .expect("compose file is valid");
+
+        let expected = options
+            .from_yaml_str(
+                "
+services:
+  app:
+    image: image2
+    command: [two]
+    ports:
+      - 8080:80
+      - 8443:443
+    environment:
+      A: a
+      B: b
+",
+            )
+            .expect("compose file is valid");
+
+        assert_eq!(compose, expected);
+    }
+
+    #[test]
+    fn keyed_sequences() {
+        let yaml = "
+services:
+  app:
+    image: image
+    environment:
+      - A=1
+      - B
+    labels:
+      a: a
+    ports:
+      - 8080:80
+    volumes:
+      - data:/data
+    secrets:
+      - token
+---
+services:
+  app:
+    environment:
+      - A=2
+    labels:
+      - a=b
+    ports:
+      - target: 80
+        published: 8080
+    volumes:
+      - other:/data:ro
+      - cache:/cache
+    secrets:
+      - source: token
+        target: token
+        uid: '1000'
+";
+
+        let mut options = compose_spec::Compose::options();
+        options.apply_merge(true);
+        let compose = options
+            .from_yaml_value(merge_documents(yaml).expect("documents merge"))
+            .expect("compose file is valid");
+
+        let expected = options
+            .from_yaml_str(
+                "
+services:
+  app:
+    image: image
+    environment:
+      A: '2'
+      B:
+    labels:
+      a: b
+    ports:
+      - target: 80
+        published: 8080
+    volumes:
+      - other:/data:ro
+      - cache:/cache
+    secrets:
+      - source: token
+        target: token
+        uid: '1000'
+",
+            )
+            .expect("compose file is valid");
+
+        assert_eq!(compose, expected);
+    }
+
+    #[test]
+    fn single_document() {
+        let yaml = "
+services:
+  app:
+    image: image
+";
+
+        let mut options = compose_spec::Compose::options();
+        options.apply_merge(true);
+        assert_eq!(
+            options
+                .from_yaml_value(merge_documents(yaml).expect("valid YAML"))
+                .expect("compose file is valid"),
+            options.from_yaml_str(yaml).expect("compose file is valid"),
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! Support for octal file modes of compose service `configs` and `secrets`.
+
+use color_eyre::eyre::WrapErr;
+use serde_yaml::Value;
+
+/// Convert the `mode` of each long syntax config and secret of each service in a compose file's
+/// YAML `value` from an octal string, like `0440`, into an integer.
+///
+/// The compose specification writes file modes in octal with a leading `0`, which YAML 1.1
+/// parsers read as an octal integer. [`serde_yaml`] follows YAML 1.2, where `0440` is a string,
+/// so [`compose_spec`] would fail to deserialize it.
+///
+/// # Errors
+///
+/// Returns an error if a string `mode` is not a valid octal number.
+pub fn parse_octal_modes(value: &mut Value) -> color_eyre::Result<()> {
+    let Some(Value::Mapping(services)) = value.get_mut("services") else {
+        return Ok(());
+    };
+
+    for (name, service) in services {
+        for key in ["configs", "secrets"] {
+            let Some(Value::Sequence(items)) = service.get_mut(key) else {
+                continue;
+            };
+
+            for item in items {
+                let Some(mode) = item.get_mut("mode") else {
+                    continue;
+                };
+                let Value::String(octal) = mode else {
+                    continue;
+                };
+
+                let parsed =
+                    u32::from_str_radix(octal.trim_start_matches("0o"), 8).wrap_err_with(|| {
+                        format!(
+                            "invalid `mode` `{octal}` in `{key}` of service `{}`, \
+                                must be an octal number",
+                            name.as_str().unwrap_or_default(),
+                        )
+                    })?;
+                *mode = parsed.into();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn octal_strings() {
+        let mut value: Value = serde_yaml::from_str(
+            "
+            services:
+              app:
+                configs:
+                  - source: config
+                    mode: 0440
+                secrets:
+                  - source: secret
+                    mode: 0o400
+                  - source: other
+                    mode: 256
+            ",
+        )
+        .expect("valid YAML");
+        parse_octal_modes(&mut value).expect("modes are valid");
+
+        let expected: Value = serde_yaml::from_str(
+            "
+            services:
+              app:
+                configs:
+                  - source: config
+                    mode: 288
+                secrets:
+                  - source: secret
+                    mode: 256
+                  - source: other
+                    mode: 256
+            ",
+        )
+        .expect("valid YAML");
+        assert_eq!(value, expected);
+    }
+}
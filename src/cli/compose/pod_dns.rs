@@ -0,0 +1,156 @@
+//! Lifting of a [`Service`]'s DNS settings and extra hosts to the [`quadlet::Pod`] it is a part
+//! of.
+//!
+//! Containers in a pod share its network namespace, and therefore its `/etc/resolv.conf` and
+//! `/etc/hosts`, so DNS settings and extra hosts are set once on the pod instead of on each
+//! container.
+
+use std::net::IpAddr;
+
+use compose_spec::{Identifier, ItemOrList, Service};
+
+use crate::{quadlet, warning};
+
+/// Take `dns`, `dns_opt`, `dns_search`, and `extra_hosts` from the `service` and add them to the
+/// `pod`.
+///
+/// Values are unioned with those from other services in the pod. A warning is given if the
+/// service's values conflict with those already set on the pod, as all containers in the pod will
+/// use the combined values.
+pub fn lift(name: &Identifier, service: &mut Service, pod: &mut quadlet::Pod) {
+    if let Some(dns) = service.dns.take() {
+        let dns = match dns {
+            ItemOrList::Item(dns) => vec![dns],
+            ItemOrList::List(dns) => dns.into_iter().collect(),
+        };
+        union_servers(name, dns, &mut pod.dns);
+    }
+
+    for option in std::mem::take(&mut service.dns_opt) {
+        let key = option_key(&option);
+        match pod
+            .dns_option
+            .iter()
+            .find(|existing| option_key(existing) == key)
+        {
+            Some(existing) if *existing == option => {}
+            Some(existing) => warning::warn(format_args!(
+                "service `{name}` sets DNS option `{option}` which conflicts with `{existing}` \
+                    set by another service in the pod, keeping `{existing}`"
+            )),
+            None => pod.dns_option.push(option),
+        }
+    }
+
+    if let Some(dns_search) = service.dns_search.take() {
+        let dns_search: Vec<String> = match dns_search {
+            ItemOrList::Item(domain) => vec![domain.into()],
+            ItemOrList::List(domains) => domains.into_iter().map(Into::into).collect(),
+        };
+        if !pod.dns_search.is_empty() && pod.dns_search != dns_search {
+            warning::warn(format_args!(
+                "service `{name}` sets different DNS search domains than another service in the \
+                    pod, all containers in the pod will use the combined domains"
+            ));
+        }
+        for domain in dns_search {
+            if !pod.dns_search.contains(&domain) {
+                pod.dns_search.push(domain);
+            }
+        }
+    }
+
+    for (host, ip) in std::mem::take(&mut service.extra_hosts) {
+        let host = String::from(host);
+        match pod
+            .add_host
+            .iter()
+            .find_map(|existing| existing.split_once(':').filter(|(name, _)| *name == host))
+        {
+            Some((_, existing)) if existing == ip.to_string() => {}
+            Some((_, existing)) => warning::warn(format_args!(
+                "service `{name}` maps extra host `{host}` to `{ip}` but another service in the \
+                    pod maps it to `{existing}`, keeping `{existing}`"
+            )),
+            None => pod.add_host.push(format!("{host}:{ip}")),
+        }
+    }
+}
+
+/// Add a service's DNS servers to those of the pod, warning if both are set and are different.
+fn union_servers(name: &Identifier, dns: Vec<IpAddr>, pod_dns: &mut Vec<IpAddr>) {
+    if !pod_dns.is_empty() && *pod_dns != dns {
+        warning::warn(format_args!(
+            "service `{name}` sets different DNS servers than another service in the pod, \
+                all containers in the pod will use the combined servers"
+        ));
+    }
+    for server in dns {
+        if !pod_dns.contains(&server) {
+            pod_dns.push(server);
+        }
+    }
+}
+
+/// The name of a DNS option, e.g. `ndots` for `ndots:2`.
+fn option_key(option: &str) -> &str {
+    option.split_once(':').map_or(option, |(key, _)| key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{convert, parse_args};
+    use super::*;
+
+    #[test]
+    fn pod_dns() {
+        let yaml = "
+            name: pod
+            services:
+              web:
+                image: web
+                dns:
+                  - 10.0.0.53
+                  - 1.1.1.1
+                dns_search: example.test
+                extra_hosts:
+                  db.internal: 10.0.0.5
+              api:
+                image: api
+                dns: 10.0.0.53
+                dns_opt:
+                  - ndots:2
+                dns_search: example.test
+                extra_hosts:
+                  db.internal: 10.0.0.6
+        ";
+
+        warning::take();
+        let args = parse_args(&["--pod"]).expect("args are valid");
+        let files = convert(args, yaml).expect("compose file converts");
+        let [web, api, pod] = files
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .try_into()
+            .expect("two containers and a pod");
+
+        for container in [web, api] {
+            assert!(!container.contains("DNS"), "{container}");
+            assert!(!container.contains("add-host"), "{container}");
+        }
+        assert!(
+            pod.contains(
+                "AddHost=db.internal:10.0.0.5\n\
+                DNS=10.0.0.53\n\
+                DNS=1.1.1.1\n\
+                DNSOption=ndots:2\n\
+                DNSSearch=example.test\n"
+            ),
+            "{pod}"
+        );
+
+        let warnings = warning::take();
+        assert_eq!(warnings.len(), 2, "{warnings:?}");
+    }
+}
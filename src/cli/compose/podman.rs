@@ -0,0 +1,102 @@
+//! The top-level `x-podman` compose extension used by podman-compose.
+
+use color_eyre::eyre::WrapErr;
+use compose_spec::Extensions;
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use crate::warning;
+
+/// Key of the podman-compose extension.
+pub const KEY: &str = "x-podman";
+
+/// Podman specific options for a compose file, set with the top-level `x-podman` extension of
+/// podman-compose.
+#[derive(Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct PodmanExtension {
+    /// Whether to put the containers of the services in a pod, like `--pod`.
+    pub in_pod: Option<bool>,
+
+    /// Extra arguments for creating the pod, appended, verbatim, to the end of the pod's
+    /// `PodmanArgs=`.
+    #[serde(default)]
+    pub pod_args: Vec<String>,
+
+    /// Whether to separate the project name from resource names with `_`, like
+    /// `--prefix-separator _`.
+    #[serde(default)]
+    pub name_separator_compat: bool,
+
+    /// Keys not supported by Podlet.
+    #[serde(flatten)]
+    unsupported: IndexMap<String, serde_yaml::Value>,
+}
+
+impl PodmanExtension {
+    /// Take the `x-podman` extension from the top-level compose `extensions`, warning about each
+    /// key not supported by Podlet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the extension has an invalid value for a supported key.
+    pub fn take_from(extensions: &mut Extensions) -> color_eyre::Result<Self> {
+        let mut extension: Self = extensions
+            .shift_remove(KEY)
+            .map(|extension| {
+                serde_yaml::from_value(extension).wrap_err_with(|| format!("invalid `{KEY}`"))
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        for key in std::mem::take(&mut extension.unsupported).into_keys() {
+            warning::warn(format_args!(
+                "`{KEY}.{key}` is not supported by Podlet, ignoring it"
+            ));
+        }
+
+        Ok(extension)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{tests::convert, Compose};
+    use super::*;
+
+    #[test]
+    fn x_podman_extension() {
+        let yaml = "
+            name: app
+            services:
+              web:
+                image: docker.io/library/nginx
+                ports:
+                  - 8080:80
+            x-podman:
+              in_pod: true
+              pod_args:
+                - --infra=true
+              default_net_behavior_compat: true
+        ";
+
+        warning::take();
+        let files = convert(Compose::default(), yaml).expect("compose file converts");
+        let [container, pod]: [String; 2] = files
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .try_into()
+            .expect("container and pod files");
+        assert!(container.contains("Pod=app.pod\n"), "{container}");
+        assert!(pod.contains("PodmanArgs=--infra=true\n"), "{pod}");
+        assert!(pod.contains("PublishPort=8080:80\n"), "{pod}");
+
+        let [unsupported]: [String; 1] = warning::take()
+            .try_into()
+            .expect("a warning for the unsupported key");
+        assert!(
+            unsupported.contains("`x-podman.default_net_behavior_compat` is not supported"),
+            "{unsupported}"
+        );
+    }
+}
@@ -0,0 +1,56 @@
+//! Splitting of compose [`Service`](compose_spec::Service)s by their `profiles`, for
+//! `podlet compose --split-by-profile`.
+
+use color_eyre::eyre::ensure;
+use compose_spec::Compose;
+use indexmap::IndexSet;
+
+/// Name of the set of services without `profiles`.
+pub const BASE: &str = "base";
+
+/// Split a [`Compose`] file into a base file with the services without `profiles`, and a file
+/// for each profile with the services in it.
+///
+/// A service with multiple profiles is in the file of each one. The `profiles` of each service
+/// are removed. All other parts of the compose file, such as `networks` and `volumes`, are kept in
+/// each file so the services can be converted. The files generated from them should only be taken
+/// from the base file.
+///
+/// # Errors
+///
+/// Returns an error if a profile is named "base".
+pub fn split(mut compose: Compose) -> color_eyre::Result<(Compose, Vec<(String, Compose)>)> {
+    let profiles: IndexSet<String> = compose
+        .services
+        .values()
+        .flat_map(|service| &service.profiles)
+        .map(ToString::to_string)
+        .collect();
+    ensure!(
+        !profiles.contains(BASE),
+        "profile `{BASE}` conflicts with the directory for services without `profiles`"
+    );
+
+    let profiles = profiles
+        .into_iter()
+        .map(|profile| {
+            let mut compose = compose.clone();
+            compose.services.retain(|_, service| {
+                service
+                    .profiles
+                    .iter()
+                    .any(|service_profile| service_profile.as_str() == profile)
+            });
+            for service in compose.services.values_mut() {
+                service.profiles.clear();
+            }
+            (profile, compose)
+        })
+        .collect();
+
+    compose
+        .services
+        .retain(|_, service| service.profiles.is_empty());
+
+    Ok((compose, profiles))
+}
@@ -0,0 +1,83 @@
+//! Namespacing of generated Quadlet files with the compose project's `name`, for
+//! `podlet compose --use-project-name`.
+
+use crate::quadlet::{self, container::volume::Source};
+
+/// Prefix `name` with the `project` name and `separator`.
+pub fn prefix(project: &str, separator: char, name: &str) -> String {
+    format!("{project}{separator}{name}")
+}
+
+/// Prefix the references to `.network` and `.volume` Quadlet files in a `container` with the
+/// `project` name and `separator`, so they refer to the renamed files.
+pub fn prefix_references(container: &mut quadlet::Container, project: &str, separator: char) {
+    for network in &mut container.network {
+        // Network options follow the name after a ':'.
+        let is_quadlet_network = network
+            .split(':')
+            .next()
+            .is_some_and(|name| name.ends_with(".network"));
+        if is_quadlet_network {
+            *network = prefix(project, separator, network);
+        }
+    }
+
+    for volume in &mut container.volume {
+        if let Some(Source::NamedVolume(source)) = &mut volume.source {
+            if source.ends_with(".volume") {
+                *source = prefix(project, separator, source);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::File;
+
+    use super::super::tests::{convert, parse_args};
+
+    #[test]
+    fn use_project_name() {
+        let yaml = "
+            name: test
+            services:
+              web:
+                image: web
+                depends_on:
+                  - db
+                networks:
+                  - backend
+                volumes:
+                  - data:/data
+              db:
+                image: db
+                networks:
+                  - backend
+            networks:
+              backend: {}
+            volumes:
+              data:
+                driver: local
+        ";
+
+        let args = parse_args(&["--use-project-name"]).expect("args are valid");
+        let files = convert(args, yaml).expect("compose file converts");
+        let names: Vec<&str> = files.iter().map(File::name).collect();
+        assert_eq!(names, ["test-web", "test-db", "test-backend", "test-data"]);
+
+        let web = files.first().expect("web file").to_string();
+        assert!(web.contains("Requires=test-db.service\n"), "{web}");
+        assert!(web.contains("Network=test-backend.network\n"), "{web}");
+        assert!(web.contains("Volume=test-data.volume:/data\n"), "{web}");
+
+        assert!(
+            parse_args(&["--use-project-name", "--pod"]).is_err(),
+            "`--use-project-name` conflicts with `--pod`"
+        );
+        let args = parse_args(&["--use-project-name", "--prefix-separator", "_"])
+            .expect("`--prefix-separator` is allowed with `--use-project-name`");
+        let files = convert(args, yaml).expect("compose file converts");
+        assert_eq!(files.first().map(File::name), Some("test_web"));
+    }
+}
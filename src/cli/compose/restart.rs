@@ -0,0 +1,112 @@
+//! Support for the `on-failure:{max}` form of compose service `restart`.
+
+use color_eyre::eyre::{bail, WrapErr};
+use compose_spec::service::{deploy::RestartPolicy, Deploy};
+use serde_yaml::{Mapping, Value};
+
+/// Split `restart: on-failure:{max}` of each service in a compose file's YAML `value` into
+/// `restart: on-failure` and `deploy.restart_policy.max_attempts: {max}`.
+///
+/// [`compose_spec`] only accepts `restart` without a maximum number of attempts.
+///
+/// # Errors
+///
+/// Returns an error if the maximum is not a positive integer or conflicts with an existing
+/// `deploy.restart_policy.max_attempts`.
+pub fn split_max_attempts(value: &mut Value) -> color_eyre::Result<()> {
+    let Some(Value::Mapping(services)) = value.get_mut("services") else {
+        return Ok(());
+    };
+
+    for (name, service) in services {
+        let Some(service) = service.as_mapping_mut() else {
+            continue;
+        };
+        let Some(max_attempts) = service
+            .get("restart")
+            .and_then(Value::as_str)
+            .and_then(|restart| restart.strip_prefix("on-failure:"))
+        else {
+            continue;
+        };
+
+        let name = name.as_str().unwrap_or_default();
+        let max_attempts: u64 = max_attempts.parse().wrap_err_with(|| {
+            format!("invalid maximum restart attempts `{max_attempts}` for service `{name}`")
+        })?;
+        service.insert("restart".into(), "on-failure".into());
+
+        let restart_policy = service
+            .entry("deploy".into())
+            .or_insert_with(|| Mapping::new().into())
+            .as_mapping_mut()
+            .and_then(|deploy| {
+                deploy
+                    .entry("restart_policy".into())
+                    .or_insert_with(|| Mapping::new().into())
+                    .as_mapping_mut()
+            });
+        let Some(restart_policy) = restart_policy else {
+            bail!("`deploy.restart_policy` of service `{name}` must be a mapping");
+        };
+        match restart_policy.get("max_attempts").and_then(Value::as_u64) {
+            Some(existing) if existing != max_attempts => bail!(
+                "maximum restart attempts for service `{name}` in `restart` ({max_attempts}) \
+                    and `deploy.restart_policy.max_attempts` ({existing}) must be the same"
+            ),
+            _ => {
+                restart_policy.insert("max_attempts".into(), max_attempts.into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Take the maximum number of restart attempts from `deploy.restart_policy.max_attempts`.
+///
+/// If the `deploy` section is empty after taking `max_attempts`, it is set to [`None`] so the
+/// remainder can still be checked for unsupported options.
+pub fn take_max_attempts(deploy: &mut Option<Deploy>) -> Option<u64> {
+    let restart_policy = deploy
+        .as_mut()
+        .and_then(|deploy| deploy.restart_policy.as_mut())?;
+    let max_attempts = restart_policy.max_attempts.take();
+
+    if let Some(deploy) = deploy.as_mut() {
+        if deploy
+            .restart_policy
+            .as_ref()
+            .is_some_and(RestartPolicy::is_empty)
+        {
+            deploy.restart_policy = None;
+        }
+    }
+    if deploy.as_ref().is_some_and(Deploy::is_empty) {
+        *deploy = None;
+    }
+
+    max_attempts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{tests::convert_to_string, Compose};
+
+    #[test]
+    fn restart_on_failure_max_attempts() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                restart: on-failure:3
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(quadlet.contains("[Unit]\nStartLimitBurst=3\n"), "{quadlet}");
+        assert!(
+            quadlet.contains("[Service]\nRestart=on-failure\n"),
+            "{quadlet}"
+        );
+    }
+}
@@ -0,0 +1,72 @@
+//! Removal of the rollout options in the `deploy` section of compose
+//! [`Service`](compose_spec::Service)s.
+
+use compose_spec::{service::Deploy, Compose};
+
+use crate::warning;
+
+/// Remove `deploy.update_config` and `deploy.rollback_config` from each service of a [`Compose`]
+/// file, warning for each one removed.
+///
+/// These options control how a replicated service is updated or rolled back by an orchestrator.
+/// Podlet creates a container, or a container in a Kubernetes pod, for each replica instead of a
+/// replicated service or deployment, so there is nothing to update in steps. They are ignored with
+/// a warning instead of causing an error, allowing the rest of the service to be converted.
+pub fn remove_rollout_configs(compose: &mut Compose) {
+    for (name, service) in &mut compose.services {
+        let Some(deploy) = service.deploy.as_mut() else {
+            continue;
+        };
+
+        for (option, config) in [
+            ("update_config", deploy.update_config.take()),
+            ("rollback_config", deploy.rollback_config.take()),
+        ] {
+            if config.is_some() {
+                warning::warn(format_args!(
+                    "service `{name}` sets `deploy.{option}`, podlet does not create replicated \
+                        services or deployments so it is ignored"
+                ));
+            }
+        }
+
+        if service.deploy.as_ref().is_some_and(Deploy::is_empty) {
+            service.deploy = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::File;
+
+    use super::super::{tests::convert, Compose};
+    use super::*;
+
+    #[test]
+    fn deploy_rollout_configs() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                deploy:
+                  replicas: 2
+                  update_config:
+                    parallelism: 1
+                    delay: 10s
+                    order: start-first
+                  rollback_config:
+                    parallelism: 1
+        ";
+
+        let files = convert(Compose::default(), yaml).expect("compose file converts");
+        let names: Vec<&str> = files.iter().map(File::name).collect();
+        assert_eq!(names, ["app-1", "app-2"]);
+
+        let [update, rollback]: [String; 2] = warning::take()
+            .try_into()
+            .expect("a warning for each rollout option");
+        assert!(update.contains("`deploy.update_config`"), "{update}");
+        assert!(rollback.contains("`deploy.rollback_config`"), "{rollback}");
+    }
+}
@@ -0,0 +1,275 @@
+//! Renaming of compose [`Service`]s, networks, and volumes whose names are not suitable for Quadlet
+//! file names.
+//!
+//! Compose allows `.` and uppercase letters in names. A `.` is mistaken for the start of the file
+//! extension, e.g. a `web.api` service would be written to `web.container`, and uppercase letters
+//! make the generated systemd unit names inconsistent with the rest of the project.
+
+use std::collections::HashMap;
+
+use color_eyre::eyre::{ensure, WrapErr};
+use compose_spec::{
+    service::{
+        network_config::NetworkMode,
+        volumes::{mount, Mount, ShortOptions, ShortVolume, Source},
+        NetworkConfig, VolumesFromSource,
+    },
+    Compose, Identifier, Service, ShortOrLong,
+};
+use indexmap::{IndexMap, IndexSet};
+
+use crate::warning;
+
+/// Rename each service, network, and volume of the `compose` file whose name contains a `.` or
+/// uppercase letters, and update all references to them.
+///
+/// Each `.` is replaced with a `-` and uppercase letters are converted to lowercase. A warning is
+/// given for each renamed service, network, or volume.
+///
+/// # Errors
+///
+/// Returns an error if a renamed service, network, or volume would have the same name as another.
+pub fn file_names(compose: &mut Compose) -> color_eyre::Result<()> {
+    let services = rename_keys("service", &mut compose.services)?;
+    let networks = rename_keys("network", &mut compose.networks)?;
+    let volumes = rename_keys("volume", &mut compose.volumes)?;
+
+    if services.is_empty() && networks.is_empty() && volumes.is_empty() {
+        return Ok(());
+    }
+
+    for service in compose.services.values_mut() {
+        rename_service_references(service, &services);
+        rename_network_references(service, &networks);
+        rename_volume_references(service, &volumes);
+    }
+
+    Ok(())
+}
+
+/// The name to use for a Quadlet file, [`None`] if `name` can be used as is.
+fn sanitize(name: &Identifier) -> Option<String> {
+    let sanitized = name.as_str().replace('.', "-").to_lowercase();
+    (sanitized != name.as_str()).then_some(sanitized)
+}
+
+/// Rename the keys of `map` with [`sanitize()`], keeping their order.
+///
+/// Returns a map of the original names to the new names of renamed keys.
+///
+/// # Errors
+///
+/// Returns an error if a renamed key is the same as another key.
+fn rename_keys<V>(
+    kind: &str,
+    map: &mut IndexMap<Identifier, V>,
+) -> color_eyre::Result<HashMap<Identifier, Identifier>> {
+    let mut renames = HashMap::new();
+    if !map.keys().any(|name| sanitize(name).is_some()) {
+        return Ok(renames);
+    }
+
+    let mut sanitized = IndexMap::with_capacity(map.len());
+    for (name, value) in map.drain(..) {
+        let new_name = match sanitize(&name) {
+            Some(new_name) => {
+                let new_name = Identifier::new(new_name).wrap_err_with(|| {
+                    format!("error creating a valid file name for {kind} `{name}`")
+                })?;
+                warning::warn(format_args!(
+                    "renaming {kind} `{name}` to `{new_name}`, \
+                        `.` and uppercase letters are not supported in Quadlet file names"
+                ));
+                renames.insert(name.clone(), new_name.clone());
+                new_name
+            }
+            None => name.clone(),
+        };
+        ensure!(
+            !sanitized.contains_key(&new_name),
+            "{kind} `{name}` cannot be renamed to `{new_name}` as another {kind} has that name"
+        );
+        sanitized.insert(new_name, value);
+    }
+    *map = sanitized;
+
+    Ok(renames)
+}
+
+/// Rename `name` if it was renamed.
+fn rename(name: &mut Identifier, renames: &HashMap<Identifier, Identifier>) {
+    if let Some(new_name) = renames.get(name) {
+        name.clone_from(new_name);
+    }
+}
+
+/// Rename each key of `set` which was renamed, keeping their order.
+fn rename_set(set: &mut IndexSet<Identifier>, renames: &HashMap<Identifier, Identifier>) {
+    *set = set
+        .drain(..)
+        .map(|mut name| {
+            rename(&mut name, renames);
+            name
+        })
+        .collect();
+}
+
+/// Rename each key of `map` which was renamed, keeping their order.
+fn rename_map<V>(map: &mut IndexMap<Identifier, V>, renames: &HashMap<Identifier, Identifier>) {
+    *map = map
+        .drain(..)
+        .map(|(mut name, value)| {
+            rename(&mut name, renames);
+            (name, value)
+        })
+        .collect();
+}
+
+/// Update the references of a `service` to other services which were renamed.
+fn rename_service_references(service: &mut Service, renames: &HashMap<Identifier, Identifier>) {
+    if renames.is_empty() {
+        return;
+    }
+
+    match &mut service.depends_on {
+        ShortOrLong::Short(depends_on) => rename_set(depends_on, renames),
+        ShortOrLong::Long(depends_on) => rename_map(depends_on, renames),
+    }
+
+    service.links = std::mem::take(&mut service.links)
+        .into_iter()
+        .map(|mut link| {
+            rename(&mut link.service, renames);
+            link
+        })
+        .collect();
+
+    if let Some(NetworkConfig::NetworkMode(NetworkMode::Service(name))) =
+        &mut service.network_config
+    {
+        rename(name, renames);
+    }
+
+    service.volumes_from = std::mem::take(&mut service.volumes_from)
+        .into_iter()
+        .map(|mut volumes_from| {
+            if let VolumesFromSource::Service(name) = &mut volumes_from.source {
+                rename(name, renames);
+            }
+            volumes_from
+        })
+        .collect();
+}
+
+/// Update the references of a `service` to networks which were renamed.
+fn rename_network_references(service: &mut Service, renames: &HashMap<Identifier, Identifier>) {
+    if renames.is_empty() {
+        return;
+    }
+
+    match &mut service.network_config {
+        Some(NetworkConfig::Networks(ShortOrLong::Short(networks))) => {
+            rename_set(networks, renames);
+        }
+        Some(NetworkConfig::Networks(ShortOrLong::Long(networks))) => {
+            rename_map(networks, renames);
+        }
+        Some(NetworkConfig::NetworkMode(_)) | None => {}
+    }
+}
+
+/// Update the references of a `service` to volumes which were renamed.
+fn rename_volume_references(service: &mut Service, renames: &HashMap<Identifier, Identifier>) {
+    if renames.is_empty() {
+        return;
+    }
+
+    service.volumes = std::mem::take(&mut service.volumes)
+        .into_iter()
+        .map(|mut volume| {
+            match &mut volume {
+                ShortOrLong::Short(ShortVolume {
+                    options:
+                        Some(ShortOptions {
+                            source: Source::Volume(source),
+                            ..
+                        }),
+                    ..
+                })
+                | ShortOrLong::Long(Mount::Volume(mount::Volume {
+                    source: Some(source),
+                    ..
+                })) => rename(source, renames),
+                _ => {}
+            }
+            volume
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::{cli::File, warning};
+
+    use super::super::{
+        tests::{convert, convert_to_string},
+        Compose,
+    };
+
+    #[test]
+    fn sanitize_file_names() {
+        let yaml = "
+            services:
+              web.api:
+                image: web
+                depends_on:
+                  - Db
+                networks:
+                  - front.net
+                volumes:
+                  - api.data:/data
+              Db:
+                image: db
+                networks:
+                  - front.net
+            networks:
+              front.net:
+            volumes:
+              api.data:
+                labels:
+                  backup: \"true\"
+        ";
+
+        warning::take();
+        let files = convert(Compose::default(), yaml).expect("compose file converts");
+        assert_eq!(warning::take().len(), 4);
+
+        let paths: Vec<_> = files.iter().map(File::relative_path).collect();
+        assert_eq!(
+            paths,
+            [
+                "web-api.container",
+                "db.container",
+                "front-net.network",
+                "api-data.volume"
+            ]
+            .map(PathBuf::from)
+        );
+
+        let web = convert_to_string(Compose::default(), yaml);
+        assert!(web.contains("Requires=db.service\n"), "{web}");
+        assert!(web.contains("Network=front-net.network\n"), "{web}");
+        assert!(web.contains("Volume=api-data.volume:/data\n"), "{web}");
+
+        let collision = "
+            services:
+              Web:
+                image: web
+              web:
+                image: web
+        ";
+        assert!(convert(Compose::default(), collision).is_err());
+    }
+}
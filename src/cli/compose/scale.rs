@@ -0,0 +1,141 @@
+//! Scaling of compose [`Service`]s, with `scale` or `deploy.replicas`, into multiple containers.
+
+use color_eyre::{
+    eyre::{bail, ensure, eyre},
+    Section,
+};
+use compose_spec::{
+    service::{ports, Deploy},
+    Identifier, Service,
+};
+
+/// Take the number of replicas of a [`Service`] from its `scale` or `deploy.replicas` fields.
+///
+/// Defaults to 1 if neither is set. If the `deploy` section is empty after taking `replicas`, it
+/// is set to [`None`] so the remainder can still be checked for unsupported options.
+///
+/// # Errors
+///
+/// Returns an error if `scale` and `deploy.replicas` are both set to different values, the
+/// service is scaled to 0, or the service is scaled and sets `container_name` or publishes a
+/// fixed host port.
+pub fn take_replicas(service: &mut Service) -> color_eyre::Result<u64> {
+    let replicas = service
+        .deploy
+        .as_mut()
+        .and_then(|deploy| deploy.replicas.take());
+    if service.deploy.as_ref().is_some_and(Deploy::is_empty) {
+        service.deploy = None;
+    }
+
+    let replicas = match (service.scale.take(), replicas) {
+        (Some(scale), Some(replicas)) if scale != replicas => {
+            bail!("`scale` ({scale}) and `deploy.replicas` ({replicas}) must be the same");
+        }
+        (scale, replicas) => scale.or(replicas).unwrap_or(1),
+    };
+
+    ensure!(replicas > 0, "scaling a service to 0 is not supported");
+
+    if replicas > 1 {
+        ensure!(
+            service.container_name.is_none(),
+            "`container_name` cannot be set when scaling a service"
+        );
+        if ports::into_long_iter(service.ports.clone()).any(|port| port.published.is_some()) {
+            return Err(
+                eyre!("cannot publish a fixed host port when scaling a service").suggestion(
+                    "remove the published host port from `ports` so Podman assigns a random one",
+                ),
+            );
+        }
+    }
+
+    Ok(replicas)
+}
+
+/// Names of the containers created from the service `name` scaled to `replicas`.
+///
+/// If `replicas` is 1, the name is unchanged. Otherwise, each replica is named `{name}-{index}`,
+/// starting from 1.
+///
+/// # Panics
+///
+/// Panics if `name` with a numeric suffix is not a valid [`Identifier`], which cannot happen.
+pub fn replica_names(name: &Identifier, replicas: u64) -> Vec<Identifier> {
+    if replicas == 1 {
+        vec![name.clone()]
+    } else {
+        (1..=replicas)
+            .map(|index| {
+                Identifier::new(format!("{name}-{index}"))
+                    .expect("identifier with numeric suffix is valid")
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::File;
+
+    use super::super::{tests::convert, Compose};
+
+    #[test]
+    fn scale() {
+        let yaml = "
+            services:
+              worker:
+                image: image
+                scale: 3
+              app:
+                image: image
+                depends_on:
+                  - worker
+        ";
+
+        let files = convert(Compose::default(), yaml).expect("compose file converts");
+        let names: Vec<_> = files.iter().map(File::name).collect();
+        assert_eq!(names, ["worker-1", "worker-2", "worker-3", "app"]);
+
+        let app = files.last().expect("app file").to_string();
+        assert!(
+            app.contains("Requires=worker-1.service worker-2.service worker-3.service\n"),
+            "{app}"
+        );
+
+        let replicas = "
+            services:
+              worker:
+                image: image
+                deploy:
+                  replicas: 2
+        ";
+        let files = convert(Compose::default(), replicas).expect("compose file converts");
+        let names: Vec<_> = files.iter().map(File::name).collect();
+        assert_eq!(names, ["worker-1", "worker-2"]);
+    }
+
+    #[test]
+    fn scale_published_port() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                scale: 2
+                ports:
+                  - 8080:80
+        ";
+        assert!(convert(Compose::default(), yaml).is_err());
+
+        let yaml = "
+            services:
+              app:
+                image: image
+                scale: 2
+                ports:
+                  - 80
+        ";
+        assert!(convert(Compose::default(), yaml).is_ok());
+    }
+}
@@ -0,0 +1,239 @@
+//! Use of the `name` of external compose [`Secrets`] in [`Service`] secrets, and the type of
+//! service secrets.
+
+use color_eyre::eyre::WrapErr;
+use compose_spec::{Extensions, Identifier, Resource, Secrets, Service, ShortOrLong};
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use super::extension::KEY;
+
+/// Replace each service secret which refers to an external secret with a `name` with a reference
+/// to the Podman secret of that name.
+///
+/// If the service secret does not set a `target`, it is set to the secret's key in the compose
+/// file so the secret is mounted to the same path in the container.
+///
+/// # Errors
+///
+/// Returns an error if an external secret's `name` is not a valid identifier.
+pub fn use_external_names(
+    services: &mut IndexMap<Identifier, Service>,
+    secrets: &Secrets,
+) -> color_eyre::Result<()> {
+    for service in services.values_mut() {
+        for secret in &mut service.secrets {
+            let source = match secret {
+                ShortOrLong::Short(source) => &*source,
+                ShortOrLong::Long(secret) => &secret.source,
+            };
+            let Some(Resource::External {
+                name: Some(external_name),
+            }) = secrets.get(source)
+            else {
+                continue;
+            };
+            let external_name = Identifier::new(external_name.as_str()).wrap_err_with(|| {
+                format!("invalid name `{external_name}` of external secret `{source}`")
+            })?;
+
+            let mut long = secret.clone().into_long();
+            long.target
+                .get_or_insert_with(|| long.source.as_str().into());
+            long.source = external_name;
+            *secret = ShortOrLong::Long(long);
+        }
+    }
+
+    Ok(())
+}
+
+/// Podlet specific options for a [`Service`] secret, set with the `x-podlet` extension.
+#[derive(Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+struct SecretExtension {
+    /// How the secret is exposed to the container.
+    #[serde(default, rename = "type")]
+    kind: SecretType,
+}
+
+/// How a [`Service`] secret is exposed to its container.
+#[derive(Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SecretType {
+    /// Mounted as a file, the default.
+    #[default]
+    Mount,
+
+    /// Set as an environment variable named after the secret's `target`.
+    Env,
+}
+
+impl SecretType {
+    /// Take the type of a [`Service`] secret from the `type` option of its `x-podlet` extension.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the extension could not be deserialized.
+    pub fn take_from(extensions: &mut Extensions) -> color_eyre::Result<Self> {
+        extensions
+            .shift_remove(KEY)
+            .map(|extension| {
+                serde_yaml::from_value(extension).wrap_err_with(|| format!("invalid `{KEY}`"))
+            })
+            .transpose()
+            .map(|extension: Option<SecretExtension>| extension.unwrap_or_default().kind)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::warning;
+
+    use super::super::{
+        tests::{convert, convert_to_string, parse_args},
+        Compose,
+    };
+
+    #[test]
+    fn env_secret() {
+        let yaml = "
+            name: secrets
+            services:
+              app:
+                image: app
+                environment:
+                  LOG_LEVEL: info
+                secrets:
+                  - source: api_token
+                    target: API_TOKEN
+                    x-podlet:
+                      type: env
+                  - source: db_password
+                    x-podlet:
+                      type: env
+            secrets:
+              api_token:
+                file: ./compose-example.yaml
+              db_password:
+                external: true
+                name: prod_db_password
+        ";
+
+        // The secret's file is read relative to the compose file, and any file in the repository
+        // will do. Reading an environment variable is tested with the Kubernetes conversion.
+        warning::take();
+        let mut kube = parse_args(&["--kube"]).expect("args are valid");
+        kube.compose_file = Some(Path::new(env!("CARGO_MANIFEST_DIR")).join("compose.yaml"));
+        let files = convert(kube, yaml).expect("compose file converts");
+        let pod = files.get(1).expect("pod file");
+        #[cfg(unix)]
+        assert_eq!(pod.mode(), Some(0o600));
+        let pod = pod.to_string();
+        let [warning]: [String; 1] = warning::take()
+            .try_into()
+            .expect("a warning for the secret");
+        assert!(
+            warning.contains("secrets `api_token` are written in plain text"),
+            "{warning}"
+        );
+        assert!(
+            pod.contains(
+                "kind: Secret
+metadata:
+  name: api_token
+stringData:
+  api_token: |
+"
+            ),
+            "{pod}"
+        );
+        assert!(
+            pod.contains(
+                "  - env:
+    - name: LOG_LEVEL
+      value: info
+    - name: API_TOKEN
+      valueFrom:
+        secretKeyRef:
+          key: api_token
+          name: api_token
+    - name: db_password
+      valueFrom:
+        secretKeyRef:
+          key: prod_db_password
+          name: prod_db_password
+"
+            ),
+            "{pod}"
+        );
+        assert!(!pod.contains("volumeMounts"), "{pod}");
+
+        let file_secret = "
+            name: secrets
+            services:
+              app:
+                image: app
+                secrets: [token]
+            secrets:
+              token:
+                external: true
+        ";
+        let kube = parse_args(&["--kube"]).expect("args are valid");
+        assert!(convert(kube, file_secret).is_err());
+
+        let env_secret = "
+            services:
+              app:
+                image: app
+                secrets:
+                  - source: token
+                    target: TOKEN
+                    x-podlet:
+                      type: env
+            secrets:
+              token:
+                external: true
+        ";
+        let app = convert_to_string(Compose::default(), env_secret);
+        assert!(
+            app.contains("Secret=token,type=env,target=TOKEN\n"),
+            "{app}"
+        );
+    }
+
+    #[test]
+    fn external_secret_name() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                secrets:
+                  - token
+                  - source: key
+                    target: /etc/key
+                  - plain
+            secrets:
+              token:
+                external: true
+                name: podman-token
+              key:
+                external: true
+                name: podman-key
+              plain:
+                external: true
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(
+            quadlet.contains(
+                "Secret=podman-token,target=token\n\
+                Secret=podman-key,target=/etc/key\n\
+                Secret=plain\n"
+            ),
+            "{quadlet}"
+        );
+    }
+}
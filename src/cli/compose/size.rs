@@ -0,0 +1,222 @@
+//! Parsing and conversion of memory sizes, like a service's `mem_limit`.
+//!
+//! Sizes are written in many forms, like `512m`, `512M`, `1gb`, `1Gi`, or `1.5GiB`. Like Docker
+//! and Podman, all units are binary, so `1g`, `1gb`, and `1Gi` are each 1024 MiB.
+//! [`compose_spec`] only accepts a few of these forms, so sizes are normalized before the compose
+//! file is deserialized.
+
+use color_eyre::eyre::{eyre, OptionExt};
+use compose_spec::service::ByteValue;
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
+use serde_yaml::Value;
+
+/// Service fields which are memory sizes.
+const FIELDS: &[&str] = &["mem_limit", "mem_reservation", "memswap_limit", "shm_size"];
+
+/// Binary units, from largest to smallest, with the suffix [`ByteValue`] accepts.
+const UNITS: [(u64, &str); 4] = [(1 << 30, "g"), (1 << 20, "m"), (1 << 10, "k"), (1, "b")];
+
+/// Parse a memory size into a number of bytes.
+///
+/// The size is a number, which may have a fraction, followed by an optional unit. Units are case
+/// insensitive and binary: `b`, `k`, `kb`, `ki`, `kib`, `m`, `mb`, `mi`, `mib`, `g`, `gb`, `gi`,
+/// `gib`, `t`, `tb`, `ti`, or `tib`. A fraction of a byte is rounded down.
+///
+/// # Errors
+///
+/// Returns an error if the number could not be parsed, the unit is unknown, or the size is too
+/// large.
+pub fn parse(size: &str) -> color_eyre::Result<u64> {
+    let size = size.trim();
+    let unit_start = size
+        .find(|char: char| !char.is_ascii_digit() && char != '.')
+        .unwrap_or(size.len());
+    let (number, unit) = size.split_at(unit_start);
+
+    let multiplier: u64 = match unit.trim_start().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "ki" | "kib" => 1 << 10,
+        "m" | "mb" | "mi" | "mib" => 1 << 20,
+        "g" | "gb" | "gi" | "gib" => 1 << 30,
+        "t" | "tb" | "ti" | "tib" => 1 << 40,
+        _ => return Err(eyre!("size `{size}` has an unknown unit `{unit}`")),
+    };
+
+    let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+    let whole: u64 = whole
+        .parse()
+        .map_err(|_| eyre!("size `{size}` is not a number followed by a unit"))?;
+    let bytes = whole
+        .checked_mul(multiplier)
+        .ok_or_eyre("size is too large")?;
+    if fraction.is_empty() {
+        return Ok(bytes);
+    }
+
+    // Apply the fraction in integer arithmetic to avoid rounding errors.
+    let digits = u32::try_from(fraction.len())
+        .ok()
+        .filter(|digits| *digits <= 9)
+        .ok_or_else(|| eyre!("size `{size}` has too many decimal places"))?;
+    let fraction: u64 = fraction
+        .parse()
+        .map_err(|_| eyre!("size `{size}` is not a number followed by a unit"))?;
+    let fraction = (u128::from(fraction) * u128::from(multiplier)).div_euclid(10_u128.pow(digits));
+    u64::try_from(fraction)
+        .ok()
+        .and_then(|fraction| bytes.checked_add(fraction))
+        .ok_or_eyre("size is too large")
+}
+
+/// Format a number of bytes with the largest binary unit which divides it exactly, in a form
+/// accepted by both [`ByteValue`] and Podman, e.g. `512m`.
+fn format(bytes: u64) -> String {
+    let (size, unit) = UNITS
+        .into_iter()
+        .find(|(multiplier, _)| bytes.is_multiple_of(*multiplier))
+        .map_or((bytes, "b"), |(multiplier, unit)| {
+            (bytes.div_euclid(multiplier), unit)
+        });
+    format!("{size}{unit}")
+}
+
+/// Normalize the memory sizes of each service in a compose file's YAML `value`.
+///
+/// String sizes are parsed with [`parse()`] and replaced with a form [`ByteValue`] accepts.
+/// Numbers, including `-1` for an unlimited `memswap_limit`, are kept as is.
+///
+/// # Errors
+///
+/// Returns an error if a size could not be parsed.
+pub fn normalize(value: &mut Value) -> color_eyre::Result<()> {
+    let Some(Value::Mapping(services)) = value.get_mut("services") else {
+        return Ok(());
+    };
+
+    for (name, service) in services {
+        let name = name.as_str().unwrap_or_default().to_owned();
+        for field in FIELDS {
+            let Some(Value::String(size)) = service.get_mut(*field) else {
+                continue;
+            };
+            if size == "-1" {
+                continue;
+            }
+            let bytes = parse(size).map_err(|error| {
+                error.wrap_err(format!("invalid `{field}` for service `{name}`"))
+            })?;
+            *size = format(bytes);
+        }
+    }
+
+    Ok(())
+}
+
+/// Convert a [`ByteValue`] into a size for Podman, e.g. `512m`.
+///
+/// Units are binary, as Podman reads them.
+pub fn into_podman(size: ByteValue) -> String {
+    match size {
+        ByteValue::Bytes(bytes) => bytes.to_string(),
+        ByteValue::Kilobytes(size) => format!("{size}k"),
+        ByteValue::Megabytes(size) => format!("{size}m"),
+        ByteValue::Gigabytes(size) => format!("{size}g"),
+    }
+}
+
+/// Convert a [`ByteValue`] into a Kubernetes [`Quantity`] with a binary suffix, e.g. `512Mi`.
+pub fn into_quantity(size: ByteValue) -> Quantity {
+    Quantity(match size {
+        ByteValue::Bytes(bytes) => bytes.to_string(),
+        ByteValue::Kilobytes(size) => format!("{size}Ki"),
+        ByteValue::Megabytes(size) => format!("{size}Mi"),
+        ByteValue::Gigabytes(size) => format!("{size}Gi"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        tests::{convert_to_string, parse_args},
+        Compose,
+    };
+    use super::*;
+
+    #[test]
+    fn suffixes() {
+        for (size, bytes) in [
+            ("100", 100),
+            ("100b", 100),
+            ("100B", 100),
+            ("2k", 2048),
+            ("2kb", 2048),
+            ("2Ki", 2048),
+            ("512m", 512 << 20),
+            ("512M", 512 << 20),
+            ("512mb", 512 << 20),
+            ("512MB", 512 << 20),
+            ("512Mi", 512 << 20),
+            ("512MiB", 512 << 20),
+            ("1g", 1 << 30),
+            ("1gb", 1 << 30),
+            ("1GB", 1 << 30),
+            ("1Gi", 1 << 30),
+            ("1 GiB", 1 << 30),
+            ("1.5g", 3 << 29),
+            ("1t", 1 << 40),
+        ] {
+            assert_eq!(parse(size).expect(size), bytes, "{size}");
+        }
+    }
+
+    #[test]
+    fn invalid() {
+        for size in ["", "m", "1x", "1.5.5g", "-1g", "99999999999t"] {
+            assert!(parse(size).is_err(), "{size}");
+        }
+    }
+
+    #[test]
+    fn binary_not_decimal() {
+        // Docker and Podman read all units as binary, Kubernetes needs the binary suffix.
+        assert_eq!(format(parse("1gb").expect("valid size")), "1g");
+        assert_eq!(format(parse("1.5g").expect("valid size")), "1536m");
+        assert_eq!(format(1_000_000), "1000000b");
+        assert_eq!(into_podman(ByteValue::Megabytes(512)), "512m");
+        assert_eq!(into_quantity(ByteValue::Megabytes(512)).0, "512Mi");
+        assert_eq!(into_quantity(ByteValue::Gigabytes(1)).0, "1Gi");
+    }
+
+    #[test]
+    fn memory_sizes() {
+        let yaml = "
+            name: memory
+            services:
+              app:
+                image: app
+                mem_limit: 1GB
+                mem_reservation: 512Mi
+                shm_size: 1.5g
+        ";
+        let app = convert_to_string(Compose::default(), yaml);
+        assert!(app.contains("ShmSize=1536m\n"), "{app}");
+        assert!(
+            app.contains("PodmanArgs=--memory 1g --memory-reservation 512m\n"),
+            "{app}"
+        );
+
+        // `shm_size` is not supported by Kubernetes.
+        let yaml = "
+            name: memory
+            services:
+              app:
+                image: app
+                mem_limit: 1GB
+                mem_reservation: 512Mi
+        ";
+        let kube = parse_args(&["--kube"]).expect("args are valid");
+        let pod = convert_to_string(kube, yaml);
+        assert!(pod.contains("memory: 1Gi"), "{pod}");
+        assert!(pod.contains("memory: 512Mi"), "{pod}");
+    }
+}
@@ -0,0 +1,94 @@
+//! Validation of the `volume.subpath` option of a service's long syntax `volumes`.
+
+use color_eyre::{eyre::eyre, Section};
+use serde_yaml::Value;
+
+/// Ensure no bind mount in each service's `volumes`, in a compose file's YAML `value`, sets
+/// `volume.subpath`.
+///
+/// This is checked before deserialization as the `volume` options of a bind mount would otherwise
+/// be rejected as an invalid extension key.
+///
+/// # Errors
+///
+/// Returns an error if a `bind` type volume sets `volume.subpath`.
+pub fn reject_bind_subpaths(value: &Value) -> color_eyre::Result<()> {
+    let Some(Value::Mapping(services)) = value.get("services") else {
+        return Ok(());
+    };
+
+    for (name, service) in services {
+        let Some(Value::Sequence(volumes)) = service.get("volumes") else {
+            continue;
+        };
+        let name = name.as_str().unwrap_or_default();
+
+        for volume in volumes {
+            let is_bind = volume.get("type").and_then(Value::as_str) == Some("bind");
+            if is_bind
+                && volume
+                    .get("volume")
+                    .and_then(|volume| volume.get("subpath"))
+                    .is_some()
+            {
+                let source = volume
+                    .get("source")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                return Err(eyre!(
+                    "bind mount `{source}` of service `{name}` sets `volume.subpath`, \
+                        which is only supported for named volumes"
+                )
+                .suggestion("bind mount the subdirectory of the host path instead"));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        tests::{convert, convert_to_string, parse_args},
+        Compose,
+    };
+
+    #[test]
+    fn volume_subpath() {
+        let yaml = "
+            name: subpath
+            services:
+              web:
+                image: web
+                volumes:
+                  - type: volume
+                    source: data
+                    target: /srv/www
+                    volume:
+                      subpath: site
+            volumes:
+              data:
+        ";
+
+        let web = convert_to_string(Compose::default(), yaml);
+        assert!(web.contains("Volume=data:/srv/www:subpath=site\n"), "{web}");
+
+        let kube = parse_args(&["--kube"]).expect("args are valid");
+        let pod = convert_to_string(kube, yaml);
+        assert!(pod.contains("subPath: site"), "{pod}");
+
+        let bind = "
+            services:
+              web:
+                image: web
+                volumes:
+                  - type: bind
+                    source: ./data
+                    target: /srv/www
+                    volume:
+                      subpath: site
+        ";
+        assert!(convert(Compose::default(), bind).is_err());
+    }
+}
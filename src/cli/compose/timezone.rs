@@ -0,0 +1,110 @@
+//! Timezone of compose [`Service`] containers, from the `TZ` environment variable.
+
+use compose_spec::{ListOrMap, MapKey, Service, Value};
+
+/// Name of the environment variable which sets the timezone of a container.
+const TZ: &str = "TZ";
+
+/// Get the value of the `TZ` environment variable, if set in a service's `environment`.
+pub fn from_environment(environment: &ListOrMap) -> Option<String> {
+    match environment {
+        ListOrMap::List(environment) => environment.iter().find_map(|variable| {
+            variable
+                .split_once('=')
+                .and_then(|(key, value)| (key == TZ).then(|| value.to_owned()))
+        }),
+        ListOrMap::Map(environment) => environment
+            .get(TZ)
+            .and_then(Option::as_ref)
+            .map(ToString::to_string),
+    }
+}
+
+/// Set the `TZ` environment variable of the `service` to `timezone`, unless it is already set.
+///
+/// # Panics
+///
+/// Panics if `TZ` is not a valid [`MapKey`], which cannot happen.
+pub fn set_environment(service: &mut Service, timezone: &str) {
+    if from_environment(&service.environment).is_some() {
+        return;
+    }
+
+    match &mut service.environment {
+        ListOrMap::List(environment) => {
+            environment.insert(format!("{TZ}={timezone}"));
+        }
+        ListOrMap::Map(environment) => {
+            environment.insert(
+                MapKey::new(TZ).expect("`TZ` is a valid map key"),
+                Some(Value::String(timezone.to_owned())),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        tests::{convert, convert_to_string},
+        Compose,
+    };
+
+    #[test]
+    fn timezone() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                environment:
+                  TZ: Europe/Berlin
+              other:
+                image: image
+                x-podlet:
+                  timezone: UTC
+              default:
+                image: image
+        ";
+
+        let files: Vec<_> = convert(
+            Compose {
+                timezone: Some("local".to_owned()),
+                ..Compose::default()
+            },
+            yaml,
+        )
+        .expect("compose file converts")
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+        let [app, other, default] = files.try_into().expect("three files");
+
+        assert!(app.contains("Environment=TZ=Europe/Berlin\n"), "{app}");
+        assert!(app.contains("Timezone=Europe/Berlin\n"), "{app}");
+        assert!(other.contains("Timezone=UTC\n"), "{other}");
+        assert!(default.contains("Timezone=local\n"), "{default}");
+    }
+
+    #[test]
+    fn kube_timezone() {
+        let yaml = "
+            name: test
+            services:
+              app:
+                image: image
+        ";
+
+        let kube = convert_to_string(
+            Compose {
+                kube: true,
+                timezone: Some("Europe/Berlin".to_owned()),
+                ..Compose::default()
+            },
+            yaml,
+        );
+        assert!(
+            kube.contains("env:\n    - name: TZ\n      value: Europe/Berlin\n"),
+            "{kube}"
+        );
+    }
+}
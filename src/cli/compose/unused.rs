@@ -0,0 +1,222 @@
+//! Warnings for top-level compose [`Volumes`](compose_spec::Volumes),
+//! [`Networks`](compose_spec::Networks), [`Configs`](compose_spec::Configs), and
+//! [`Secrets`](compose_spec::Secrets) which are not used by any service.
+
+use std::collections::HashSet;
+
+use compose_spec::{
+    service::{
+        volumes::{mount, Mount, ShortOptions, ShortVolume, Source},
+        ConfigOrSecret, NetworkConfig,
+    },
+    Compose, Identifier, ShortOrLong,
+};
+use indexmap::IndexMap;
+
+use crate::warning;
+
+/// Key of the compose default network, used by services without `networks` or a `network_mode`.
+const DEFAULT_NETWORK: &str = "default";
+
+/// Warn about each top-level volume and network of the `compose` file which is not used by any
+/// service.
+///
+/// An unused volume or network is often a typo in the `source` of a service's volume or in a
+/// service's `networks`, and results in an orphaned file.
+pub fn warn_unreferenced(compose: &Compose) {
+    let mut volumes = HashSet::new();
+    let mut networks = HashSet::new();
+    let mut default_network = false;
+
+    for service in compose.services.values() {
+        volumes.extend(service.volumes.iter().filter_map(|volume| match volume {
+            ShortOrLong::Short(ShortVolume {
+                options:
+                    Some(ShortOptions {
+                        source: Source::Volume(source),
+                        ..
+                    }),
+                ..
+            })
+            | ShortOrLong::Long(Mount::Volume(mount::Volume {
+                source: Some(source),
+                ..
+            })) => Some(source),
+            _ => None,
+        }));
+
+        match &service.network_config {
+            Some(NetworkConfig::Networks(ShortOrLong::Short(service_networks))) => {
+                networks.extend(service_networks);
+            }
+            Some(NetworkConfig::Networks(ShortOrLong::Long(service_networks))) => {
+                networks.extend(service_networks.keys());
+            }
+            Some(NetworkConfig::NetworkMode(_)) => {}
+            None => default_network = true,
+        }
+    }
+
+    for name in compose
+        .volumes
+        .keys()
+        .filter(|name| !volumes.contains(name))
+    {
+        warning::warn(format_args!(
+            "volume `{name}` is not used by any service, \
+                check the `source` of each service's volumes for typos"
+        ));
+    }
+
+    let is_used = |name: &Identifier| {
+        networks.contains(name) || (default_network && name.as_str() == DEFAULT_NETWORK)
+    };
+    for name in compose.networks.keys().filter(|name| !is_used(name)) {
+        warning::warn(format_args!(
+            "network `{name}` is not used by any service, \
+                check each service's `networks` for typos"
+        ));
+    }
+}
+
+/// Remove each top-level config and secret of the `compose` file which is not used by any service,
+/// with a warning.
+///
+/// Unused configs and secrets are not converted, so options which are not supported, e.g. a
+/// non-external secret with `--format quadlet`, only cause an error if the config or secret is
+/// actually used.
+pub fn remove_unreferenced_configs_and_secrets(compose: &mut Compose) {
+    let mut configs = HashSet::new();
+    let mut secrets = HashSet::new();
+    for service in compose.services.values() {
+        configs.extend(service.configs.iter().map(source));
+        secrets.extend(service.secrets.iter().map(source));
+    }
+
+    remove_unreferenced("config", &mut compose.configs, &configs);
+    remove_unreferenced("secret", &mut compose.secrets, &secrets);
+}
+
+/// The name of the top-level config or secret a service's config or secret uses.
+fn source(config_or_secret: &ShortOrLong<Identifier, ConfigOrSecret>) -> Identifier {
+    match config_or_secret {
+        ShortOrLong::Short(source) => source.clone(),
+        ShortOrLong::Long(config_or_secret) => config_or_secret.source.clone(),
+    }
+}
+
+/// Remove each entry of `map` whose key is not `used`, warning about each one removed.
+fn remove_unreferenced<V>(
+    kind: &str,
+    map: &mut IndexMap<Identifier, V>,
+    used: &HashSet<Identifier>,
+) {
+    map.retain(|name, _| {
+        let is_used = used.contains(name);
+        if !is_used {
+            warning::warn(format_args!(
+                "{kind} `{name}` is not used by any service, ignoring it"
+            ));
+        }
+        is_used
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::warning;
+
+    use super::super::{
+        tests::{convert, convert_to_string},
+        Compose,
+    };
+
+    #[test]
+    fn unused_volumes_and_networks() {
+        let yaml = "
+            services:
+              app:
+                image: app
+                volumes:
+                  - app-data:/data
+                networks:
+                  - frontend
+            volumes:
+              app-data:
+              app_data:
+                labels:
+                  backup: \"true\"
+            networks:
+              frontend:
+              backend:
+        ";
+
+        warning::take();
+        convert(Compose::default(), yaml).expect("compose file converts");
+        let warnings = warning::take();
+        assert_eq!(warnings.len(), 2, "{warnings:?}");
+        assert!(
+            warnings
+                .iter()
+                .any(|warning| warning.contains("volume `app_data` is not used")),
+            "{warnings:?}"
+        );
+        assert!(
+            warnings
+                .iter()
+                .any(|warning| warning.contains("network `backend` is not used")),
+            "{warnings:?}"
+        );
+
+        let default_network = "
+            services:
+              app:
+                image: app
+            networks:
+              default:
+        ";
+        warning::take();
+        convert(Compose::default(), default_network).expect("compose file converts");
+        assert!(warning::take().is_empty());
+    }
+
+    #[test]
+    fn unused_configs_and_secrets() {
+        let yaml = "
+            services:
+              app:
+                image: app
+                secrets:
+                  - token
+            configs:
+              settings:
+                content: |
+                  debug = true
+            secrets:
+              token:
+                external: true
+              password:
+                file: ./password.txt
+        ";
+
+        warning::take();
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(quadlet.contains("Secret=token\n"), "{quadlet}");
+        assert!(!quadlet.contains("debug = true"), "{quadlet}");
+
+        let warnings = warning::take();
+        assert_eq!(warnings.len(), 2, "{warnings:?}");
+        assert!(
+            warnings
+                .iter()
+                .any(|warning| warning.contains("config `settings` is not used")),
+            "{warnings:?}"
+        );
+        assert!(
+            warnings
+                .iter()
+                .any(|warning| warning.contains("secret `password` is not used")),
+            "{warnings:?}"
+        );
+    }
+}
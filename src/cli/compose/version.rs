@@ -0,0 +1,61 @@
+//! Handling of the top-level `version` of a [`Compose`](compose_spec::Compose) file.
+//!
+//! The `version` is ignored by the Compose Specification, but legacy compose files declaring a
+//! `2.x` version were written for a file format with different semantics.
+
+use crate::warning;
+
+/// Warn if `version` is a legacy `2.x` compose file format version.
+///
+/// Conversion always follows the Compose Specification, so the warning notes where it differs
+/// from the version 2 file format.
+pub fn warn_legacy(version: Option<&str>) {
+    let Some(version) = version else {
+        return;
+    };
+    if !is_legacy_v2(version) {
+        return;
+    }
+
+    warning::warn(format_args!(
+        "compose file declares legacy file format version `{version}`, it is converted according \
+            to the Compose Specification instead: dependencies in `depends_on` are required, so a \
+            container is stopped when a dependency it requires is stopped, and `depends_on` \
+            conditions other than `service_started` are not supported"
+    ));
+}
+
+/// Returns `true` if `version` is `2` or `2.x`.
+fn is_legacy_v2(version: &str) -> bool {
+    let version = version.trim();
+    version == "2"
+        || version
+            .strip_prefix("2.")
+            .is_some_and(|minor| !minor.is_empty() && minor.bytes().all(|b| b.is_ascii_digit()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{tests::convert, Compose};
+    use super::*;
+
+    #[test]
+    fn legacy_version() {
+        let yaml = "
+            version: \"2.4\"
+            services:
+              web:
+                image: web
+        ";
+        let files = convert(Compose::default(), yaml).expect("compose file converts");
+        assert_eq!(files.len(), 1);
+
+        let warnings = warning::take();
+        assert_eq!(warnings.len(), 1, "{warnings:?}");
+        let warning = warnings.first().expect("one warning");
+        assert!(
+            warning.contains("legacy file format version `2.4`"),
+            "{warning}"
+        );
+    }
+}
@@ -0,0 +1,187 @@
+//! Removal of Windows specific options from compose [`Service`](compose_spec::Service)s, and
+//! normalization of Windows-style volume paths.
+
+use color_eyre::{eyre::eyre, Section};
+use compose_spec::{service::platform::Os, Compose};
+use serde_yaml::Value;
+
+use crate::warning;
+
+/// Remove the `isolation` option and a Windows `platform` from each service of a [`Compose`]
+/// file, warning for each one removed.
+///
+/// These options are used by compose files targeting Windows containers. Podman only runs Linux
+/// containers, so they are ignored with a warning instead of causing an error, allowing the rest
+/// of the service to be converted.
+pub fn remove_windows_options(compose: &mut Compose) {
+    for (name, service) in &mut compose.services {
+        if let Some(isolation) = service.isolation.take() {
+            warning::warn(format_args!(
+                "service `{name}` sets `isolation: {isolation}`, which is only supported for \
+                    Windows containers, podlet targets Linux Podman so it is ignored"
+            ));
+        }
+
+        if let Some(platform) = service
+            .platform
+            .filter(|platform| platform.os() == Os::Windows)
+        {
+            service.platform = None;
+            warning::warn(format_args!(
+                "service `{name}` sets `platform: {platform}`, podlet targets Linux Podman \
+                    so it is ignored"
+            ));
+        }
+    }
+}
+
+/// Replace the backslashes in the host path source of each service's `volumes`, in a compose
+/// file's YAML `value`, with forward slashes, warning for each one changed.
+///
+/// Compose files authored on Windows may separate path components with backslashes, like
+/// `./data\config`, which Podman would read as part of a file name.
+///
+/// # Errors
+///
+/// Returns an error if a volume's source is a Windows path with a drive letter, like `C:\data`, or
+/// starts with a backslash, as these are not valid on a Linux host.
+pub fn normalize_volume_paths(value: &mut Value) -> color_eyre::Result<()> {
+    let Some(Value::Mapping(services)) = value.get_mut("services") else {
+        return Ok(());
+    };
+
+    for (name, service) in services {
+        let Some(Value::Sequence(volumes)) = service.get_mut("volumes") else {
+            continue;
+        };
+        let name = name.as_str().unwrap_or_default();
+
+        for volume in volumes {
+            match volume {
+                Value::String(short) => {
+                    // A drive letter's ':' would otherwise be read as the source separator.
+                    let skip = if has_drive_letter(short) { 2 } else { 0 };
+                    let source_end = short
+                        .get(skip..)
+                        .and_then(|path| path.find(':'))
+                        .map_or(short.len(), |index| index + skip);
+                    let (source, rest) = short.split_at(source_end);
+                    if let Some(source) = normalize_path(source, name)? {
+                        *short = source + rest;
+                    }
+                }
+                Value::Mapping(long) => {
+                    if let Some(Value::String(source)) = long.get_mut("source") {
+                        if let Some(normalized) = normalize_path(source, name)? {
+                            *source = normalized;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Replace the backslashes in a volume's `source` path with forward slashes.
+///
+/// Returns [`None`] if the source does not contain a backslash.
+///
+/// # Errors
+///
+/// Returns an error if the source is a Windows path with a drive letter or starts with a backslash.
+fn normalize_path(source: &str, service: &str) -> color_eyre::Result<Option<String>> {
+    if has_drive_letter(source) || source.starts_with('\\') {
+        return Err(
+            eyre!("volume source `{source}` of service `{service}` is a Windows path")
+                .suggestion("Windows paths are not supported, use a path on the Linux host"),
+        );
+    }
+
+    if !source.contains('\\') {
+        return Ok(None);
+    }
+
+    let normalized = source.replace('\\', "/");
+    warning::warn(format_args!(
+        "volume source `{source}` of service `{service}` contains backslashes, \
+            using `{normalized}`"
+    ));
+    Ok(Some(normalized))
+}
+
+/// Returns `true` if `path` starts with a Windows drive letter, like `C:\` or `C:/`.
+fn has_drive_letter(path: &str) -> bool {
+    let mut chars = path.chars();
+    chars.next().is_some_and(|char| char.is_ascii_alphabetic())
+        && chars.next() == Some(':')
+        && chars.next().is_some_and(|char| matches!(char, '\\' | '/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        tests::{convert, convert_to_string},
+        Compose,
+    };
+    use super::*;
+
+    #[test]
+    fn windows_options() {
+        let yaml = "
+            services:
+              app:
+                image: image
+                isolation: hyperv
+                platform: windows/amd64
+        ";
+
+        let quadlet = convert_to_string(Compose::default(), yaml);
+        assert!(quadlet.contains("Image=image"), "{quadlet}");
+        assert!(!quadlet.contains("--platform"), "{quadlet}");
+
+        let [isolation, platform]: [String; 2] = warning::take()
+            .try_into()
+            .expect("a warning for each Windows option");
+        assert!(isolation.contains("`isolation: hyperv`"), "{isolation}");
+        assert!(platform.contains("`platform: windows/amd64`"), "{platform}");
+    }
+
+    #[test]
+    fn volume_backslash_paths() {
+        warning::take();
+        let quadlet = convert_to_string(
+            Compose::default(),
+            r"
+            services:
+              app:
+                image: image
+                volumes:
+                  - ./data\config:/config:ro
+                  - type: bind
+                    source: .\logs
+                    target: /logs
+            ",
+        );
+        assert!(
+            quadlet.contains("Volume=./data/config:/config:ro\nVolume=./logs:/logs\n"),
+            "{quadlet}"
+        );
+        assert_eq!(warning::take().len(), 2);
+
+        let error = convert(
+            Compose::default(),
+            r"
+            services:
+              app:
+                image: image
+                volumes:
+                  - C:\data:/data
+            ",
+        )
+        .expect_err("Windows paths are not supported");
+        assert!(error.to_string().contains(r"`C:\data`"), "{error}");
+    }
+}
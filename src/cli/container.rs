@@ -1,4 +1,5 @@
 mod compose;
+pub mod gpu;
 mod podman;
 mod quadlet;
 pub mod security_opt;
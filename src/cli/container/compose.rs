@@ -6,16 +6,18 @@ use std::{net::IpAddr, time::Duration};
 use color_eyre::eyre::ensure;
 use compose_spec::{
     service::{
-        build::Context, device::CgroupRule, AbsolutePath, BlkioConfig, Build, ByteValue, Cgroup,
-        Command, ConfigOrSecret, CpuSet, Cpus, CredentialSpec, Deploy, Develop, Device, EnvFile,
-        Expose, Extends, Healthcheck, Hostname, IdOrName, Image, Ipc, Limit, Link, Logging,
-        MacAddress, NetworkConfig, OomScoreAdj, Percent, Platform, Ports, PullPolicy, Ulimits,
-        User, Uts, Volumes, VolumesFrom,
+        build::Context, deploy, device::CgroupRule, AbsolutePath, BlkioConfig, Build, ByteValue,
+        Cgroup, Command, ConfigOrSecret, CpuSet, Cpus, CredentialSpec, Deploy, Develop, Device,
+        EnvFile, Expose, Extends, Healthcheck, Hostname, IdOrName, Image, Ipc, Limit, Link,
+        Logging, MacAddress, NetworkConfig, OomScoreAdj, Percent, Platform, Ports, PullPolicy,
+        Ulimits, User, Uts, Volumes, VolumesFrom,
     },
     Extensions, Identifier, ItemOrList, ListOrMap, MapKey, ShortOrLong, StringOrNumber,
 };
 use indexmap::{IndexMap, IndexSet};
 
+use super::gpu;
+
 /// A struct for splitting up a [`compose_spec::Service`] into parts used to construct a
 /// [`Container`](super::Container).
 pub struct Service {
@@ -45,12 +47,13 @@ impl From<compose_spec::Service> for Service {
             cgroup,
             cgroup_parent,
             command,
-            configs,
+            // Taken in `crate::cli::compose::service_try_into_quadlet_file()`.
+            configs: _,
             container_name,
             credential_spec,
             // Taken in `crate::cli::compose::service_try_into_quadlet_file()`.
             depends_on: _,
-            deploy,
+            mut deploy,
             develop,
             device_cgroup_rules,
             devices,
@@ -118,6 +121,8 @@ impl From<compose_spec::Service> for Service {
             extensions,
         }: compose_spec::Service,
     ) -> Self {
+        let reserved_devices = gpu::take_reserved_devices(&mut deploy);
+
         let Logging {
             driver: log_driver,
             options: log_options,
@@ -130,7 +135,6 @@ impl From<compose_spec::Service> for Service {
                 build,
                 cpu_count,
                 cpu_percent,
-                configs,
                 credential_spec,
                 deploy,
                 develop,
@@ -194,6 +198,7 @@ impl From<compose_spec::Service> for Service {
                 cgroup,
                 cgroup_parent,
                 device_cgroup_rules,
+                reserved_devices,
                 extra_hosts,
                 ipc,
                 uts,
@@ -225,7 +230,6 @@ pub struct Unsupported {
     build: Option<ShortOrLong<Context, Build>>,
     cpu_count: Option<u64>,
     cpu_percent: Option<Percent>,
-    configs: Vec<ShortOrLong<Identifier, ConfigOrSecret>>,
     credential_spec: Option<CredentialSpec>,
     deploy: Option<Deploy>,
     develop: Option<Develop>,
@@ -253,7 +257,6 @@ impl Unsupported {
             build,
             cpu_count,
             cpu_percent,
-            configs,
             credential_spec,
             deploy,
             develop,
@@ -275,7 +278,6 @@ impl Unsupported {
             ("build", build.is_none()),
             ("cpu_count", cpu_count.is_none()),
             ("cpu_percent", cpu_percent.is_none()),
-            ("configs", configs.is_empty()),
             ("credential_spec", credential_spec.is_none()),
             ("deploy", deploy.is_none()),
             ("develop", develop.is_none()),
@@ -355,6 +357,7 @@ pub struct PodmanArgs {
     pub cgroup: Option<Cgroup>,
     pub cgroup_parent: Option<String>,
     pub device_cgroup_rules: IndexSet<CgroupRule>,
+    pub reserved_devices: Vec<deploy::resources::Device>,
     pub extra_hosts: IndexMap<Hostname, IpAddr>,
     pub ipc: Option<Ipc>,
     pub uts: Option<Uts>,
@@ -0,0 +1,218 @@
+//! Conversion of GPU device reservations from a compose [`Service`](compose_spec::Service)'s
+//! [`Deploy`] section.
+
+use std::mem;
+
+use color_eyre::{
+    eyre::{ensure, eyre},
+    Section,
+};
+use compose_spec::service::{
+    deploy::{
+        resources::{Capability, Count, Device, Reservations},
+        Resources,
+    },
+    Deploy,
+};
+
+use crate::warning;
+
+/// CDI device name for NVIDIA GPUs.
+pub const NVIDIA_GPU: &str = "nvidia.com/gpu";
+
+/// Replace the legacy `nvidia` `runtime` of a compose [`Service`](compose_spec::Service) with a
+/// reservation of all GPUs, which converts to the `nvidia.com/gpu=all` CDI device.
+///
+/// The `nvidia` runtime is from the deprecated nvidia-docker, the NVIDIA Container Toolkit now
+/// generates a CDI specification instead. A warning is given when the runtime is replaced. If the
+/// service already reserves devices, they are kept as is. The service's `NVIDIA_VISIBLE_DEVICES`
+/// environment variable, if any, is not changed.
+pub fn replace_nvidia_runtime(name: &str, service: &mut compose_spec::Service) {
+    if service.runtime.as_deref() != Some("nvidia") {
+        return;
+    }
+    service.runtime = None;
+    warning::warn(format_args!(
+        "service `{name}` uses the legacy `nvidia` runtime, \
+            the `{NVIDIA_GPU}=all` CDI device is used instead"
+    ));
+
+    let reservations = service
+        .deploy
+        .get_or_insert_with(Deploy::default)
+        .resources
+        .get_or_insert_with(Resources::default)
+        .reservations
+        .get_or_insert_with(Reservations::default);
+    if reservations.devices.is_empty() {
+        reservations.devices.push(Device {
+            capabilities: [Capability::Gpu].into_iter().collect(),
+            count: Some(Count::All),
+            ..Device::default()
+        });
+    }
+}
+
+/// Take the devices from a compose [`Deploy`]'s `resources.reservations.devices`.
+///
+/// Any sections of the [`Deploy`] left empty after taking the devices are set to [`None`] so the
+/// remainder can still be checked for unsupported options.
+pub fn take_reserved_devices(deploy: &mut Option<Deploy>) -> Vec<Device> {
+    let Some(resources) = deploy.as_mut().and_then(|deploy| deploy.resources.as_mut()) else {
+        return Vec::new();
+    };
+    let devices = resources
+        .reservations
+        .as_mut()
+        .map(|reservations| mem::take(&mut reservations.devices))
+        .unwrap_or_default();
+
+    if resources
+        .reservations
+        .as_ref()
+        .is_some_and(Reservations::is_empty)
+    {
+        resources.reservations = None;
+    }
+    if let Some(deploy) = deploy.as_mut() {
+        if deploy.resources.as_ref().is_some_and(Resources::is_empty) {
+            deploy.resources = None;
+        }
+    }
+    if deploy.as_ref().is_some_and(Deploy::is_empty) {
+        *deploy = None;
+    }
+
+    devices
+}
+
+/// GPUs selected by a reserved [`Device`].
+enum Gpus {
+    /// A number of GPUs, or all of them.
+    Count(Count),
+    /// Specific GPUs by their ID.
+    Ids(Vec<String>),
+}
+
+/// Attempt to convert reserved [`Device`]s into the [`Count`] of GPUs to give to the container.
+///
+/// Returns [`None`] if there are no devices. If multiple GPU devices are reserved, their counts
+/// are summed.
+///
+/// # Errors
+///
+/// Returns an error if a device is not a GPU, uses a driver other than `nvidia`, selects GPUs by
+/// `device_ids`, or uses an unsupported option.
+pub fn reserved_devices_try_into_gpu_count(
+    devices: Vec<Device>,
+) -> color_eyre::Result<Option<Count>> {
+    devices.into_iter().try_fold(None, |total, device| {
+        let count = match device_try_into_gpus(device)? {
+            Gpus::Count(count) => count,
+            Gpus::Ids(_) => {
+                return Err(eyre!("device `device_ids` are not supported")
+                    .suggestion("use `count` to reserve a number of GPUs instead"));
+            }
+        };
+        Ok(Some(add_counts(total, count)))
+    })
+}
+
+/// Attempt to convert reserved [`Device`]s into CDI device names suitable for
+/// `podman run --device`.
+///
+/// The counts of devices are summed and converted with [`count_into_cdi_devices()`], GPUs selected
+/// by `device_ids` are added by their ID.
+///
+/// # Errors
+///
+/// Returns an error if a device is not a GPU, uses a driver other than `nvidia`, or uses an
+/// unsupported option.
+pub fn reserved_devices_try_into_cdi_devices(
+    devices: Vec<Device>,
+) -> color_eyre::Result<Vec<String>> {
+    let mut count = None;
+    let mut ids = Vec::new();
+    for device in devices {
+        match device_try_into_gpus(device)? {
+            Gpus::Count(device_count) => count = Some(add_counts(count, device_count)),
+            Gpus::Ids(device_ids) => ids.extend(device_ids),
+        }
+    }
+
+    let mut cdi_devices = count.map(count_into_cdi_devices).unwrap_or_default();
+    for id in ids {
+        let device = format!("{NVIDIA_GPU}={id}");
+        if !cdi_devices.contains(&device) {
+            cdi_devices.push(device);
+        }
+    }
+    Ok(cdi_devices)
+}
+
+/// Add a GPU [`Count`] to a running `total`.
+fn add_counts(total: Option<Count>, count: Count) -> Count {
+    match (total, count) {
+        (None, count) => count,
+        (Some(Count::All), _) | (_, Count::All) => Count::All,
+        (Some(Count::Integer(total)), Count::Integer(count)) => {
+            Count::Integer(total.saturating_add(count))
+        }
+    }
+}
+
+/// Attempt to convert a reserved [`Device`] into the [`Gpus`] it selects.
+///
+/// # Errors
+///
+/// Returns an error if the device is not a GPU, uses a driver other than `nvidia`, sets both
+/// `count` and `device_ids`, or uses an unsupported option.
+fn device_try_into_gpus(
+    Device {
+        capabilities,
+        driver,
+        count,
+        device_ids,
+        options,
+        extensions,
+    }: Device,
+) -> color_eyre::Result<Gpus> {
+    ensure!(
+        capabilities.iter().any(Capability::is_gpu),
+        "only devices with the `gpu` capability are supported"
+    );
+    if let Some(driver) = driver {
+        ensure!(
+            driver == "nvidia",
+            "device driver `{driver}` is not supported, only `nvidia` is supported"
+        );
+    }
+    ensure!(options.is_empty(), "device `options` are not supported");
+    ensure!(
+        extensions.is_empty(),
+        "compose extensions are not supported"
+    );
+
+    if device_ids.is_empty() {
+        Ok(Gpus::Count(count.unwrap_or_default()))
+    } else {
+        ensure!(
+            count.is_none(),
+            "device `count` and `device_ids` cannot both be set"
+        );
+        Ok(Gpus::Ids(device_ids.into_iter().collect()))
+    }
+}
+
+/// Convert a GPU [`Count`] into CDI device names suitable for `podman run --device`.
+///
+/// [`Count::All`] becomes `nvidia.com/gpu=all`, while an integer count selects GPUs by index,
+/// starting from 0.
+pub fn count_into_cdi_devices(count: Count) -> Vec<String> {
+    match count {
+        Count::All => vec![format!("{NVIDIA_GPU}=all")],
+        Count::Integer(count) => (0..count)
+            .map(|index| format!("{NVIDIA_GPU}={index}"))
+            .collect(),
+    }
+}
@@ -18,9 +18,12 @@ use compose_spec::service::{
 use serde::Serialize;
 use smart_default::SmartDefault;
 
-use crate::{cli::blkio_weight_parser, serde::skip_true};
+use crate::{
+    cli::{blkio_weight_parser, compose::size},
+    serde::skip_true,
+};
 
-use super::compose;
+use super::{compose, gpu};
 
 #[allow(clippy::struct_excessive_bools, clippy::module_name_repetitions)]
 #[derive(Args, Serialize, SmartDefault, Debug, Clone, PartialEq)]
@@ -135,6 +138,12 @@ pub struct PodmanArgs {
     #[arg(long, value_name = "SEQUENCE")]
     detach_keys: Option<String>,
 
+    /// CDI devices, such as GPUs, reserved by a compose service's `deploy` section
+    ///
+    /// The `--device` option is otherwise converted to "AddDevice=DEVICE"
+    #[arg(skip)]
+    device: Vec<String>,
+
     /// Add a rule to the cgroup allowed devices list
     ///
     /// Can be specified multiple times
@@ -437,6 +446,7 @@ impl TryFrom<compose::PodmanArgs> for PodmanArgs {
             cgroup,
             cgroup_parent,
             device_cgroup_rules,
+            reserved_devices,
             extra_hosts,
             ipc,
             uts,
@@ -463,7 +473,11 @@ impl TryFrom<compose::PodmanArgs> for PodmanArgs {
             weight_device: blkio_weight_device,
         } = blkio_config.unwrap_or_default();
 
+        let device = gpu::reserved_devices_try_into_cdi_devices(reserved_devices)
+            .wrap_err("error converting `deploy.resources.reservations.devices`")?;
+
         Ok(Self {
+            device,
             device_read_bps: device_read_bps
                 .into_iter()
                 .map(bps_limit_into_short)
@@ -510,8 +524,8 @@ impl TryFrom<compose::PodmanArgs> for PodmanArgs {
                 .wrap_err("`ipc` invalid")?,
             uts: uts.as_ref().map(ToString::to_string),
             mac_address: mac_address.as_ref().map(ToString::to_string),
-            memory: mem_limit.as_ref().map(ToString::to_string),
-            memory_reservation: mem_reservation.as_ref().map(ToString::to_string),
+            memory: mem_limit.map(size::into_podman),
+            memory_reservation: mem_reservation.map(size::into_podman),
             memory_swap: memswap_limit,
             memory_swappiness: mem_swappiness.map(Into::into),
             oom_kill_disable,
@@ -519,9 +533,11 @@ impl TryFrom<compose::PodmanArgs> for PodmanArgs {
             pid,
             platform: platform.as_ref().map(ToString::to_string),
             privileged,
-            attach: stdin_open
-                .then(|| vec!["stdin".to_owned()])
-                .unwrap_or_default(),
+            attach: if stdin_open {
+                vec!["stdin".to_owned()]
+            } else {
+                Vec::new()
+            },
             tty,
             ..Self::default()
         })
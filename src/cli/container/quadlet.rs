@@ -1,4 +1,5 @@
 use std::{
+    cmp::Reverse,
     iter,
     net::{IpAddr, Ipv4Addr, Ipv6Addr},
     path::PathBuf,
@@ -28,9 +29,13 @@ use compose_spec::{
 };
 use smart_default::SmartDefault;
 
-use crate::quadlet::{
-    container::{Device, DnsEntry, Mount, Notify, PullPolicy, Rootfs, Volume},
-    AutoUpdate,
+use crate::{
+    cli::compose::{secrets::SecretType, size},
+    quadlet::{
+        container::{Device, DnsEntry, Mount, Notify, PullPolicy, Rootfs, Volume},
+        AutoUpdate,
+    },
+    warning,
 };
 
 use super::compose;
@@ -595,7 +600,7 @@ impl From<QuadletOptions> for crate::quadlet::Container {
             publish_port,
             pull,
             read_only,
-            read_only_tmpfs,
+            read_only_tmpfs: (!read_only_tmpfs).then_some(false),
             rootfs,
             run_init,
             secret,
@@ -667,6 +672,7 @@ impl TryFrom<compose::Quadlet> for QuadletOptions {
             health_timeout,
             health_retries,
             health_start_period,
+            health_startup_cmd,
             health_startup_interval,
         } = healthcheck
             .unwrap_or_default()
@@ -728,6 +734,7 @@ impl TryFrom<compose::Quadlet> for QuadletOptions {
             health_timeout,
             health_retries,
             health_start_period,
+            health_startup_cmd,
             health_startup_interval,
             hostname: hostname.map(Into::into),
             init,
@@ -768,7 +775,7 @@ impl TryFrom<compose::Quadlet> for QuadletOptions {
                 .map(secret_try_into_short)
                 .collect::<Result<_, _>>()
                 .wrap_err("error converting `secrets`")?,
-            shm_size: shm_size.as_ref().map(ToString::to_string),
+            shm_size: shm_size.map(size::into_podman),
             stop_signal,
             stop_timeout: stop_grace_period.as_ref().map(Duration::as_secs),
             sysctl: sysctls.into_list().into_iter().collect(),
@@ -798,6 +805,7 @@ struct Healthcheck {
     health_timeout: Option<String>,
     health_retries: Option<u64>,
     health_start_period: Option<String>,
+    health_startup_cmd: Option<String>,
     health_startup_interval: Option<String>,
 }
 
@@ -821,14 +829,38 @@ impl TryFrom<service::Healthcheck> for Healthcheck {
                     extensions.is_empty(),
                     "compose extensions are not supported"
                 );
+
+                // Shell commands are run with `/bin/sh -c` as a whole instead of being split, so
+                // pipes and other shell syntax are kept intact.
+                let health_cmd = test
+                    .map(|test| {
+                        let command = match test {
+                            Test::Command(command) => command,
+                            Test::ShellCommand(command) => {
+                                vec!["/bin/sh".to_owned(), "-c".to_owned(), command]
+                            }
+                        };
+                        serde_json::to_string(&command)
+                            .wrap_err("error serializing healthcheck test command as JSON")
+                    })
+                    .transpose()?;
+
+                // `start_interval` is the interval between checks during the `start_period`,
+                // which is emulated with a startup healthcheck running the same command.
+                let mut start_interval = start_interval;
+                if start_interval.is_some()
+                    && (health_cmd.is_none() || start_period.is_none_or(|period| period.is_zero()))
+                {
+                    warning::warn(
+                        "healthcheck `start_interval` requires `test` and a `start_period`, \
+                            ignoring it",
+                    );
+                    start_interval = None;
+                }
+
                 Ok(Self {
-                    health_cmd: test
-                        .map(|test| match test {
-                            Test::Command(command) => serde_json::to_string(&command)
-                                .wrap_err("error serializing healthcheck test command as JSON"),
-                            Test::ShellCommand(command) => Ok(command),
-                        })
-                        .transpose()?,
+                    health_startup_cmd: start_interval.and_then(|_| health_cmd.clone()),
+                    health_cmd,
                     health_interval: interval.map(duration::to_string),
                     health_timeout: timeout.map(duration::to_string),
                     health_retries: retries,
@@ -913,6 +945,14 @@ fn tmpfs_try_into_short(
         "compose extensions are not supported"
     );
 
+    // tmpfs mount option `size` does not accept compose's byte value units
+    let size = size
+        .map(|size| {
+            size.into_bytes()
+                .ok_or_else(|| eyre!("tmpfs `size` `{size}` is too large"))
+        })
+        .transpose()?;
+
     let mut tmpfs = target.as_path().display().to_string();
 
     let options = read_only
@@ -939,6 +979,10 @@ fn tmpfs_try_into_short(
 /// Attempt to convert a compose service [`NetworkConfig`] into network options for the `network`
 /// field of [`QuadletOptions`].
 ///
+/// Networks are ordered by descending `priority`, as Podman uses the first network for the
+/// container's default route. Networks without a `priority` have the default of 0, and networks
+/// with the same `priority` keep their order.
+///
 /// # Errors
 ///
 /// Returns an error if an option is not supported by `podman run --network`.
@@ -949,22 +993,34 @@ fn network_config_try_into_network_options(
         NetworkConfig::NetworkMode(network_mode) => {
             validate_network_mode(network_mode).map(|network_mode| vec![network_mode])
         }
-        NetworkConfig::Networks(networks) => networks
-            .into_long()
-            .into_iter()
-            .map(|(identifier, options)| {
-                let mut network = String::from(identifier.clone());
-                network.push_str(".network");
-                if let Some(options) = options {
-                    let options = network_options(options).wrap_err_with(|| {
-                        format!("error converting `{identifier}` network options")
-                    })?;
-                    network.push(':');
-                    network.push_str(&options);
-                }
-                Ok(network)
-            })
-            .collect(),
+        NetworkConfig::Networks(networks) => {
+            let mut networks: Vec<_> = networks.into_long().into_iter().collect();
+            networks.sort_by_key(|(_, options)| {
+                Reverse(
+                    options
+                        .as_ref()
+                        .and_then(|options| options.priority)
+                        .unwrap_or(0),
+                )
+            });
+            networks
+                .into_iter()
+                .map(|(identifier, options)| {
+                    let mut network = String::from(identifier.clone());
+                    network.push_str(".network");
+                    if let Some(options) = options {
+                        let options = network_options(options).wrap_err_with(|| {
+                            format!("error converting `{identifier}` network options")
+                        })?;
+                        if !options.is_empty() {
+                            network.push(':');
+                            network.push_str(&options);
+                        }
+                    }
+                    Ok(network)
+                })
+                .collect()
+        }
     }
 }
 
@@ -1004,6 +1060,8 @@ fn validate_network_mode(network_mode: NetworkMode) -> color_eyre::Result<String
 
 /// Convert compose service [`Network`] options into a comma (,) separated list of key value pairs.
 ///
+/// The `priority` is not included, it only affects the order of the container's networks.
+///
 /// # Errors
 ///
 /// Returns an error if an option not supported by `podman run --network` is used.
@@ -1015,7 +1073,7 @@ fn network_options(
         link_local_ips,
         mac_address,
         driver_opts,
-        priority,
+        priority: _,
         extensions,
     }: Network,
 ) -> color_eyre::Result<String> {
@@ -1027,10 +1085,6 @@ fn network_options(
         driver_opts.is_empty(),
         "container specific network `driver_opts` are not supported"
     );
-    ensure!(
-        priority.is_none(),
-        "network `priority` option is not supported"
-    );
     ensure!(
         extensions.is_empty(),
         "compose extensions are not supported"
@@ -1057,7 +1111,7 @@ fn network_options(
 ///
 /// # Errors
 ///
-/// Returns an error if the secret has extensions.
+/// Returns an error if the secret has unsupported extensions.
 fn secret_try_into_short(
     secret: ShortOrLong<Identifier, ConfigOrSecret>,
 ) -> color_eyre::Result<String> {
@@ -1069,14 +1123,16 @@ fn secret_try_into_short(
             uid,
             gid,
             mode,
-            extensions,
+            mut extensions,
         }) => {
+            let secret_type = SecretType::take_from(&mut extensions)?;
             ensure!(
                 extensions.is_empty(),
                 "compose extensions are not supported"
             );
 
             Ok(iter::once(source.into())
+                .chain((secret_type == SecretType::Env).then(|| "type=env".to_owned()))
                 .chain(target.map(|target| format!("target={}", target.display())))
                 .chain(uid.map(|uid| format!("uid={uid}")))
                 .chain(gid.map(|gid| format!("gid={gid}")))
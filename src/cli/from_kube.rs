@@ -0,0 +1,390 @@
+//! Provides the `podlet from-kube` subcommand, see [`FromKube`].
+//!
+//! `podlet from-kube` converts a Kubernetes [`Pod`] into a [`quadlet::Container`] for each of its
+//! containers, the inverse of `podlet compose --kube`.
+
+mod container;
+
+use std::{fs, path::PathBuf};
+
+use clap::Args;
+use color_eyre::eyre::{bail, ensure, eyre, OptionExt, WrapErr};
+use compose_spec::service::Restart;
+use indexmap::IndexMap;
+use k8s_openapi::api::core::v1::{PersistentVolumeClaim, Pod, PodSpec, Volume};
+use serde::Deserialize;
+
+use crate::{
+    quadlet::{self, container::volume::Source, Globals},
+    warning,
+};
+
+use super::{service::Service, unit::Unit};
+
+/// Prefix of the [`PersistentVolumeClaim`] annotations used by `podman kube play` for volume
+/// options.
+const VOLUME_ANNOTATION_PREFIX: &str = "volume.podman.io/";
+
+/// [`Args`] for the `podlet from-kube` subcommand.
+#[derive(Args, Debug, Clone, PartialEq, Eq)]
+pub struct FromKube {
+    /// The Kubernetes YAML file to convert
+    ///
+    /// Must contain a single `Pod`. Any `PersistentVolumeClaim`s in the file are used for the
+    /// options of the `.volume` files created for the claims the pod uses.
+    pub kube_file: PathBuf,
+}
+
+impl FromKube {
+    /// Attempt to convert the `kube_file` into [`quadlet::File`]s.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file could not be read or deserialized, or the pod could not be
+    /// converted.
+    pub fn try_into_quadlet_files(
+        self,
+        unit: Option<&Unit>,
+        install: Option<&quadlet::Install>,
+    ) -> color_eyre::Result<Vec<quadlet::File>> {
+        let Self { kube_file } = self;
+
+        let yaml = fs::read_to_string(&kube_file).wrap_err_with(|| {
+            format!(
+                "error reading Kubernetes YAML file `{}`",
+                kube_file.display()
+            )
+        })?;
+
+        yaml_try_into_quadlet_files(&yaml, unit, install)
+    }
+}
+
+/// Attempt to convert Kubernetes YAML, which may contain multiple documents, into
+/// [`quadlet::File`]s.
+///
+/// Creates a `.container` file for each container in the [`Pod`] and a `.volume` file for each
+/// [`PersistentVolumeClaim`] used by the pod.
+///
+/// # Errors
+///
+/// Returns an error if the YAML could not be deserialized, it does not contain exactly one pod, or
+/// the pod could not be converted.
+fn yaml_try_into_quadlet_files(
+    yaml: &str,
+    unit: Option<&Unit>,
+    install: Option<&quadlet::Install>,
+) -> color_eyre::Result<Vec<quadlet::File>> {
+    let mut pods = Vec::new();
+    let mut persistent_volume_claims = IndexMap::new();
+    for document in serde_yaml::Deserializer::from_str(yaml) {
+        let document = serde_yaml::Value::deserialize(document)
+            .wrap_err("error deserializing Kubernetes YAML")?;
+        match document.get("kind").and_then(serde_yaml::Value::as_str) {
+            Some("Pod") => {
+                pods.push(serde_yaml::from_value::<Pod>(document).wrap_err("invalid `Pod`")?);
+            }
+            Some("PersistentVolumeClaim") => {
+                let claim = serde_yaml::from_value::<PersistentVolumeClaim>(document)
+                    .wrap_err("invalid `PersistentVolumeClaim`")?;
+                let name = claim
+                    .metadata
+                    .name
+                    .clone()
+                    .ok_or_eyre("`PersistentVolumeClaim` `metadata.name` is required")?;
+                persistent_volume_claims.insert(name, claim);
+            }
+            Some(kind) => warning::warn(format_args!("ignoring unsupported kind `{kind}`")),
+            None => bail!("Kubernetes YAML document is missing `kind`"),
+        }
+    }
+
+    let pod = match <[Pod; 1]>::try_from(pods) {
+        Ok([pod]) => pod,
+        Err(pods) => bail!(
+            "Kubernetes YAML must contain exactly one `Pod`, found {}",
+            pods.len()
+        ),
+    };
+
+    pod_try_into_quadlet_files(pod, persistent_volume_claims, unit, install)
+}
+
+/// Attempt to convert a [`Pod`] into [`quadlet::File`]s.
+///
+/// `persistent_volume_claims`, by name, are used for the options of the `.volume` files created
+/// for the claims used by the pod.
+///
+/// Pod spec fields other than `containers`, `initContainers`, `restartPolicy`, and `volumes` are
+/// ignored.
+///
+/// # Errors
+///
+/// Returns an error if the pod has no spec, uses init containers, or a volume or container could
+/// not be converted.
+fn pod_try_into_quadlet_files(
+    pod: Pod,
+    mut persistent_volume_claims: IndexMap<String, PersistentVolumeClaim>,
+    unit: Option<&Unit>,
+    install: Option<&quadlet::Install>,
+) -> color_eyre::Result<Vec<quadlet::File>> {
+    let PodSpec {
+        containers,
+        init_containers,
+        restart_policy,
+        volumes,
+        ..
+    } = pod.spec.ok_or_eyre("pod `spec` is required")?;
+
+    ensure!(
+        init_containers.unwrap_or_default().is_empty(),
+        "`initContainers` are not supported"
+    );
+
+    let service = restart_policy
+        .map(|restart_policy| match restart_policy.as_str() {
+            "Always" => Ok(Restart::Always),
+            "OnFailure" => Ok(Restart::OnFailure),
+            "Never" => Ok(Restart::No),
+            restart_policy => Err(eyre!("unknown `restartPolicy` `{restart_policy}`")),
+        })
+        .transpose()?
+        .map(Service::from);
+
+    let mut volume_files: Vec<quadlet::File> = Vec::new();
+    let volumes = volumes
+        .into_iter()
+        .flatten()
+        .map(|volume| {
+            if let Some(claim) = &volume.persistent_volume_claim {
+                let claim_name = &claim.claim_name;
+                if !volume_files.iter().any(|file| file.name == *claim_name) {
+                    let claim = persistent_volume_claims
+                        .shift_remove(claim_name)
+                        .unwrap_or_default();
+                    volume_files.push(quadlet::File {
+                        name: claim_name.clone(),
+                        unit: unit.cloned(),
+                        resource: persistent_volume_claim_into_volume(claim).into(),
+                        globals: Globals::default(),
+                        service: None,
+                        install: install.cloned(),
+                    });
+                }
+            }
+
+            let name = volume.name.clone();
+            PodVolume::try_from(volume)
+                .wrap_err_with(|| format!("error converting volume `{name}`"))
+                .map(|volume| (name, volume))
+        })
+        .collect::<color_eyre::Result<IndexMap<_, _>>>()?;
+
+    for name in persistent_volume_claims.keys() {
+        warning::warn(format_args!(
+            "ignoring `PersistentVolumeClaim` `{name}` as it is not used by the pod"
+        ));
+    }
+
+    let containers = containers.into_iter().map(|container| {
+        let name = container.name.clone();
+        container::try_into_quadlet(container, &volumes)
+            .wrap_err_with(|| format!("error converting container `{name}`"))
+            .map(|container| quadlet::File {
+                name,
+                unit: unit.cloned(),
+                resource: container.into(),
+                globals: Globals::default(),
+                service: service.clone(),
+                install: install.cloned(),
+            })
+    });
+
+    containers.chain(volume_files.into_iter().map(Ok)).collect()
+}
+
+/// A pod [`Volume`], as used by the volume mounts of its containers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PodVolume {
+    /// Source of the volume mounts, [`None`] for anonymous volumes.
+    source: Option<Source>,
+
+    /// Whether the volume must be mounted read-only.
+    read_only: bool,
+}
+
+impl TryFrom<Volume> for PodVolume {
+    type Error = color_eyre::Report;
+
+    /// [`PersistentVolumeClaim`]s are linked to a `.volume` file of the same name. `emptyDir`
+    /// volumes become anonymous volumes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the volume is not a `persistentVolumeClaim`, `hostPath`, or `emptyDir`
+    /// volume.
+    fn try_from(volume: Volume) -> Result<Self, Self::Error> {
+        if let Some(claim) = volume.persistent_volume_claim {
+            Ok(Self {
+                source: Some(Source::NamedVolume(format!("{}.volume", claim.claim_name))),
+                read_only: claim.read_only.unwrap_or_default(),
+            })
+        } else if let Some(host_path) = volume.host_path {
+            Ok(Self {
+                source: Some(Source::HostPath(host_path.path.into())),
+                read_only: false,
+            })
+        } else if volume.empty_dir.is_some() {
+            Ok(Self {
+                source: None,
+                read_only: false,
+            })
+        } else {
+            bail!("only `persistentVolumeClaim`, `hostPath`, and `emptyDir` volumes are supported")
+        }
+    }
+}
+
+/// Convert a [`PersistentVolumeClaim`] into a [`quadlet::Volume`].
+///
+/// Labels are carried over and the `volume.podman.io/` annotations used by `podman kube play` are
+/// converted into their equivalent Quadlet options.
+fn persistent_volume_claim_into_volume(claim: PersistentVolumeClaim) -> quadlet::Volume {
+    let mut volume = quadlet::Volume {
+        label: claim
+            .metadata
+            .labels
+            .into_iter()
+            .flatten()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect(),
+        ..quadlet::Volume::default()
+    };
+
+    for (key, value) in claim.metadata.annotations.into_iter().flatten() {
+        let Some(option) = key.strip_prefix(VOLUME_ANNOTATION_PREFIX) else {
+            continue;
+        };
+        match option {
+            "driver" => volume.driver = Some(value),
+            "device" => volume.device = Some(value.into()),
+            "type" => volume.fs_type = Some(value),
+            "uid" => volume.user = Some(value),
+            "gid" => volume.group = Some(value),
+            "mount-options" => volume.options = Some(value),
+            "image" => volume.image = Some(value),
+            _ => warning::warn(format_args!("ignoring unsupported annotation `{key}`")),
+        }
+    }
+
+    volume
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pod_with_persistent_volume_claim() {
+        let yaml = "
+apiVersion: v1
+kind: PersistentVolumeClaim
+metadata:
+  name: data
+  annotations:
+    volume.podman.io/driver: local
+---
+apiVersion: v1
+kind: Pod
+metadata:
+  name: app
+spec:
+  restartPolicy: Always
+  containers:
+    - name: web
+      image: docker.io/library/nginx
+      ports:
+        - containerPort: 80
+          hostPort: 8080
+      env:
+        - name: MODE
+          value: production
+      volumeMounts:
+        - name: data
+          mountPath: /usr/share/nginx/html
+          readOnly: true
+      livenessProbe:
+        exec:
+          command: [curl, -f, http://localhost]
+        periodSeconds: 30
+        failureThreshold: 3
+    - name: worker
+      image: docker.io/library/busybox
+      command: [sh, -c]
+      args: [sleep infinity]
+      volumeMounts:
+        - name: data
+          mountPath: /data
+  volumes:
+    - name: data
+      persistentVolumeClaim:
+        claimName: data
+";
+
+        let files = yaml_try_into_quadlet_files(yaml, None, None).expect("pod converts");
+        let names: Vec<_> = files
+            .iter()
+            .map(|file| format!("{}.{}", file.name, file.resource.extension()))
+            .collect();
+        assert_eq!(names, ["web.container", "worker.container", "data.volume"]);
+
+        let [web, worker, volume] = files
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .try_into()
+            .expect("three files");
+
+        for line in [
+            "Image=docker.io/library/nginx\n",
+            "PublishPort=8080:80\n",
+            "Environment=MODE=production\n",
+            "Volume=data.volume:/usr/share/nginx/html:ro\n",
+            "HealthCmd=curl -f http://localhost\n",
+            "HealthInterval=30s\n",
+            "HealthRetries=3\n",
+            "[Service]\nRestart=always\n",
+        ] {
+            assert!(web.contains(line), "{web}");
+        }
+
+        for line in [
+            "Image=docker.io/library/busybox\n",
+            "Entrypoint=[\"sh\",\"-c\"]\n",
+            "Exec='sleep infinity'\n",
+            "Volume=data.volume:/data\n",
+        ] {
+            assert!(worker.contains(line), "{worker}");
+        }
+
+        assert_eq!(volume, "[Volume]\nDriver=local\n");
+    }
+
+    #[test]
+    fn multiple_pods() {
+        let yaml = "
+apiVersion: v1
+kind: Pod
+metadata:
+  name: one
+---
+apiVersion: v1
+kind: Pod
+metadata:
+  name: two
+";
+
+        let error = yaml_try_into_quadlet_files(yaml, None, None).expect_err("two pods");
+        assert!(error.to_string().contains("exactly one"), "{error}");
+    }
+}
@@ -0,0 +1,430 @@
+//! Conversion of a Kubernetes [`Container`] into a [`quadlet::Container`].
+
+use color_eyre::eyre::{bail, ensure, eyre, OptionExt, WrapErr};
+use indexmap::IndexMap;
+use k8s_openapi::api::core::v1::{
+    Capabilities, Container, ContainerPort, EnvVar, Probe, SecurityContext, VolumeMount,
+};
+
+use crate::{
+    escape::command_join,
+    quadlet::{
+        self,
+        container::{volume, PullPolicy, Volume},
+    },
+    warning,
+};
+
+use super::PodVolume;
+
+/// Attempt to convert a Kubernetes [`Container`] into a [`quadlet::Container`].
+///
+/// `volumes` are the pod's volumes, by name, used by the container's `volumeMounts`.
+///
+/// # Errors
+///
+/// Returns an error if the container uses an unsupported option or there was an error converting
+/// one of its options.
+pub(super) fn try_into_quadlet(
+    Container {
+        args,
+        command,
+        env,
+        env_from,
+        image,
+        image_pull_policy,
+        lifecycle,
+        liveness_probe,
+        name,
+        ports,
+        readiness_probe,
+        resize_policy,
+        resources,
+        restart_policy,
+        security_context,
+        startup_probe,
+        stdin,
+        stdin_once,
+        // Only used for Kubernetes status reporting.
+        termination_message_path: _,
+        termination_message_policy: _,
+        tty,
+        volume_devices,
+        volume_mounts,
+        working_dir,
+    }: Container,
+    volumes: &IndexMap<String, PodVolume>,
+) -> color_eyre::Result<quadlet::Container> {
+    ensure!(
+        env_from.unwrap_or_default().is_empty(),
+        "`envFrom` is not supported"
+    );
+    ensure!(lifecycle.is_none(), "`lifecycle` is not supported");
+    ensure!(
+        resize_policy.unwrap_or_default().is_empty(),
+        "`resizePolicy` is not supported"
+    );
+    ensure!(resources.is_none(), "`resources` is not supported");
+    ensure!(
+        restart_policy.is_none(),
+        "`restartPolicy` is not supported for containers, set it for the pod instead"
+    );
+    ensure!(stdin_once.is_none(), "`stdinOnce` is not supported");
+    ensure!(
+        volume_devices.unwrap_or_default().is_empty(),
+        "`volumeDevices` is not supported"
+    );
+
+    if readiness_probe.is_some() {
+        warning::warn(format_args!(
+            "container `{name}` has a `readinessProbe`, which has no Quadlet equivalent, \
+                ignoring it"
+        ));
+    }
+
+    let mut container = quadlet::Container {
+        image: image.ok_or_eyre("`image` is required")?,
+        entrypoint: command
+            .map(|command| {
+                serde_json::to_string(&command).wrap_err("error serializing `command` as JSON")
+            })
+            .transpose()?,
+        exec: args.map(command_join),
+        environment: env
+            .into_iter()
+            .flatten()
+            .map(env_var_try_into_environment)
+            .collect::<Result<_, _>>()
+            .wrap_err("error converting `env`")?,
+        pull: image_pull_policy
+            .map(|pull_policy| match pull_policy.as_str() {
+                "Always" => Ok(PullPolicy::Always),
+                "IfNotPresent" => Ok(PullPolicy::Missing),
+                "Never" => Ok(PullPolicy::Never),
+                pull_policy => Err(eyre!("unknown `imagePullPolicy` `{pull_policy}`")),
+            })
+            .transpose()?,
+        volume: volume_mounts
+            .into_iter()
+            .flatten()
+            .map(|volume_mount| volume_mount_try_into_volume(volume_mount, volumes))
+            .collect::<Result<_, _>>()
+            .wrap_err("error converting `volumeMounts`")?,
+        working_dir: working_dir.map(Into::into),
+        ..quadlet::Container::default()
+    };
+
+    for port in ports.into_iter().flatten() {
+        add_port(&mut container, port).wrap_err("error converting `ports`")?;
+    }
+
+    if let Some(probe) = liveness_probe {
+        add_liveness_probe(&mut container, probe).wrap_err("error converting `livenessProbe`")?;
+    }
+    if let Some(probe) = startup_probe {
+        add_startup_probe(&mut container, probe).wrap_err("error converting `startupProbe`")?;
+    }
+
+    if let Some(security_context) = security_context {
+        add_security_context(&mut container, security_context)
+            .wrap_err("error converting `securityContext`")?;
+    }
+
+    if stdin.unwrap_or_default() {
+        container.podman_args_push_str("--interactive");
+    }
+    if tty.unwrap_or_default() {
+        container.podman_args_push_str("--tty");
+    }
+
+    Ok(container)
+}
+
+/// Attempt to convert an [`EnvVar`] into a `NAME=value` environment variable.
+///
+/// # Errors
+///
+/// Returns an error if `valueFrom` is set.
+fn env_var_try_into_environment(
+    EnvVar {
+        name,
+        value,
+        value_from,
+    }: EnvVar,
+) -> color_eyre::Result<String> {
+    ensure!(value_from.is_none(), "`valueFrom` is not supported");
+    Ok(format!("{name}={}", value.unwrap_or_default()))
+}
+
+/// Add a [`ContainerPort`] to the `container`.
+///
+/// Ports with a `hostPort` are published, otherwise they are only exposed.
+///
+/// # Errors
+///
+/// Returns an error if the port has an unknown `protocol`.
+fn add_port(
+    container: &mut quadlet::Container,
+    ContainerPort {
+        container_port,
+        host_ip,
+        host_port,
+        name: _,
+        protocol,
+    }: ContainerPort,
+) -> color_eyre::Result<()> {
+    let protocol = match protocol.as_deref() {
+        None | Some("TCP") => "",
+        Some("UDP") => "/udp",
+        Some("SCTP") => "/sctp",
+        Some(protocol) => bail!("unknown `protocol` `{protocol}`"),
+    };
+
+    if let Some(host_port) = host_port {
+        let host_ip = host_ip
+            .map(|host_ip| format!("{host_ip}:"))
+            .unwrap_or_default();
+        container
+            .publish_port
+            .push(format!("{host_ip}{host_port}:{container_port}{protocol}"));
+    } else {
+        ensure!(host_ip.is_none(), "`hostIP` requires `hostPort`");
+        container
+            .expose_host_port
+            .push(format!("{container_port}{protocol}"));
+    }
+
+    Ok(())
+}
+
+/// Attempt to convert a [`VolumeMount`] into a [`Volume`] using the pod's `volumes`.
+///
+/// # Errors
+///
+/// Returns an error if the volume is not one of the pod's `volumes`, or an unsupported option is
+/// used.
+fn volume_mount_try_into_volume(
+    VolumeMount {
+        mount_path,
+        mount_propagation,
+        name,
+        read_only,
+        recursive_read_only,
+        sub_path,
+        sub_path_expr,
+    }: VolumeMount,
+    volumes: &IndexMap<String, PodVolume>,
+) -> color_eyre::Result<Volume> {
+    ensure!(
+        mount_propagation.is_none(),
+        "`mountPropagation` is not supported"
+    );
+    ensure!(
+        recursive_read_only.is_none(),
+        "`recursiveReadOnly` is not supported"
+    );
+    ensure!(
+        sub_path.is_none() && sub_path_expr.is_none(),
+        "`subPath` is not supported"
+    );
+
+    let PodVolume {
+        source,
+        read_only: volume_read_only,
+    } = volumes
+        .get(&name)
+        .ok_or_else(|| eyre!("volume `{name}` is not defined in the pod's `volumes`"))?;
+
+    Ok(Volume {
+        source: source.clone(),
+        container_path: mount_path.into(),
+        options: volume::Options {
+            read_only: read_only.unwrap_or_default() || *volume_read_only,
+            ..volume::Options::default()
+        },
+    })
+}
+
+/// Options of a [`Probe`] converted into their Quadlet equivalents.
+struct HealthOptions {
+    /// Command run by the probe.
+    command: String,
+
+    /// `periodSeconds` as a duration.
+    interval: Option<String>,
+
+    /// `timeoutSeconds` as a duration.
+    timeout: Option<String>,
+
+    /// `initialDelaySeconds` as a duration.
+    initial_delay: Option<String>,
+
+    /// Number of consecutive failures before the probe fails.
+    failure_threshold: Option<i32>,
+
+    /// Number of consecutive successes before the probe succeeds.
+    success_threshold: Option<i32>,
+}
+
+impl TryFrom<Probe> for HealthOptions {
+    type Error = color_eyre::Report;
+
+    fn try_from(
+        Probe {
+            exec,
+            failure_threshold,
+            grpc,
+            http_get,
+            initial_delay_seconds,
+            period_seconds,
+            success_threshold,
+            tcp_socket,
+            termination_grace_period_seconds,
+            timeout_seconds,
+        }: Probe,
+    ) -> Result<Self, Self::Error> {
+        ensure!(
+            grpc.is_none() && http_get.is_none() && tcp_socket.is_none(),
+            "only `exec` probes are supported"
+        );
+        ensure!(
+            termination_grace_period_seconds.is_none(),
+            "`terminationGracePeriodSeconds` is not supported"
+        );
+
+        let command = exec
+            .and_then(|exec| exec.command)
+            .filter(|command| !command.is_empty())
+            .ok_or_eyre("`exec.command` is required")?;
+
+        let seconds = |seconds: i32| format!("{seconds}s");
+        Ok(Self {
+            command: command_join(command),
+            interval: period_seconds.map(seconds),
+            timeout: timeout_seconds.map(seconds),
+            initial_delay: initial_delay_seconds.map(seconds),
+            failure_threshold,
+            success_threshold,
+        })
+    }
+}
+
+/// Add a liveness [`Probe`] to the `container` as its healthcheck.
+///
+/// # Errors
+///
+/// Returns an error if the probe is not an `exec` probe or uses an unsupported option.
+fn add_liveness_probe(container: &mut quadlet::Container, probe: Probe) -> color_eyre::Result<()> {
+    let HealthOptions {
+        command,
+        interval,
+        timeout,
+        initial_delay,
+        failure_threshold,
+        // Must be 1 for liveness probes.
+        success_threshold: _,
+    } = probe.try_into()?;
+
+    container.health_cmd = Some(command);
+    container.health_interval = interval;
+    container.health_timeout = timeout;
+    container.health_start_period = initial_delay;
+    container.health_retries = failure_threshold
+        .map(TryInto::try_into)
+        .transpose()
+        .wrap_err("error converting `failureThreshold`")?;
+
+    Ok(())
+}
+
+/// Add a startup [`Probe`] to the `container` as its startup healthcheck.
+///
+/// # Errors
+///
+/// Returns an error if the probe is not an `exec` probe or uses an unsupported option.
+fn add_startup_probe(container: &mut quadlet::Container, probe: Probe) -> color_eyre::Result<()> {
+    let HealthOptions {
+        command,
+        interval,
+        timeout,
+        initial_delay,
+        failure_threshold,
+        success_threshold,
+    } = probe.try_into()?;
+
+    ensure!(
+        initial_delay.is_none(),
+        "`initialDelaySeconds` is not supported"
+    );
+
+    container.health_startup_cmd = Some(command);
+    container.health_startup_interval = interval;
+    container.health_startup_timeout = timeout;
+    container.health_startup_retries = failure_threshold
+        .map(TryInto::try_into)
+        .transpose()
+        .wrap_err("error converting `failureThreshold`")?;
+    container.health_startup_success = success_threshold
+        .map(TryInto::try_into)
+        .transpose()
+        .wrap_err("error converting `successThreshold`")?;
+
+    Ok(())
+}
+
+/// Add a container's [`SecurityContext`] to the `container`.
+///
+/// # Errors
+///
+/// Returns an error if an unsupported option is used.
+fn add_security_context(
+    container: &mut quadlet::Container,
+    SecurityContext {
+        allow_privilege_escalation,
+        app_armor_profile,
+        capabilities,
+        privileged,
+        proc_mount,
+        read_only_root_filesystem,
+        run_as_group,
+        run_as_non_root,
+        run_as_user,
+        se_linux_options,
+        seccomp_profile,
+        windows_options,
+    }: SecurityContext,
+) -> color_eyre::Result<()> {
+    ensure!(
+        app_armor_profile.is_none(),
+        "`appArmorProfile` is not supported"
+    );
+    ensure!(
+        !privileged.unwrap_or_default(),
+        "`privileged` is not supported"
+    );
+    ensure!(proc_mount.is_none(), "`procMount` is not supported");
+    ensure!(run_as_non_root.is_none(), "`runAsNonRoot` is not supported");
+    ensure!(
+        se_linux_options.is_none(),
+        "`seLinuxOptions` is not supported"
+    );
+    ensure!(
+        seccomp_profile.is_none(),
+        "`seccompProfile` is not supported"
+    );
+    ensure!(
+        windows_options.is_none(),
+        "`windowsOptions` is not supported"
+    );
+
+    if let Some(Capabilities { add, drop }) = capabilities {
+        container.add_capability = add.unwrap_or_default();
+        container.drop_capability = drop.unwrap_or_default();
+    }
+    container.no_new_privileges = allow_privilege_escalation == Some(false);
+    container.read_only = read_only_root_filesystem.unwrap_or_default();
+    container.user = run_as_user.map(|user| user.to_string());
+    container.group = run_as_group.map(|group| group.to_string());
+
+    Ok(())
+}
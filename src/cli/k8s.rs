@@ -1,20 +1,31 @@
-//! Kubernetes YAML [`File`] for converting a [`Compose`] file into a [`Pod`] and
-//! [`PersistentVolumeClaim`]s.
+//! Kubernetes YAML [`File`] for converting a [`Compose`] file into a [`Pod`],
+//! [`PersistentVolumeClaim`]s, [`ConfigMap`]s, and [`Secret`]s.
 
+mod api_level;
+mod config;
+mod pod_options;
+mod secret;
 mod service;
 mod volume;
 
-use std::fmt::{self, Display, Formatter};
+use std::{
+    fmt::{self, Display, Formatter},
+    path::{Path, PathBuf},
+};
 
-use color_eyre::eyre::{ensure, OptionExt, WrapErr};
-use compose_spec::{Compose, Resource};
+use color_eyre::eyre::{ensure, eyre, OptionExt, WrapErr};
+use compose_spec::{Compose, Identifier, ListOrMap, Resource};
 use k8s_openapi::{
-    api::core::v1::{PersistentVolumeClaim, Pod, PodSpec},
+    api::core::v1::{ConfigMap, PersistentVolumeClaim, Pod, PodSpec, Secret},
     apimachinery::pkg::apis::meta::v1::ObjectMeta,
 };
 
+pub use self::{api_level::KubeApiLevel, pod_options::PodOptions};
+
 use self::service::Service;
 
+use super::compose::{secrets, Hooks};
+
 /// A Kubernetes YAML file representing a [`Pod`] and optional [`PersistentVolumeClaim`]s.
 ///
 /// Created by converting from a [`Compose`] file.
@@ -30,28 +41,55 @@ pub struct File {
     ///
     /// Needed if a [`compose_spec::Volume`] has additional options set.
     pub persistent_volume_claims: Vec<PersistentVolumeClaim>,
+
+    /// Kubernetes [`ConfigMap`]s created from the compose file's `configs`.
+    ///
+    /// Not written as part of the file, use [`File::take_config_maps()`] to place them in their own
+    /// [`ConfigMapFile`].
+    pub config_maps: Vec<ConfigMap>,
+
+    /// Kubernetes [`Secret`]s created from the compose file's non-external `secrets`.
+    pub secrets: Vec<Secret>,
 }
 
 impl TryFrom<Compose> for File {
     type Error = color_eyre::Report;
 
-    fn try_from(
+    fn try_from(value: Compose) -> Result<Self, Self::Error> {
+        Self::try_from_compose(value, None, PodOptions::default())
+    }
+}
+
+impl File {
+    /// Attempt to convert a [`Compose`] file into a [`File`], applying `pod_options` to the
+    /// [`Pod`]'s spec.
+    ///
+    /// Relative paths of configs and secrets with a `file` source are resolved relative to the
+    /// directory of the compose file at `compose_file`, which is [`None`] if it was read from
+    /// stdin.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an unsupported option is used, a part of the compose file could not be
+    /// converted, or the `pod_options` could not be applied.
+    pub fn try_from_compose(
         Compose {
             version: _,
             name,
             include,
-            services,
+            mut services,
             networks,
             volumes,
             configs,
             secrets,
             extensions,
         }: Compose,
-    ) -> Result<Self, Self::Error> {
+        compose_file: Option<&Path>,
+        pod_options: PodOptions,
+    ) -> color_eyre::Result<Self> {
         ensure!(include.is_empty(), "`include` is not supported");
         ensure!(networks.is_empty(), "`networks` is not supported");
-        ensure!(configs.is_empty(), "`configs` is not supported");
-        ensure!(secrets.is_empty(), "`secrets` is not supported");
+        secrets::use_external_names(&mut services, &secrets)?;
         ensure!(
             extensions.is_empty(),
             "compose extensions are not supported"
@@ -59,17 +97,25 @@ impl TryFrom<Compose> for File {
 
         let name = name.map(String::from).ok_or_eyre("`name` is required")?;
 
-        let spec =
-            services
-                .into_iter()
-                .try_fold(PodSpec::default(), |mut spec, (name, service)| {
-                    Service::from_compose(&name, service)
-                        .add_to_pod_spec(&mut spec)
-                        .wrap_err_with(|| {
-                            format!("error adding service `{name}` to Kubernetes pod spec")
-                        })
-                        .map(|()| spec)
-                })?;
+        let mut spec = services.into_iter().try_fold(
+            PodSpec::default(),
+            |mut spec, (name, mut service)| {
+                Hooks::take_from(&mut service.extensions)
+                    .and_then(|hooks| {
+                        Service::from_compose(&name, service)
+                            .with_hooks(hooks)
+                            .add_to_pod_spec(&mut spec)
+                    })
+                    .wrap_err_with(|| {
+                        format!("error adding service `{name}` to Kubernetes pod spec")
+                    })
+                    .map(|()| spec)
+            },
+        )?;
+
+        pod_options
+            .apply_to_pod_spec(&mut spec)
+            .wrap_err("error applying pod options")?;
 
         let pod = Pod {
             metadata: ObjectMeta {
@@ -92,12 +138,102 @@ impl TryFrom<Compose> for File {
             })
             .collect::<Result<_, _>>()?;
 
+        let config_maps = configs
+            .into_iter()
+            .map(|(name, config)| match config {
+                Resource::Compose(config) => {
+                    config::try_into_config_map(name.clone(), config, compose_file).wrap_err_with(
+                        || format!("error converting config `{name}` to a config map"),
+                    )
+                }
+                Resource::External { .. } => {
+                    Err(eyre!("external config `{name}` is not supported"))
+                }
+            })
+            .collect::<Result<_, _>>()?;
+
+        let secrets = secrets
+            .into_iter()
+            .filter_map(|(name, secret)| match secret {
+                Resource::Compose(secret) => Some(
+                    secret::try_into_secret(name.clone(), secret, compose_file)
+                        .wrap_err_with(|| format!("error converting secret `{name}`")),
+                ),
+                Resource::External { .. } => None,
+            })
+            .collect::<Result<_, _>>()?;
+
         Ok(Self {
             name,
             pod,
             persistent_volume_claims,
+            config_maps,
+            secrets,
+        })
+    }
+
+    /// Remove fields from the [`Pod`] which are unsupported at the given [`KubeApiLevel`].
+    ///
+    /// A warning is emitted for each removed field.
+    pub fn downgrade(&mut self, kube_api_level: KubeApiLevel) {
+        if let Some(spec) = &mut self.pod.spec {
+            kube_api_level.downgrade_pod_spec(spec);
+        }
+    }
+
+    /// The permissions to give the file once written, if not the default.
+    ///
+    /// A file with [`Secret`]s, whose contents are written in plain text, is only readable and
+    /// writable by its owner.
+    #[cfg(unix)]
+    pub fn mode(&self) -> Option<u32> {
+        (!self.secrets.is_empty()).then_some(0o600)
+    }
+
+    /// Take the [`ConfigMap`]s out of the file and place them into their own [`ConfigMapFile`].
+    ///
+    /// Returns [`None`] if there are no [`ConfigMap`]s.
+    pub fn take_config_maps(&mut self) -> Option<ConfigMapFile> {
+        (!self.config_maps.is_empty()).then(|| ConfigMapFile {
+            name: format!("{}-configmap", self.name),
+            config_maps: std::mem::take(&mut self.config_maps),
         })
     }
+
+    /// Take each [`ConfigMap`] out of the file and place it into its own [`ConfigMapFile`].
+    pub fn split_config_maps(&mut self) -> Vec<ConfigMapFile> {
+        std::mem::take(&mut self.config_maps)
+            .into_iter()
+            .map(|config_map| ConfigMapFile {
+                name: format!(
+                    "{}-configmap-{}",
+                    self.name,
+                    config_map.metadata.name.as_deref().unwrap_or_default()
+                ),
+                config_maps: vec![config_map],
+            })
+            .collect()
+    }
+
+    /// Take each [`PersistentVolumeClaim`] out of the file and place it into its own
+    /// [`PersistentVolumeClaimFile`].
+    pub fn split_persistent_volume_claims(&mut self) -> Vec<PersistentVolumeClaimFile> {
+        std::mem::take(&mut self.persistent_volume_claims)
+            .into_iter()
+            .map(|persistent_volume_claim| PersistentVolumeClaimFile {
+                name: format!(
+                    "{}-pvc-{}",
+                    self.name,
+                    persistent_volume_claim
+                        .metadata
+                        .name
+                        .as_deref()
+                        .unwrap_or_default()
+                ),
+                persistent_volume_claim,
+            })
+            .collect()
+    }
 }
 
 impl Display for File {
@@ -106,8 +242,15 @@ impl Display for File {
             name: _,
             pod,
             persistent_volume_claims,
+            config_maps: _,
+            secrets,
         } = self;
 
+        for secret in secrets {
+            f.write_str(&serde_yaml::to_string(secret).map_err(|_| fmt::Error)?)?;
+            writeln!(f, "---")?;
+        }
+
         for volume in persistent_volume_claims {
             f.write_str(&serde_yaml::to_string(volume).map_err(|_| fmt::Error)?)?;
             writeln!(f, "---")?;
@@ -116,3 +259,94 @@ impl Display for File {
         f.write_str(&serde_yaml::to_string(pod).map_err(|_| fmt::Error)?)
     }
 }
+
+/// A Kubernetes YAML file containing a single [`PersistentVolumeClaim`].
+///
+/// Created with [`File::split_persistent_volume_claims()`].
+#[derive(Debug)]
+pub struct PersistentVolumeClaimFile {
+    /// The name of the file, without the extension.
+    pub name: String,
+
+    /// The Kubernetes [`PersistentVolumeClaim`].
+    pub persistent_volume_claim: PersistentVolumeClaim,
+}
+
+impl Display for PersistentVolumeClaimFile {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(&serde_yaml::to_string(&self.persistent_volume_claim).map_err(|_| fmt::Error)?)
+    }
+}
+
+/// A Kubernetes YAML file containing [`ConfigMap`]s.
+///
+/// Created with [`File::take_config_maps()`].
+#[derive(Debug)]
+pub struct ConfigMapFile {
+    /// The name of the file, without the extension.
+    pub name: String,
+
+    /// The Kubernetes [`ConfigMap`]s.
+    pub config_maps: Vec<ConfigMap>,
+}
+
+impl Display for ConfigMapFile {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let Self {
+            name: _,
+            config_maps,
+        } = self;
+
+        for (index, config_map) in config_maps.iter().enumerate() {
+            if index > 0 {
+                writeln!(f, "---")?;
+            }
+            f.write_str(&serde_yaml::to_string(config_map).map_err(|_| fmt::Error)?)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Create [`ObjectMeta`] for a config map or secret with the given `name` and compose `labels`.
+///
+/// # Errors
+///
+/// Returns an error if the `labels` could not be converted into a map.
+fn object_meta(name: Identifier, labels: ListOrMap) -> color_eyre::Result<ObjectMeta> {
+    Ok(ObjectMeta {
+        name: Some(name.into()),
+        labels: (!labels.is_empty())
+            .then(|| {
+                labels.into_map().map(|labels| {
+                    labels
+                        .into_iter()
+                        .map(|(key, value)| (key.into(), value.map(Into::into).unwrap_or_default()))
+                        .collect()
+                })
+            })
+            .transpose()
+            .wrap_err("error converting `labels`")?,
+        ..ObjectMeta::default()
+    })
+}
+
+/// Resolve the path of a config or secret `file` source relative to the directory of the compose
+/// file at `compose_file`.
+///
+/// # Errors
+///
+/// Returns an error if `file` is relative and `compose_file` is [`None`].
+fn resolve_path(file: &Path, compose_file: Option<&Path>) -> color_eyre::Result<PathBuf> {
+    if file.is_absolute() {
+        return Ok(file.to_owned());
+    }
+
+    let compose_file = compose_file.ok_or_else(|| {
+        eyre!(
+            "relative `file` path `{}` is not supported when reading the compose file from stdin",
+            file.display()
+        )
+    })?;
+    Ok(compose_file.parent().unwrap_or(Path::new("")).join(file))
+}
@@ -0,0 +1,192 @@
+//! [`KubeApiLevel`] for omitting fields from generated Kubernetes objects which are unsupported by
+//! older versions of Kubernetes.
+//!
+//! All objects podlet generates ([`Pod`](k8s_openapi::api::core::v1::Pod), `ConfigMap`, `Secret`,
+//! and `PersistentVolumeClaim`) are part of the core `v1` API group, so their `apiVersion` is the
+//! same at every level. Only omitting fields is supported.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use k8s_openapi::api::core::v1::{Container, PodSpec};
+use thiserror::Error;
+
+use crate::warning;
+
+/// Kubernetes version, set with `--kube-api-level`, which generated objects conform to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct KubeApiLevel {
+    /// The minor version of Kubernetes 1.x.
+    minor: u32,
+}
+
+impl KubeApiLevel {
+    /// Kubernetes 1.10, where the `size_limit` of an
+    /// [`EmptyDirVolumeSource`](k8s_openapi::api::core::v1::EmptyDirVolumeSource) is enforced by
+    /// default.
+    const EMPTY_DIR_SIZE_LIMIT: Self = Self { minor: 10 };
+
+    /// Kubernetes 1.18, where [`Container`]'s `startup_probe` is enabled by default.
+    const STARTUP_PROBE: Self = Self { minor: 18 };
+
+    /// Remove [`PodSpec`] fields unsupported at this level.
+    ///
+    /// A warning is emitted for each removed field.
+    pub(super) fn downgrade_pod_spec(self, spec: &mut PodSpec) {
+        if self < Self::EMPTY_DIR_SIZE_LIMIT {
+            for volume in spec.volumes.iter_mut().flatten() {
+                if let Some(size_limit) = volume
+                    .empty_dir
+                    .as_mut()
+                    .and_then(|empty_dir| empty_dir.size_limit.take())
+                {
+                    warning::warn(format_args!(
+                        "`sizeLimit` of volume `{}` ({}) omitted, it is not supported before \
+                            Kubernetes {}",
+                        volume.name,
+                        size_limit.0,
+                        Self::EMPTY_DIR_SIZE_LIMIT,
+                    ));
+                }
+            }
+        }
+
+        if self < Self::STARTUP_PROBE {
+            for container in &mut spec.containers {
+                remove_startup_probe(container);
+            }
+        }
+    }
+}
+
+/// Remove the `startup_probe` from a [`Container`].
+///
+/// The startup probe allows the container `period_seconds` times `failure_threshold` seconds to
+/// start. Instead, the `initial_delay_seconds` of the container's liveness probe is increased by
+/// that amount. A warning is emitted either way, noting the delay if there is a liveness probe.
+fn remove_startup_probe(container: &mut Container) {
+    let Some(startup_probe) = container.startup_probe.take() else {
+        return;
+    };
+
+    // Kubernetes defaults
+    let start_period = startup_probe
+        .period_seconds
+        .unwrap_or(10)
+        .saturating_mul(startup_probe.failure_threshold.unwrap_or(3));
+
+    let name = &container.name;
+    let level = KubeApiLevel::STARTUP_PROBE;
+    if let Some(liveness_probe) = &mut container.liveness_probe {
+        let initial_delay = liveness_probe.initial_delay_seconds.get_or_insert(0);
+        *initial_delay = initial_delay.saturating_add(start_period);
+        warning::warn(format_args!(
+            "startup probe of container `{name}` omitted, it is not supported before Kubernetes \
+                {level}; the liveness probe is delayed by {start_period} seconds instead"
+        ));
+    } else {
+        warning::warn(format_args!(
+            "startup probe of container `{name}` omitted, it is not supported before Kubernetes \
+                {level}"
+        ));
+    }
+}
+
+impl FromStr for KubeApiLevel {
+    type Err = ParseKubeApiLevelError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let minor = s.strip_prefix("1.").ok_or(ParseKubeApiLevelError::Major)?;
+        minor
+            .parse()
+            .map(|minor| Self { minor })
+            .map_err(|_| ParseKubeApiLevelError::Minor)
+    }
+}
+
+impl Display for KubeApiLevel {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let Self { minor } = self;
+        write!(f, "1.{minor}")
+    }
+}
+
+/// Error returned when parsing a [`KubeApiLevel`].
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseKubeApiLevelError {
+    #[error("Kubernetes version must start with `1.`")]
+    Major,
+    #[error("Kubernetes minor version must be a number")]
+    Minor,
+}
+
+#[cfg(test)]
+mod tests {
+    use k8s_openapi::api::core::v1::Probe;
+
+    use super::*;
+
+    fn container(liveness_probe: Option<Probe>) -> Container {
+        Container {
+            name: "app".to_owned(),
+            liveness_probe,
+            startup_probe: Some(Probe {
+                period_seconds: Some(5),
+                failure_threshold: Some(12),
+                ..Probe::default()
+            }),
+            ..Container::default()
+        }
+    }
+
+    #[test]
+    fn startup_probe() {
+        let liveness_probe = Probe {
+            initial_delay_seconds: Some(10),
+            ..Probe::default()
+        };
+        let mut spec = PodSpec {
+            containers: vec![container(Some(liveness_probe)), container(None)],
+            ..PodSpec::default()
+        };
+
+        let supported = spec.clone();
+        "1.18"
+            .parse::<KubeApiLevel>()
+            .expect("valid level")
+            .downgrade_pod_spec(&mut spec);
+        assert_eq!(spec, supported);
+        assert!(warning::take().is_empty());
+
+        "1.17"
+            .parse::<KubeApiLevel>()
+            .expect("valid level")
+            .downgrade_pod_spec(&mut spec);
+        let mut containers = spec.containers.iter();
+        let delayed = containers.next().expect("first container");
+        assert!(delayed.startup_probe.is_none());
+        assert_eq!(
+            delayed
+                .liveness_probe
+                .as_ref()
+                .and_then(|probe| probe.initial_delay_seconds),
+            Some(70)
+        );
+        let removed = containers.next().expect("second container");
+        assert!(removed.startup_probe.is_none());
+        assert!(removed.liveness_probe.is_none());
+
+        let warnings = warning::take();
+        let mut warnings = warnings.iter();
+        let delayed = warnings.next().expect("delayed warning");
+        assert!(
+            delayed.ends_with("the liveness probe is delayed by 60 seconds instead"),
+            "{delayed}"
+        );
+        let removed = warnings.next().expect("removed warning");
+        assert!(removed.ends_with("Kubernetes 1.18"), "{removed}");
+        assert!(warnings.next().is_none());
+    }
+}
@@ -0,0 +1,48 @@
+//! Utilities for converting compose [`Config`]s into Kubernetes [`ConfigMap`]s.
+
+use std::{fs, path::Path};
+
+use color_eyre::eyre::{bail, ensure, WrapErr};
+use compose_spec::{config::Source, Config, Identifier};
+use k8s_openapi::api::core::v1::ConfigMap;
+
+/// Attempt to convert a compose [`Config`] into a [`ConfigMap`].
+///
+/// The [`ConfigMap`] has the same `name` as the config and stores its contents under a key of the
+/// same name. Configs with a `file` source are read from the host, relative to the directory
+/// of the compose file at `compose_file`.
+///
+/// # Errors
+///
+/// Returns an error if the config has an unsupported source or option, or its file could not be
+/// read.
+pub(super) fn try_into_config_map(
+    name: Identifier,
+    Config {
+        source,
+        labels,
+        extensions,
+    }: Config,
+    compose_file: Option<&Path>,
+) -> color_eyre::Result<ConfigMap> {
+    ensure!(
+        extensions.is_empty(),
+        "compose extensions are not supported"
+    );
+
+    let content = match source {
+        Source::File(path) => {
+            let path = super::resolve_path(&path, compose_file)?;
+            fs::read_to_string(&path)
+                .wrap_err_with(|| format!("error reading config file `{}`", path.display()))?
+        }
+        Source::Content(content) => content,
+        Source::Environment(_) => bail!("configs with an `environment` source are not supported"),
+    };
+
+    Ok(ConfigMap {
+        metadata: super::object_meta(name.clone(), labels)?,
+        data: Some([(name.into(), content)].into()),
+        ..ConfigMap::default()
+    })
+}
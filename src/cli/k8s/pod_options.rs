@@ -0,0 +1,84 @@
+//! [`PodOptions`] for setting Kubernetes [`PodSpec`] fields which have no compose equivalent.
+
+use clap::Args;
+use color_eyre::eyre::ensure;
+use k8s_openapi::api::core::v1::{PodSecurityContext, PodSpec};
+
+/// Options applied to the Kubernetes [`PodSpec`] generated from a compose file.
+///
+/// Only supported with `--format kube`.
+#[derive(Args, Default, Debug, Clone, PartialEq, Eq)]
+#[command(next_help_heading = "Kubernetes Pod Options")]
+pub struct PodOptions {
+    /// Name of the service account used to run the pod
+    #[arg(long, value_name = "NAME")]
+    pub service_account: Option<String>,
+
+    /// Whether a service account token should be automatically mounted
+    #[arg(
+        long,
+        value_name = "BOOL",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "true"
+    )]
+    pub automount_service_account_token: Option<bool>,
+
+    /// Group ID applied to all volumes in the pod
+    #[arg(long, value_name = "GID")]
+    pub fs_group: Option<i64>,
+
+    /// Require all containers in the pod to run as a non-root user
+    ///
+    /// An error is returned if a service sets `user` to 0.
+    #[arg(long)]
+    pub run_as_non_root: bool,
+}
+
+impl PodOptions {
+    /// Returns `true` if no options are set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply the options to a [`PodSpec`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `run_as_non_root` is set and a container in the `spec` runs as root.
+    pub(super) fn apply_to_pod_spec(self, spec: &mut PodSpec) -> color_eyre::Result<()> {
+        let Self {
+            service_account,
+            automount_service_account_token,
+            fs_group,
+            run_as_non_root,
+        } = self;
+
+        if run_as_non_root {
+            for container in &spec.containers {
+                let run_as_user = container
+                    .security_context
+                    .as_ref()
+                    .and_then(|security_context| security_context.run_as_user);
+                ensure!(
+                    run_as_user != Some(0),
+                    "`--run-as-non-root` is set but service `{}` runs as root (`user: 0`)",
+                    container.name
+                );
+            }
+        }
+
+        spec.service_account_name = service_account;
+        spec.automount_service_account_token = automount_service_account_token;
+
+        if fs_group.is_some() || run_as_non_root {
+            let security_context = spec
+                .security_context
+                .get_or_insert_with(PodSecurityContext::default);
+            security_context.fs_group = fs_group;
+            security_context.run_as_non_root = run_as_non_root.then_some(true);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+//! Utilities for converting compose [`Secret`]s into Kubernetes [`k8s::Secret`]s.
+
+use std::{env, fs, path::Path};
+
+use color_eyre::eyre::{ensure, eyre, WrapErr};
+use compose_spec::{
+    secret::{Secret, Source},
+    Identifier,
+};
+use k8s_openapi::api::core::v1 as k8s;
+
+/// Attempt to convert a compose [`Secret`] into a [`k8s::Secret`].
+///
+/// The [`k8s::Secret`] has the same `name` as the secret and stores its contents under a key of
+/// the same name. Secrets with a `file` source are read from the host, relative to the directory
+/// of the compose file at `compose_file`. Secrets with an `environment` source are read from the
+/// environment Podlet is run in.
+///
+/// # Errors
+///
+/// Returns an error if the secret has an unsupported option, or its file or environment variable
+/// could not be read.
+pub(super) fn try_into_secret(
+    name: Identifier,
+    secret: Secret,
+    compose_file: Option<&Path>,
+) -> color_eyre::Result<k8s::Secret> {
+    try_into_secret_with(name, secret, compose_file, &|variable| {
+        env::var(variable).ok()
+    })
+}
+
+/// Attempt to convert a compose [`Secret`] into a [`k8s::Secret`], getting the values of
+/// environment variables with `lookup`.
+fn try_into_secret_with(
+    name: Identifier,
+    Secret {
+        source,
+        labels,
+        driver,
+        driver_opts,
+        extensions,
+    }: Secret,
+    compose_file: Option<&Path>,
+    lookup: &dyn Fn(&str) -> Option<String>,
+) -> color_eyre::Result<k8s::Secret> {
+    ensure!(driver.is_none(), "`driver` is not supported");
+    ensure!(driver_opts.is_empty(), "`driver_opts` is not supported");
+    ensure!(
+        extensions.is_empty(),
+        "compose extensions are not supported"
+    );
+
+    let content = match source {
+        Source::File(path) => {
+            let path = super::resolve_path(&path, compose_file)?;
+            fs::read_to_string(&path)
+                .wrap_err_with(|| format!("error reading secret file `{}`", path.display()))?
+        }
+        Source::Environment(variable) => lookup(&variable).ok_or_else(|| {
+            eyre!("environment variable `{variable}` is not set or is not valid unicode")
+        })?,
+    };
+
+    Ok(k8s::Secret {
+        metadata: super::object_meta(name.clone(), labels)?,
+        string_data: Some([(name.into(), content)].into()),
+        ..k8s::Secret::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn environment_source() {
+        let name = Identifier::new("api_token").expect("valid identifier");
+        let secret = Secret::from(Source::Environment("API_TOKEN".to_owned()));
+        let lookup = |name: &str| (name == "API_TOKEN").then(|| "hunter2".to_owned());
+
+        let k8s_secret = try_into_secret_with(name.clone(), secret.clone(), None, &lookup)
+            .expect("variable is set");
+        assert_eq!(
+            k8s_secret.string_data,
+            Some([("api_token".to_owned(), "hunter2".to_owned())].into())
+        );
+
+        let error =
+            try_into_secret_with(name, secret, None, &|_| None).expect_err("variable is unset");
+        assert!(format!("{error:?}").contains("`API_TOKEN`"), "{error:?}");
+    }
+}
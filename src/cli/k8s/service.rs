@@ -11,6 +11,7 @@ use color_eyre::{
 use compose_spec::{
     service::{
         build::Context,
+        deploy::resources::{Count, Device as ReservedDevice},
         device::CgroupRule,
         healthcheck::{self, Test},
         ports::{self, Port, Protocol},
@@ -25,18 +26,25 @@ use compose_spec::{
 use indexmap::{IndexMap, IndexSet};
 use k8s_openapi::{
     api::core::v1::{
-        Capabilities, Container, ContainerPort, EnvVar, ExecAction, PodSpec, Probe,
-        ResourceRequirements, SELinuxOptions, SecurityContext,
+        Capabilities, Container, ContainerPort, EnvVar, EnvVarSource, ExecAction, HostAlias,
+        Lifecycle, LifecycleHandler, PodSpec, Probe, ResourceRequirements, SELinuxOptions,
+        SecretKeySelector, SecurityContext,
     },
     apimachinery::pkg::api::resource::Quantity,
 };
 
-use crate::cli::{
-    compose::command_try_into_vec,
-    container::security_opt::{LabelOpt, SecurityOpt},
+use crate::{
+    cli::{
+        compose::{command_try_into_vec, secrets::SecretType, size, Hook, Hooks},
+        container::{
+            gpu,
+            security_opt::{LabelOpt, SecurityOpt},
+        },
+    },
+    warning,
 };
 
-use self::mount::tmpfs_and_volumes_try_into_volume_mounts;
+use self::mount::{configs_try_into_volume_mounts, tmpfs_and_volumes_try_into_volume_mounts};
 
 /// Fields from a [`compose_spec::Service`] which will be [added](Service::add_to_pod_spec()) to a
 /// [`PodSpec`]'s [`Container`]s and [`Volume`](k8s_openapi::api::core::v1::Volume)s.
@@ -47,17 +55,22 @@ pub(super) struct Service {
     resources: ContainerResources,
     security_context: ContainerSecurityContext,
     command: Option<Command>,
+    configs: Vec<ShortOrLong<Identifier, ConfigOrSecret>>,
     entrypoint: Option<Command>,
     environment: ListOrMap,
+    expose: IndexSet<Expose>,
+    extra_hosts: IndexMap<Hostname, IpAddr>,
     healthcheck: Option<Healthcheck>,
     image: Option<Image>,
     ports: Ports,
     pull_policy: Option<PullPolicy>,
+    secrets: Vec<ShortOrLong<Identifier, ConfigOrSecret>>,
     stdin_open: bool,
     tmpfs: Option<ItemOrList<AbsolutePath>>,
     tty: bool,
     volumes: Volumes,
     working_dir: Option<AbsolutePath>,
+    hooks: Hooks,
 }
 
 impl Service {
@@ -86,7 +99,7 @@ impl Service {
             container_name,
             credential_spec,
             depends_on,
-            deploy,
+            mut deploy,
             develop,
             device_cgroup_rules,
             devices,
@@ -151,6 +164,8 @@ impl Service {
             extensions,
         }: compose_spec::Service,
     ) -> Self {
+        let reserved_devices = gpu::take_reserved_devices(&mut deploy);
+
         Self {
             unsupported: Unsupported {
                 attach,
@@ -166,7 +181,6 @@ impl Service {
                 cpuset,
                 cgroup,
                 cgroup_parent,
-                configs,
                 credential_spec,
                 depends_on,
                 deploy,
@@ -178,11 +192,9 @@ impl Service {
                 dns_search,
                 domain_name,
                 env_file,
-                expose,
                 extends,
                 annotations,
                 external_links,
-                extra_hosts,
                 group_add,
                 hostname,
                 init,
@@ -205,7 +217,6 @@ impl Service {
                 restart,
                 runtime,
                 scale,
-                secrets,
                 shm_size,
                 stop_grace_period,
                 stop_signal,
@@ -221,6 +232,7 @@ impl Service {
                 cpus,
                 mem_limit,
                 mem_reservation,
+                reserved_devices,
             },
             security_context: ContainerSecurityContext {
                 cap_add,
@@ -231,25 +243,38 @@ impl Service {
                 user,
             },
             command,
+            configs,
             entrypoint,
             environment,
+            expose,
+            extra_hosts,
             healthcheck,
             image,
             ports,
             pull_policy,
+            secrets,
             stdin_open,
             tmpfs,
             tty,
             volumes,
             working_dir,
+            hooks: Hooks::default(),
         }
     }
 
+    /// Set the service's lifecycle [`Hooks`], which are converted into the [`Container`]'s
+    /// [`Lifecycle`].
+    pub(super) fn with_hooks(mut self, hooks: Hooks) -> Self {
+        self.hooks = hooks;
+        self
+    }
+
     /// Add the service to a [`PodSpec`]'s [`Container`]s and [`Volume`]s.
     ///
     /// # Errors
     ///
     /// Returns an error if an unsupported option was used or conversion of one of the fields fails.
+    #[allow(clippy::too_many_lines)]
     pub(super) fn add_to_pod_spec(self, spec: &mut PodSpec) -> color_eyre::Result<()> {
         let Self {
             unsupported,
@@ -257,29 +282,59 @@ impl Service {
             resources,
             security_context,
             command,
+            configs,
             entrypoint,
             environment,
+            expose,
+            extra_hosts,
             healthcheck,
             image,
             ports,
             pull_policy,
+            secrets,
             stdin_open,
             tmpfs,
             tty,
             volumes,
             working_dir,
+            hooks,
         } = self;
 
         unsupported.ensure_empty()?;
 
-        let volume_mounts =
+        add_host_aliases(extra_hosts, &mut spec.host_aliases);
+
+        let mut volume_mounts =
             tmpfs_and_volumes_try_into_volume_mounts(tmpfs, volumes, &name, &mut spec.volumes)
                 // converting `tmpfs` always succeeds
                 .wrap_err("error converting `volumes`")?;
+        volume_mounts.extend(
+            configs_try_into_volume_mounts(configs, &mut spec.volumes)
+                .wrap_err("error converting `configs`")?,
+        );
+
+        let (liveness_probe, startup_probe) = match healthcheck {
+            Some(Healthcheck::Command(command)) => healthcheck_command_try_into_probes(command)
+                .map(|(liveness_probe, startup_probe)| (Some(liveness_probe), startup_probe))
+                .wrap_err("error converting `healthcheck`")?,
+            // container image healthchecks are disabled by default in k8s
+            Some(Healthcheck::Disable) | None => (None, None),
+        };
+
+        let mut ports: Vec<_> = ports::into_long_iter(ports)
+            .map(port_try_into_container_port)
+            .collect::<Result<_, _>>()
+            .wrap_err("error converting `ports`")?;
+        // Exposed ports are added without a host port, so they are not published.
+        for expose in expose {
+            ports.extend(
+                expose_try_into_container_ports(expose).wrap_err("error converting `expose`")?,
+            );
+        }
 
         spec.containers.push(Container {
             name: name.into(),
-            resources: resources.into_resource_requirements(),
+            resources: resources.try_into_resource_requirements()?,
             security_context: security_context.try_into_security_context()?,
             args: command
                 .map(command_try_into_vec)
@@ -291,40 +346,12 @@ impl Service {
                 }
                 Command::List(entrypoint) => entrypoint,
             }),
-            env: (!environment.is_empty())
-                .then(|| {
-                    environment.into_map().map(|environment| {
-                        environment
-                            .into_iter()
-                            .map(|(name, value)| EnvVar {
-                                name: name.into(),
-                                value: value.map(Into::into),
-                                value_from: None,
-                            })
-                            .collect()
-                    })
-                })
-                .transpose()
-                .wrap_err("error converting `environment`")?,
-            liveness_probe: healthcheck
-                .and_then(|healthcheck| match healthcheck {
-                    Healthcheck::Command(command) => {
-                        Some(healthcheck_command_try_into_probe(command))
-                    }
-                    // container image healthchecks are disabled by default in k8s
-                    Healthcheck::Disable => None,
-                })
-                .transpose()
-                .wrap_err("error converting `healthcheck`")?,
+            env: environment_try_into_env(environment, secrets)?,
+            lifecycle: hooks_try_into_lifecycle(hooks)?,
+            liveness_probe,
+            startup_probe,
             image: Some(image.ok_or_eyre("`image` is required")?.into_inner()),
-            ports: (!ports.is_empty())
-                .then(|| {
-                    ports::into_long_iter(ports)
-                        .map(port_try_into_container_port)
-                        .collect()
-                })
-                .transpose()
-                .wrap_err("error converting `ports`")?,
+            ports: (!ports.is_empty()).then_some(ports),
             image_pull_policy: pull_policy
                 .map(|pull_policy| match pull_policy {
                     PullPolicy::Always => Ok("Always".to_owned()),
@@ -352,62 +379,297 @@ impl Service {
     }
 }
 
-/// Attempt to convert a [`compose_spec::Service`]'s [`healthcheck::Command`] into a Kubernetes
-/// [`Probe`] for use in the `liveness_probe` field of [`Container`].
+/// Add a service's `extra_hosts` to a [`PodSpec`]'s `host_aliases`.
+///
+/// Hostnames with the same IP address, including those from other services, are grouped into a
+/// single [`HostAlias`].
+fn add_host_aliases(
+    extra_hosts: IndexMap<Hostname, IpAddr>,
+    host_aliases: &mut Option<Vec<HostAlias>>,
+) {
+    if extra_hosts.is_empty() {
+        return;
+    }
+
+    let host_aliases = host_aliases.get_or_insert_with(Vec::new);
+    for (hostname, ip) in extra_hosts {
+        let hostname = String::from(hostname);
+        let ip = ip.to_string();
+        let host_alias = host_aliases
+            .iter_mut()
+            .find(|host_alias| host_alias.ip.as_ref() == Some(&ip));
+        if let Some(host_alias) = host_alias {
+            let hostnames = host_alias.hostnames.get_or_insert_with(Vec::new);
+            if !hostnames.contains(&hostname) {
+                hostnames.push(hostname);
+            }
+        } else {
+            host_aliases.push(HostAlias {
+                hostnames: Some(vec![hostname]),
+                ip: Some(ip),
+            });
+        }
+    }
+}
+
+/// Attempt to convert a service's `environment` and environment variable type `secrets` into
+/// [`EnvVar`]s.
+///
+/// Returns [`None`] if there are no environment variables.
+///
+/// # Errors
+///
+/// Returns an error if the `environment` has an invalid key or a secret is not an environment
+/// variable type secret.
+fn environment_try_into_env(
+    environment: ListOrMap,
+    secrets: Vec<ShortOrLong<Identifier, ConfigOrSecret>>,
+) -> color_eyre::Result<Option<Vec<EnvVar>>> {
+    let mut env: Vec<EnvVar> = environment
+        .into_map()
+        .wrap_err("error converting `environment`")?
+        .into_iter()
+        .map(|(name, value)| EnvVar {
+            name: name.into(),
+            value: value.map(Into::into),
+            value_from: None,
+        })
+        .collect();
+
+    for secret in secrets {
+        env.push(secret_try_into_env_var(secret).wrap_err("error converting `secrets`")?);
+    }
+
+    Ok((!env.is_empty()).then_some(env))
+}
+
+/// Attempt to convert a service secret of the environment variable type into an [`EnvVar`] which
+/// references the [`Secret`](k8s_openapi::api::core::v1::Secret) of the same name.
+///
+/// The name of the environment variable is the secret's `target`, or its `source` if not set.
+///
+/// # Errors
+///
+/// Returns an error if the secret is not an environment variable type secret, or an unsupported
+/// option is used.
+fn secret_try_into_env_var(
+    secret: ShortOrLong<Identifier, ConfigOrSecret>,
+) -> color_eyre::Result<EnvVar> {
+    let ConfigOrSecret {
+        source,
+        target,
+        uid,
+        gid,
+        mode,
+        mut extensions,
+    } = secret.into_long();
+
+    let secret_type = SecretType::take_from(&mut extensions)?;
+    if secret_type != SecretType::Env {
+        return Err(
+            eyre!("secret `{source}` must be an environment variable type secret").suggestion(
+                "Set `x-podlet: {type: env}` on the service's secret, \
+                    only secrets set as environment variables are supported",
+            ),
+        );
+    }
+    ensure!(
+        uid.is_none() && gid.is_none() && mode.is_none(),
+        "`uid`, `gid`, and `mode` are not supported for environment variable type secrets"
+    );
+    ensure!(
+        extensions.is_empty(),
+        "compose extensions are not supported"
+    );
+
+    let name = target
+        .map(|target| {
+            target
+                .into_os_string()
+                .into_string()
+                .map_err(|_| eyre!("secret `{source}` `target` must contain only valid UTF-8"))
+        })
+        .transpose()?
+        .unwrap_or_else(|| source.to_string());
+
+    Ok(EnvVar {
+        name,
+        value: None,
+        value_from: Some(EnvVarSource {
+            secret_key_ref: Some(SecretKeySelector {
+                key: source.to_string(),
+                name: Some(source.into()),
+                optional: None,
+            }),
+            ..EnvVarSource::default()
+        }),
+    })
+}
+
+/// Attempt to convert a service's lifecycle [`Hooks`] into a Kubernetes [`Lifecycle`].
+///
+/// Returns [`None`] if there are no hooks.
+///
+/// # Errors
+///
+/// Returns an error if there is more than one hook of a kind or a hook uses an unsupported option.
+fn hooks_try_into_lifecycle(
+    Hooks {
+        post_start,
+        pre_stop,
+    }: Hooks,
+) -> color_eyre::Result<Option<Lifecycle>> {
+    let post_start =
+        hooks_try_into_handler(post_start).wrap_err("error converting `post_start`")?;
+    let pre_stop = hooks_try_into_handler(pre_stop).wrap_err("error converting `pre_stop`")?;
+
+    Ok(
+        (post_start.is_some() || pre_stop.is_some()).then_some(Lifecycle {
+            post_start,
+            pre_stop,
+        }),
+    )
+}
+
+/// Attempt to convert lifecycle [`Hook`]s into an exec [`LifecycleHandler`].
+///
+/// # Errors
+///
+/// Returns an error if there is more than one hook or the hook uses an unsupported option.
+fn hooks_try_into_handler(hooks: Vec<Hook>) -> color_eyre::Result<Option<LifecycleHandler>> {
+    let mut hooks = hooks.into_iter();
+    let Some(Hook {
+        command,
+        user,
+        privileged,
+        working_dir,
+        environment,
+    }) = hooks.next()
+    else {
+        return Ok(None);
+    };
+
+    if hooks.next().is_some() {
+        return Err(eyre!("Kubernetes only supports one hook of each kind")
+            .suggestion("combine the commands into a single hook"));
+    }
+    ensure!(user.is_none(), "`user` is not supported");
+    ensure!(!privileged, "`privileged` is not supported");
+    ensure!(working_dir.is_none(), "`working_dir` is not supported");
+    ensure!(environment.is_empty(), "`environment` is not supported");
+
+    Ok(Some(LifecycleHandler {
+        exec: Some(ExecAction {
+            command: Some(command_try_into_vec(command)?),
+        }),
+        ..LifecycleHandler::default()
+    }))
+}
+
+/// Default `interval` of a compose healthcheck.
+const DEFAULT_HEALTHCHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Attempt to convert a [`compose_spec::Service`]'s [`healthcheck::Command`] into Kubernetes
+/// [`Probe`]s for use in the `liveness_probe` and `startup_probe` fields of [`Container`].
+///
+/// If the healthcheck has a `start_period`, a startup probe is also created so that slow starting
+/// containers are not killed before it has elapsed. It runs the check every `start_interval`, or
+/// `interval` if not set, with a `failureThreshold` covering the `start_period`. The liveness
+/// probe takes over once the startup probe succeeds.
 ///
 /// # Errors
 ///
 /// Returns an error if extensions are present or there was an error converting one of the
 /// [`Duration`]s into seconds.
-fn healthcheck_command_try_into_probe(
+fn healthcheck_command_try_into_probes(
     healthcheck::Command {
         test,
         interval,
         timeout,
         retries,
         start_period,
-        // k8s doesn't run probes during startup
-        start_interval: _,
+        start_interval,
         extensions,
     }: healthcheck::Command,
-) -> color_eyre::Result<Probe> {
+) -> color_eyre::Result<(Probe, Option<Probe>)> {
     ensure!(
         extensions.is_empty(),
         "compose extensions are not supported"
     );
 
-    Ok(Probe {
-        exec: test.map(|test| ExecAction {
-            command: Some(match test {
-                Test::Command(test) => test,
-                Test::ShellCommand(test) => vec!["/bin/sh".to_owned(), "-c".to_owned(), test],
-            }),
+    let exec = test.map(|test| ExecAction {
+        command: Some(match test {
+            Test::Command(test) => test,
+            Test::ShellCommand(test) => vec!["/bin/sh".to_owned(), "-c".to_owned(), test],
         }),
+    });
+    let timeout_seconds = Some(
+        timeout
+            .map(duration_round_seconds)
+            .map(TryInto::try_into)
+            .transpose()
+            .wrap_err("error converting `timeout`")?
+            // default timeout for compose is 30 seconds, for k8s its 1 second
+            .unwrap_or(30),
+    );
+
+    let startup_probe = if let (Some(exec), Some(start_period)) = (
+        &exec,
+        start_period.filter(|start_period| !start_period.is_zero()),
+    ) {
+        let period = duration_round_seconds(
+            start_interval
+                .or(interval)
+                .unwrap_or(DEFAULT_HEALTHCHECK_INTERVAL),
+        );
+        Some(Probe {
+            exec: Some(exec.clone()),
+            period_seconds: Some(
+                period
+                    .try_into()
+                    .wrap_err("error converting `start_interval`")?,
+            ),
+            timeout_seconds,
+            failure_threshold: Some(
+                duration_round_seconds(start_period)
+                    .div_ceil(period)
+                    .try_into()
+                    .wrap_err("error converting `start_period`")?,
+            ),
+            ..Probe::default()
+        })
+    } else {
+        if start_interval.is_some() {
+            warning::warn(
+                "healthcheck `start_interval` requires `test` and a `start_period`, ignoring it",
+            );
+        }
+        None
+    };
+
+    let liveness_probe = Probe {
+        exec,
         period_seconds: interval
             .map(duration_round_seconds)
             .map(TryInto::try_into)
             .transpose()
             .wrap_err("error converting `interval`")?,
-        timeout_seconds: Some(
-            timeout
-                .map(duration_round_seconds)
-                .map(TryInto::try_into)
-                .transpose()
-                .wrap_err("error converting `timeout`")?
-                // default timeout for compose is 30 seconds, for k8s its 1 second
-                .unwrap_or(30),
-        ),
+        timeout_seconds,
         failure_threshold: retries
             .map(TryInto::try_into)
             .transpose()
             .wrap_err("error converting `retries`")?,
+        // the liveness probe is not run until the startup probe succeeds
         initial_delay_seconds: start_period
+            .filter(|_| startup_probe.is_none())
             .map(duration_round_seconds)
             .map(TryInto::try_into)
             .transpose()
             .wrap_err("error converting `start_period`")?,
         ..Probe::default()
-    })
+    };
+
+    Ok((liveness_probe, startup_probe))
 }
 
 /// Round a [`Duration`] to the nearest whole seconds with a minimum of 1 second.
@@ -461,40 +723,78 @@ fn port_try_into_container_port(
             .wrap_err("error converting `published`")?,
         host_ip: host_ip.as_ref().map(ToString::to_string),
         protocol: protocol
-            .map(|protocol| match protocol {
-                Protocol::Tcp => Ok("TCP".to_owned()),
-                Protocol::Udp => Ok("UDP".to_owned()),
-                Protocol::Other(mut protocol) => {
-                    protocol.make_ascii_uppercase();
-                    ensure!(
-                        protocol == "SCTP",
-                        "only `UDP`, `TCP`, and `SCTP` are supported"
-                    );
-                    Ok(protocol)
-                }
-            })
+            .map(protocol_try_into_string)
             .transpose()
             .wrap_err("error converting `protocol`")?,
     })
 }
 
+/// Attempt to convert a [`compose_spec::Service`]'s [`Expose`] into Kubernetes [`ContainerPort`]s,
+/// one for each port in its range.
+///
+/// The ports do not have a `host_port`, so they are only reachable from within the pod.
+///
+/// # Errors
+///
+/// Returns an error if the protocol is not supported.
+fn expose_try_into_container_ports(
+    Expose { range, protocol }: Expose,
+) -> color_eyre::Result<impl Iterator<Item = ContainerPort>> {
+    let protocol = protocol
+        .map(protocol_try_into_string)
+        .transpose()
+        .wrap_err("error converting `protocol`")?;
+
+    Ok(range.into_iter().map(move |port| ContainerPort {
+        container_port: port.into(),
+        protocol: protocol.clone(),
+        ..ContainerPort::default()
+    }))
+}
+
+/// Attempt to convert a port [`Protocol`] into a Kubernetes protocol.
+///
+/// # Errors
+///
+/// Returns an error if the protocol is not TCP, UDP, or SCTP.
+fn protocol_try_into_string(protocol: Protocol) -> color_eyre::Result<String> {
+    match protocol {
+        Protocol::Tcp => Ok("TCP".to_owned()),
+        Protocol::Udp => Ok("UDP".to_owned()),
+        Protocol::Other(mut protocol) => {
+            protocol.make_ascii_uppercase();
+            ensure!(
+                protocol == "SCTP",
+                "only `UDP`, `TCP`, and `SCTP` are supported"
+            );
+            Ok(protocol)
+        }
+    }
+}
+
 /// Fields from a [`compose_spec::Service`] which are converted into a [`Container`]'s
 /// [`ResourceRequirements`].
 struct ContainerResources {
     cpus: Option<Cpus>,
     mem_limit: Option<ByteValue>,
     mem_reservation: Option<ByteValue>,
+    reserved_devices: Vec<ReservedDevice>,
 }
 
 impl ContainerResources {
-    /// Convert into [`ResourceRequirements`] for a Kubernetes [`Container`].
+    /// Attempt to convert into [`ResourceRequirements`] for a Kubernetes [`Container`].
     ///
     /// Returns [`None`] if no resource options are set.
-    fn into_resource_requirements(self) -> Option<ResourceRequirements> {
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the reserved devices are not GPUs or all GPUs were requested.
+    fn try_into_resource_requirements(self) -> color_eyre::Result<Option<ResourceRequirements>> {
         let Self {
             cpus,
             mem_limit,
             mem_reservation,
+            reserved_devices,
         } = self;
 
         let mut resources = None;
@@ -512,7 +812,7 @@ impl ContainerResources {
                 .get_or_insert_with(ResourceRequirements::default)
                 .limits
                 .get_or_insert_with(BTreeMap::default)
-                .insert("memory".to_owned(), Quantity(mem_limit.to_string()));
+                .insert("memory".to_owned(), size::into_quantity(mem_limit));
         }
 
         if let Some(mem_reservation) = mem_reservation {
@@ -520,10 +820,24 @@ impl ContainerResources {
                 .get_or_insert_with(ResourceRequirements::default)
                 .requests
                 .get_or_insert_with(BTreeMap::default)
-                .insert("memory".to_owned(), Quantity(mem_reservation.to_string()));
+                .insert("memory".to_owned(), size::into_quantity(mem_reservation));
+        }
+
+        let gpus = gpu::reserved_devices_try_into_gpu_count(reserved_devices)
+            .wrap_err("error converting `deploy.resources.reservations.devices`")?;
+        if let Some(gpus) = gpus {
+            let Count::Integer(gpus) = gpus else {
+                return Err(eyre!("Kubernetes does not support reserving all GPUs")
+                    .suggestion("set `count` to a specific number of GPUs"));
+            };
+            resources
+                .get_or_insert_with(ResourceRequirements::default)
+                .limits
+                .get_or_insert_with(BTreeMap::default)
+                .insert(gpu::NVIDIA_GPU.to_owned(), Quantity(gpus.to_string()));
         }
 
-        resources
+        Ok(resources)
     }
 }
 
@@ -691,7 +1005,6 @@ struct Unsupported {
     cpuset: CpuSet,
     cgroup: Option<Cgroup>,
     cgroup_parent: Option<String>,
-    configs: Vec<ShortOrLong<Identifier, ConfigOrSecret>>,
     credential_spec: Option<CredentialSpec>,
     depends_on: DependsOn,
     deploy: Option<Deploy>,
@@ -703,11 +1016,9 @@ struct Unsupported {
     dns_search: Option<ItemOrList<Hostname>>,
     domain_name: Option<Hostname>,
     env_file: Option<EnvFile>,
-    expose: IndexSet<Expose>,
     extends: Option<Extends>,
     annotations: ListOrMap,
     external_links: IndexSet<Link>,
-    extra_hosts: IndexMap<Hostname, IpAddr>,
     group_add: IndexSet<IdOrName>,
     hostname: Option<Hostname>,
     init: bool,
@@ -730,7 +1041,6 @@ struct Unsupported {
     restart: Option<Restart>,
     runtime: Option<String>,
     scale: Option<u64>,
-    secrets: Vec<ShortOrLong<Identifier, ConfigOrSecret>>,
     shm_size: Option<ByteValue>,
     stop_grace_period: Option<Duration>,
     stop_signal: Option<String>,
@@ -764,7 +1074,6 @@ impl Unsupported {
             cpuset,
             cgroup,
             cgroup_parent,
-            configs,
             credential_spec,
             depends_on,
             deploy,
@@ -776,11 +1085,9 @@ impl Unsupported {
             dns_search,
             domain_name,
             env_file,
-            expose,
             extends,
             annotations,
             external_links,
-            extra_hosts,
             group_add,
             hostname,
             init,
@@ -803,7 +1110,6 @@ impl Unsupported {
             restart,
             runtime,
             scale,
-            secrets,
             shm_size,
             stop_grace_period,
             stop_signal,
@@ -829,7 +1135,6 @@ impl Unsupported {
             ("cpuset", cpuset.is_empty()),
             ("cgroup", cgroup.is_none()),
             ("cgroup_parent", cgroup_parent.is_none()),
-            ("configs", configs.is_empty()),
             ("credential_spec", credential_spec.is_none()),
             ("depends_on", depends_on_is_empty(depends_on)),
             ("deploy", deploy.is_none()),
@@ -837,7 +1142,6 @@ impl Unsupported {
             ("device_cgroup_rules", device_cgroup_rules.is_empty()),
             ("domainname", domain_name.is_none()),
             ("env_file", env_file.is_none()),
-            ("expose", expose.is_empty()),
             ("extends", extends.is_none()),
             ("external_links", external_links.is_empty()),
             ("group_add", group_add.is_empty()),
@@ -863,7 +1167,6 @@ impl Unsupported {
             ("profiles", profiles.is_empty()),
             ("runtime", runtime.is_none()),
             ("scale", scale.is_none()),
-            ("secrets", secrets.is_empty()),
             ("shm_size", shm_size.is_none()),
             ("stop_signal", stop_signal.is_none()),
             ("storage_opt", storage_opt.is_empty()),
@@ -882,7 +1185,6 @@ impl Unsupported {
             ("dns", dns.is_none()),
             ("dns_opt", dns_opt.is_empty()),
             ("dns_search", dns_search.is_none()),
-            ("extra_hosts", extra_hosts.is_empty()),
             ("hostname", hostname.is_none()),
             ("init", !init),
             ("ipc", ipc.is_none()),
@@ -918,7 +1220,7 @@ impl Unsupported {
                 eyre!("Kubernetes pod containers do not directly support devices")
                     .suggestion("try using a bind mount instead"),
             );
-        };
+        }
 
         ensure!(
             extensions.is_empty(),
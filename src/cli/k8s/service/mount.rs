@@ -2,6 +2,8 @@
 //! [`VolumeMount`] and [`Volume`] for a [`Container`](k8s_openapi::api::core::v1::Container) and
 //! its [`PodSpec`](k8s_openapi::api::core::v1::PodSpec).
 
+use std::path::Path;
+
 use color_eyre::eyre::{ensure, eyre, WrapErr};
 use compose_spec::{
     service::{
@@ -10,18 +12,20 @@ use compose_spec::{
             mount::{self, Bind, BindOptions, Common, Tmpfs, TmpfsOptions, VolumeOptions},
             Mount,
         },
-        AbsolutePath, Volumes,
+        AbsolutePath, ConfigOrSecret, Volumes,
     },
-    Identifier, ItemOrList,
+    Identifier, ItemOrList, ShortOrLong,
 };
 use k8s_openapi::{
     api::core::v1::{
-        EmptyDirVolumeSource, HostPathVolumeSource, PersistentVolumeClaimVolumeSource, Volume,
-        VolumeMount,
+        ConfigMapVolumeSource, EmptyDirVolumeSource, HostPathVolumeSource, KeyToPath,
+        PersistentVolumeClaimVolumeSource, Volume, VolumeMount,
     },
     apimachinery::pkg::api::resource::Quantity,
 };
 
+use crate::warning;
+
 /// Attempt to convert the `tmpfs` and `volumes` fields from a [`compose_spec::Service`] into
 /// [`VolumeMount`]s.
 ///
@@ -50,6 +54,113 @@ pub(super) fn tmpfs_and_volumes_try_into_volume_mounts(
         .collect()
 }
 
+/// Attempt to convert the `configs` field from a [`compose_spec::Service`] into [`VolumeMount`]s.
+///
+/// Each config is mounted from a [`Volume`] sourced from the [`ConfigMap`] of the same name, which
+/// is added to `pod_volumes` if not already present.
+///
+/// [`ConfigMap`]: k8s_openapi::api::core::v1::ConfigMap
+///
+/// # Errors
+///
+/// Returns an error if an unsupported option is present.
+pub(super) fn configs_try_into_volume_mounts(
+    configs: Vec<ShortOrLong<Identifier, ConfigOrSecret>>,
+    pod_volumes: &mut Option<Vec<Volume>>,
+) -> color_eyre::Result<Vec<VolumeMount>> {
+    configs
+        .into_iter()
+        .map(|config| {
+            let config = config.into_long();
+            let source = config.source.clone();
+            let (volume_mount, volume) = config_try_into_volume_mount(config)
+                .wrap_err_with(|| format!("error converting config `{source}`"))?;
+            let pod_volumes = pod_volumes.get_or_insert_with(Vec::new);
+            if !pod_volumes
+                .iter()
+                .any(|pod_volume| pod_volume.name == volume.name)
+            {
+                pod_volumes.push(volume);
+            }
+            Ok(volume_mount)
+        })
+        .collect()
+}
+
+/// Attempt to convert a service's [`ConfigOrSecret`] into a [`VolumeMount`] and its corresponding
+/// [`Volume`].
+///
+/// If not set, `target` defaults to `/<config-name>`. The `mode` is set on the config's item in
+/// the [`Volume`]. Kubernetes cannot set the owner of the file, so `uid` and `gid` are ignored with
+/// a warning.
+///
+/// # Errors
+///
+/// Returns an error if an unsupported option is present or `target` is not valid UTF-8.
+fn config_try_into_volume_mount(
+    ConfigOrSecret {
+        source,
+        target,
+        uid,
+        gid,
+        mode,
+        extensions,
+    }: ConfigOrSecret,
+) -> color_eyre::Result<(VolumeMount, Volume)> {
+    for (option, id) in [("uid", uid), ("gid", gid)] {
+        if let Some(id) = id {
+            warning::warn(format_args!(
+                "Kubernetes cannot set the owner of config files, ignoring `{option}: {id}` \
+                    of config `{source}`"
+            ));
+        }
+    }
+    ensure!(
+        extensions.is_empty(),
+        "compose extensions are not supported"
+    );
+
+    // Relative targets are relative to the container's root.
+    let mount_path = target
+        .map_or_else(
+            || Path::new("/").join(source.as_str()),
+            |target| Path::new("/").join(target),
+        )
+        .into_os_string()
+        .into_string()
+        .map_err(|_| eyre!("`target` must only contain valid UTF-8"))?;
+
+    let name = format!("{source}-config");
+    let key = String::from(source.clone());
+
+    let volume_mount = VolumeMount {
+        mount_path,
+        name: name.clone(),
+        read_only: Some(true),
+        sub_path: Some(key.clone()),
+        ..VolumeMount::default()
+    };
+
+    let volume = Volume {
+        name,
+        config_map: Some(ConfigMapVolumeSource {
+            name: Some(source.into()),
+            items: Some(vec![KeyToPath {
+                key: key.clone(),
+                mode: mode
+                    .map(i32::try_from)
+                    .transpose()
+                    .wrap_err("`mode` is too large")?,
+                path: key,
+            }]),
+            ..ConfigMapVolumeSource::default()
+        }),
+        ..Volume::default()
+    };
+
+    Ok((volume_mount, volume))
+}
+
 /// Attempt to convert a volume [`Mount`] from a [`compose_spec::Service`] into a [`VolumeMount`]
 /// and its corresponding [`Volume`].
 ///
@@ -85,14 +196,25 @@ fn volume_try_into_volume_mount(
     }: mount::Volume,
     container_name: &Identifier,
 ) -> color_eyre::Result<(VolumeMount, Volume)> {
+    let VolumeOptions {
+        nocopy,
+        subpath,
+        extensions,
+    } = volume.unwrap_or_default();
+    ensure!(!nocopy, "`volume.nocopy` is not supported");
     ensure!(
-        volume.as_ref().map_or(true, VolumeOptions::is_empty),
-        "additional `volume` options are not supported"
+        extensions.is_empty(),
+        "compose extensions are not supported"
     );
 
     let anonymous_volume = source.is_none();
+    ensure!(
+        subpath.is_none() || !anonymous_volume,
+        "`volume.subpath` requires a named volume `source`"
+    );
     let source = source.map_or(Source::Other { container_name }, Source::Volume);
-    let volume_mount = common_try_into_volume_mount(common, source)?;
+    let mut volume_mount = common_try_into_volume_mount(common, source)?;
+    volume_mount.sub_path = subpath.map(|subpath| subpath.display().to_string());
 
     let name = volume_mount.name.clone();
     let volume = if anonymous_volume {
@@ -183,7 +305,13 @@ fn tmpfs_try_into_volume_mount(
         extensions,
     } = tmpfs.unwrap_or_default();
 
-    ensure!(mode.is_none(), "`tmpfs.mode` is not supported");
+    // `emptyDir` volumes are created world-writable
+    if let Some(mode) = mode {
+        ensure!(
+            mode == 0o777 || mode == 0o1777,
+            "`tmpfs.mode` `{mode:o}` is not supported, only `777` and `1777` are supported"
+        );
+    }
     ensure!(
         extensions.is_empty(),
         "compose extensions are not supported"
@@ -195,7 +323,13 @@ fn tmpfs_try_into_volume_mount(
         name: volume_mount.name.clone(),
         empty_dir: Some(EmptyDirVolumeSource {
             medium: Some("Memory".to_owned()),
-            size_limit: size.map(|size| Quantity(size.to_string())),
+            size_limit: size
+                .map(|size| {
+                    size.into_bytes()
+                        .map(|size| Quantity(size.to_string()))
+                        .ok_or_else(|| eyre!("`tmpfs.size` `{size}` is too large"))
+                })
+                .transpose()?,
         }),
         ..Volume::default()
     };
@@ -249,7 +383,7 @@ enum Source<'a> {
     Other { container_name: &'a Identifier },
 }
 
-impl<'a> Source<'a> {
+impl Source<'_> {
     /// Convert source into a `name` for a [`Volume`].
     ///
     /// If [`Other`](Self::Other), the `container_name` is combined with the `mount_path` to create
@@ -0,0 +1,88 @@
+//! Markdown manifest of generated [`File`]s, written with the `--manifest` option.
+
+use std::fmt::Write;
+
+use super::{unit::Unit, File};
+
+/// Create a Markdown manifest listing each generated file, its kind, and the other units it
+/// references or depends on.
+///
+/// Dependencies on services generated by Quadlet, e.g. `db.service`, are listed as the Quadlet
+/// file they are generated from, e.g. `db.container`, if it is one of the `files`.
+pub(super) fn render(files: &[File]) -> String {
+    // Service name generated by Quadlet and the path of the file it is generated from.
+    let services: Vec<(String, String)> = files
+        .iter()
+        .filter_map(|file| {
+            file.as_quadlet_file().map(|quadlet_file| {
+                (
+                    quadlet_file.resource.name_to_service(&quadlet_file.name),
+                    file.relative_path().display().to_string(),
+                )
+            })
+        })
+        .collect();
+
+    let mut manifest = String::from("# Generated files\n\n");
+    for file in files {
+        let path = file.relative_path();
+        writeln!(manifest, "- `{}` ({})", path.display(), file.kind())
+            .expect("write to String never fails");
+
+        let Some(quadlet_file) = file.as_quadlet_file() else {
+            continue;
+        };
+        for reference in quadlet_file.resource.references() {
+            let kind = reference
+                .rsplit_once('.')
+                .map_or(reference, |(_, extension)| extension);
+            writeln!(manifest, "  - {kind} `{reference}`").expect("write to String never fails");
+        }
+        for dependency in quadlet_file.unit.iter().flat_map(Unit::dependencies) {
+            let dependency = services
+                .iter()
+                .find_map(|(service, path)| (service == dependency).then_some(path.as_str()))
+                .unwrap_or(dependency);
+            writeln!(manifest, "  - depends on `{dependency}`")
+                .expect("write to String never fails");
+        }
+    }
+
+    manifest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::compose::{tests::convert, Compose};
+    use super::*;
+
+    #[test]
+    fn references_and_dependencies() {
+        let yaml = "
+            services:
+              web:
+                image: web
+                depends_on:
+                  - db
+                networks:
+                  - backend
+              db:
+                image: db
+                networks:
+                  - backend
+            networks:
+              backend: {}
+        ";
+        let files = convert(Compose::default(), yaml).expect("compose file converts");
+        assert_eq!(
+            render(&files),
+            "# Generated files\n\n\
+                - `web.container` (container)\n\
+                \x20 - network `backend.network`\n\
+                \x20 - depends on `db.container`\n\
+                - `db.container` (container)\n\
+                \x20 - network `backend.network`\n\
+                - `backend.network` (network)\n"
+        );
+    }
+}
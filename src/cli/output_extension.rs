@@ -0,0 +1,136 @@
+//! Overrides of the extensions of generated [`File`]s, set with the `--output-extension-map`
+//! option.
+
+use std::{collections::HashMap, path::PathBuf, str::FromStr};
+
+use clap::builder::{NonEmptyStringValueParser, TypedValueParser};
+use color_eyre::eyre::{ensure, eyre, OptionExt};
+
+use crate::quadlet::{kube::YamlFile, Resource};
+
+use super::File;
+
+/// Kinds of [`File`]s which have an extension, see [`File::kind()`].
+const KINDS: [&str; 11] = [
+    "container",
+    "pod",
+    "kube",
+    "network",
+    "volume",
+    "build",
+    "image",
+    "kubernetes",
+    "config-map",
+    "persistent-volume-claim",
+    "compose",
+];
+
+/// Extension to use for generated [`File`]s of a kind, parsed from "{kind}={extension}".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct ExtensionMapping {
+    /// Kind of the files, see [`File::kind()`].
+    kind: String,
+
+    /// Extension to use instead of the default, without a leading `.`.
+    extension: String,
+}
+
+impl FromStr for ExtensionMapping {
+    type Err = color_eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, extension) = s
+            .split_once('=')
+            .ok_or_eyre("mapping must be in the form `{kind}={extension}`")?;
+        ensure!(
+            KINDS.contains(&kind),
+            "unknown file kind `{kind}`, must be one of: {}",
+            KINDS.join(", ")
+        );
+
+        let extension = extension.strip_prefix('.').unwrap_or(extension);
+        ensure!(
+            !extension.is_empty() && !extension.contains('/'),
+            "invalid extension `{extension}` for `{kind}` files"
+        );
+
+        Ok(Self {
+            kind: kind.to_owned(),
+            extension: extension.to_owned(),
+        })
+    }
+}
+
+/// Create a [`TypedValueParser`] for parsing an [`ExtensionMapping`].
+pub(super) fn parser() -> impl TypedValueParser<Value = ExtensionMapping> {
+    NonEmptyStringValueParser::new().try_map(|mapping| {
+        mapping
+            .parse::<ExtensionMapping>()
+            .map_err(|error| eyre!("invalid mapping `{mapping}`: {error}"))
+    })
+}
+
+/// Change the extension of each of the `files` to the one given in `mappings` for its kind.
+///
+/// `Yaml=` and `ConfigMap=` paths of `.kube` files which reference one of the `files` are updated
+/// to the renamed file.
+pub(super) fn apply(files: Vec<File>, mappings: &[ExtensionMapping]) -> Vec<File> {
+    if mappings.is_empty() {
+        return files;
+    }
+
+    let mut renamed = HashMap::new();
+    let mut files: Vec<File> = files
+        .into_iter()
+        .map(|file| {
+            let Some(ExtensionMapping { extension, .. }) = mappings
+                .iter()
+                .rev()
+                .find(|mapping| mapping.kind == file.kind())
+            else {
+                return file;
+            };
+            let path = file.relative_path();
+            let file = file.with_extension(extension.clone());
+            renamed.insert(path, file.relative_path());
+            file
+        })
+        .collect();
+
+    let rename = |path: &mut PathBuf| {
+        if let Some(new_path) = renamed.get(path) {
+            path.clone_from(new_path);
+        }
+    };
+    for file in files.iter_mut().filter_map(File::as_quadlet_file_mut) {
+        if let Resource::Kube(kube) = &mut file.resource {
+            if let YamlFile::Path(path) = &mut kube.yaml {
+                rename(path);
+            }
+            kube.config_map.iter_mut().for_each(rename);
+        }
+    }
+
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let mapping: ExtensionMapping = "kubernetes=.k8s.yaml".parse().expect("valid mapping");
+        assert_eq!(
+            mapping,
+            ExtensionMapping {
+                kind: "kubernetes".to_owned(),
+                extension: "k8s.yaml".to_owned(),
+            }
+        );
+
+        for invalid in ["kubernetes", "unknown=yaml", "container=", "container=a/b"] {
+            assert!(invalid.parse::<ExtensionMapping>().is_err(), "{invalid}");
+        }
+    }
+}
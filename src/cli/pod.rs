@@ -168,6 +168,7 @@ impl From<Create> for quadlet::Pod {
             pod_name,
             publish_port,
             volume,
+            ..Self::default()
         }
     }
 }
@@ -1,19 +1,53 @@
-use std::fmt::{self, Display, Formatter};
+use std::{
+    fmt::{self, Display, Formatter},
+    path::PathBuf,
+};
 
 use clap::{Args, ValueEnum};
 use compose_spec::service::Restart;
 
+/// Extra time, in seconds, systemd waits for a unit to stop beyond its container's stop timeout.
+///
+/// Gives Podman time to finish stopping the container after it is killed.
+pub const TIMEOUT_STOP_MARGIN: u64 = 10;
+
 #[derive(Args, Default, Debug, Clone, PartialEq, Eq)]
 pub struct Service {
     /// Configure if and when the service should be restarted
     #[arg(long, value_name = "POLICY")]
     restart: Option<RestartConfig>,
+
+    /// Time, in seconds, systemd waits for the service to stop before killing it
+    #[arg(skip)]
+    timeout_stop_sec: Option<u64>,
+
+    /// Read environment variables for the service from a file
+    ///
+    /// The file is read by systemd, not Podman,
+    /// so the variables are not set in the container.
+    ///
+    /// Converts to "EnvironmentFile=PATH"
+    ///
+    /// Can be specified multiple times
+    #[arg(long, value_name = "PATH")]
+    environment_file: Vec<PathBuf>,
 }
 
 impl Service {
     pub fn is_empty(&self) -> bool {
         *self == Self::default()
     }
+
+    /// Set `TimeoutStopSec=` to the container's `stop_timeout` plus [`TIMEOUT_STOP_MARGIN`], so
+    /// systemd does not kill the unit before Podman stops the container.
+    pub fn set_stop_timeout(&mut self, stop_timeout: u64) {
+        self.timeout_stop_sec = Some(stop_timeout.saturating_add(TIMEOUT_STOP_MARGIN));
+    }
+
+    /// Add files systemd reads environment variables for the service from.
+    pub fn extend_environment_files(&mut self, environment_files: Vec<PathBuf>) {
+        self.environment_file.extend(environment_files);
+    }
 }
 
 impl Display for Service {
@@ -22,6 +56,12 @@ impl Display for Service {
         if let Some(restart) = self.restart.and_then(|restart| restart.to_possible_value()) {
             writeln!(f, "Restart={}", restart.get_name())?;
         }
+        if let Some(timeout_stop_sec) = self.timeout_stop_sec {
+            writeln!(f, "TimeoutStopSec={timeout_stop_sec}")?;
+        }
+        for environment_file in &self.environment_file {
+            writeln!(f, "EnvironmentFile={}", environment_file.display())?;
+        }
         Ok(())
     }
 }
@@ -30,6 +70,7 @@ impl From<RestartConfig> for Service {
     fn from(restart: RestartConfig) -> Self {
         Self {
             restart: Some(restart),
+            ..Self::default()
         }
     }
 }
@@ -1,11 +1,20 @@
-use std::fmt::{self, Display, Formatter};
+use std::{
+    fmt::{self, Display, Formatter},
+    time::Duration,
+};
 
-use clap::Args;
+use clap::{
+    builder::{NonEmptyStringValueParser, TypedValueParser},
+    Args,
+};
 use color_eyre::{
     eyre::{self, bail, eyre},
     Section,
 };
-use compose_spec::service::{Condition, Dependency};
+use compose_spec::{
+    duration,
+    service::{Condition, Dependency},
+};
 use serde::Serialize;
 
 use crate::serde::quadlet::quote_spaces_join_space;
@@ -83,6 +92,30 @@ pub struct Unit {
         skip_serializing_if = "Vec::is_empty"
     )]
     after: Vec<String>,
+
+    /// Interval in which the unit may only be started --start-limit-burst times
+    ///
+    /// Converts to "StartLimitIntervalSec=DURATION"
+    ///
+    /// The duration is given in the same format as compose durations, e.g. "1m30s".
+    #[arg(long, value_name = "DURATION", value_parser = start_limit_interval_parser())]
+    #[serde(rename = "StartLimitIntervalSec")]
+    start_limit_interval: Option<String>,
+
+    /// Maximum number of times the unit may be started within the --start-limit-interval
+    ///
+    /// Converts to "StartLimitBurst=COUNT"
+    #[arg(long, value_name = "COUNT")]
+    start_limit_burst: Option<u64>,
+}
+
+/// Create a [`TypedValueParser`] for parsing the `start_limit_interval` field of [`Unit`].
+fn start_limit_interval_parser() -> impl TypedValueParser<Value = String> {
+    NonEmptyStringValueParser::new().try_map(|duration| {
+        duration::parse(&duration)
+            .map(duration::to_string)
+            .map_err(|error| eyre!(error).wrap_err(format!("invalid duration `{duration}`")))
+    })
 }
 
 impl Unit {
@@ -95,6 +128,8 @@ impl Unit {
             binds_to,
             before,
             after,
+            start_limit_interval,
+            start_limit_burst,
         } = self;
 
         description.is_none()
@@ -103,6 +138,32 @@ impl Unit {
             && binds_to.is_empty()
             && before.is_empty()
             && after.is_empty()
+            && start_limit_interval.is_none()
+            && start_limit_burst.is_none()
+    }
+
+    /// Units this unit depends on, from `Wants=`, `Requires=`, and `BindsTo=`.
+    pub fn dependencies(&self) -> impl Iterator<Item = &str> {
+        self.wants
+            .iter()
+            .chain(&self.requires)
+            .chain(&self.binds_to)
+            .map(String::as_str)
+    }
+
+    /// Set `Description=` if the unit does not already have a description.
+    pub fn set_default_description(&mut self, description: String) {
+        self.description.get_or_insert(description);
+    }
+
+    /// Set `StartLimitIntervalSec=`, the interval in which the unit's restarts are limited.
+    pub fn set_start_limit_interval(&mut self, start_limit_interval: Duration) {
+        self.start_limit_interval = Some(duration::to_string(start_limit_interval));
+    }
+
+    /// Set `StartLimitBurst=`, limiting the number of times the unit is restarted.
+    pub fn set_start_limit_burst(&mut self, start_limit_burst: u64) {
+        self.start_limit_burst = Some(start_limit_burst);
     }
 
     /// Add a compose [`Service`](compose_spec::Service) [`Dependency`] to the unit.
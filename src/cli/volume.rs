@@ -2,7 +2,7 @@ mod opt;
 
 use clap::{Args, Subcommand};
 
-pub use self::opt::Opt;
+pub use self::opt::{Opt, ParseOptError};
 
 #[derive(Subcommand, Debug, Clone, PartialEq)]
 pub enum Volume {
@@ -27,6 +27,13 @@ impl Opt {
             ("copy", None) => Ok(Self::Copy),
             ("o", Some(options)) => Ok(Self::Mount(options.split(',').map(Mount::parse).collect())),
             ("image", Some(image)) => Ok(Self::Image(image)),
+            ("copy", Some(value)) => Err(ParseOptError::UnexpectedValue {
+                option: "copy",
+                value,
+            }),
+            ("type" | "device" | "o" | "image", None) => {
+                Err(ParseOptError::MissingValue(option.to_owned()))
+            }
             (option, value) => Err(ParseOptError::InvalidVolumeDriverOption(
                 value.map_or_else(|| option.into(), |value| format!("{option}={value}")),
             )),
@@ -79,6 +86,10 @@ impl FromStr for Opt {
 pub enum ParseOptError {
     #[error("`{0}` is not a valid volume driver option")]
     InvalidVolumeDriverOption(String),
+    #[error("volume driver option `{0}` requires a value")]
+    MissingValue(String),
+    #[error("volume driver option `{option}` does not take a value, found `{value}`")]
+    UnexpectedValue { option: &'static str, value: String },
 }
 
 /// Mount options
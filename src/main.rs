@@ -16,6 +16,7 @@ mod cli;
 mod escape;
 mod quadlet;
 mod serde;
+mod warning;
 
 use clap::Parser;
 use color_eyre::eyre;
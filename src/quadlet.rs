@@ -44,19 +44,26 @@ pub struct File {
 }
 
 impl Display for File {
+    /// Sections are separated by a single blank line and the file ends with exactly one newline.
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        if let Some(unit) = &self.unit {
-            writeln!(f, "{unit}")?;
+        let sections = [
+            self.unit.as_ref().map(ToString::to_string),
+            Some(format!("{}{}", self.resource, self.globals)),
+            self.service.as_ref().map(ToString::to_string),
+            self.install.as_ref().map(ToString::to_string),
+        ];
+
+        let mut sections = sections
+            .iter()
+            .flatten()
+            .map(|section| section.trim_matches('\n'))
+            .filter(|section| !section.is_empty());
+
+        if let Some(first) = sections.next() {
+            writeln!(f, "{first}")?;
         }
-
-        write!(f, "{}{}", self.resource, self.globals)?;
-
-        if let Some(service) = &self.service {
-            write!(f, "\n{service}")?;
-        }
-
-        if let Some(install) = &self.install {
-            write!(f, "\n{install}")?;
+        for section in sections {
+            write!(f, "\n{section}\n")?;
         }
 
         Ok(())
@@ -162,12 +169,69 @@ impl From<Image> for Resource {
     }
 }
 
+/// Network names without options, e.g. `backend.network` from `backend.network:ip=10.0.0.2`.
+fn network_names(networks: &[String]) -> impl Iterator<Item = &str> {
+    networks.iter().map(|network| {
+        network
+            .split_once(':')
+            .map_or(network.as_str(), |(name, _)| name)
+    })
+}
+
+/// The source of a named `volume`, if it has one.
+fn named_volume(volume: &container::Volume) -> Option<&str> {
+    match &volume.source {
+        Some(container::volume::Source::NamedVolume(source)) => Some(source),
+        _ => None,
+    }
+}
+
 impl Resource {
     /// The extension that should be used for the generated file.
     pub fn extension(&self) -> &'static str {
         ResourceKind::from(self).as_str()
     }
 
+    /// Names of other Quadlet files the resource references, e.g. `backend.network` from
+    /// `Network=backend.network`.
+    ///
+    /// Only references ending in a Quadlet file extension are returned. Network options and
+    /// volume paths are removed.
+    pub fn references(&self) -> Vec<&str> {
+        let references = match self {
+            Self::Container(container) => {
+                let mut references: Vec<_> = network_names(&container.network).collect();
+                references.extend(container.volume.iter().filter_map(named_volume));
+                references.extend(container.mount.iter().filter_map(|mount| match mount {
+                    container::Mount::Volume(volume) => volume.source.as_deref(),
+                    _ => None,
+                }));
+                references.extend(container.pod.as_deref());
+                references.push(&container.image);
+                references
+            }
+            Self::Pod(pod) => {
+                let mut references: Vec<_> = network_names(&pod.network).collect();
+                references.extend(pod.volume.iter().filter_map(named_volume));
+                references
+            }
+            Self::Kube(kube) => network_names(&kube.network).collect(),
+            Self::Network(_) | Self::Volume(_) | Self::Build(_) | Self::Image(_) => Vec::new(),
+        };
+
+        references
+            .into_iter()
+            .filter(|reference| {
+                reference.rsplit_once('.').is_some_and(|(_, extension)| {
+                    matches!(
+                        extension,
+                        "container" | "pod" | "kube" | "network" | "volume" | "build" | "image"
+                    )
+                })
+            })
+            .collect()
+    }
+
     /// Takes a file name (no extension) and returns the corresponding service file name
     /// generated by Quadlet.
     pub fn name_to_service(&self, name: &str) -> String {
@@ -306,6 +370,9 @@ pub trait Downgrade {
     ///
     /// Returns an error if the given [`PodmanVersion`] does not support a used Quadlet option or
     /// the type of Quadlet file.
+    ///
+    /// The unsupported option is removed before returning a [`DowngradeError::Option`], so calling
+    /// downgrade again continues with the remaining options.
     fn downgrade(&mut self, version: PodmanVersion) -> Result<(), DowngradeError>;
 }
 
@@ -429,7 +496,7 @@ impl AutoUpdate {
                 .strip_prefix(Self::LABEL_KEY)
                 .and_then(|label| label.strip_prefix('='))
                 .and_then(|value| value.parse().ok())
-                .map_or(true, |value| {
+                .is_none_or(|value| {
                     auto_update = Some(value);
                     false
                 })
@@ -512,3 +579,42 @@ impl HostPaths for Context {
         .into_iter()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use compose_spec::service::Restart;
+
+    use super::*;
+
+    #[test]
+    fn file_display_spacing() {
+        let file = File {
+            name: "test".into(),
+            unit: Some(Unit::default()),
+            resource: Container {
+                image: "image".into(),
+                ..Container::default()
+            }
+            .into(),
+            globals: Globals {
+                global_args: Some("--log-level debug".into()),
+                ..Globals::default()
+            },
+            service: Some(Service::from(Restart::Always)),
+            install: Some(Install {
+                wanted_by: vec!["default.target".into()],
+                required_by: Vec::new(),
+            }),
+        };
+        let file = file.to_string();
+
+        assert!(
+            file.starts_with("[Unit]\n\n[Container]\n"),
+            "file: {file:?}"
+        );
+        assert!(file.ends_with('\n'), "file: {file:?}");
+        assert!(!file.ends_with("\n\n"), "file: {file:?}");
+        assert_eq!(file.matches("\n\n[").count(), 3, "file: {file:?}");
+        assert!(!file.contains("\n\n\n"), "file: {file:?}");
+    }
+}
@@ -1,5 +1,6 @@
 mod device;
 mod mount;
+pub(super) mod podman_args;
 mod rootfs;
 pub mod volume;
 
@@ -20,7 +21,7 @@ use smart_default::SmartDefault;
 
 use crate::serde::{
     quadlet::{quote_spaces_join_colon, quote_spaces_join_space},
-    serialize_display_seq, skip_true,
+    serialize_display_seq,
 };
 
 pub use self::{device::Device, mount::Mount, rootfs::Rootfs, volume::Volume};
@@ -216,9 +217,9 @@ pub struct Container {
 
     /// If `read_only` is set to `true`, mount a read-write tmpfs on
     /// `/dev`, `/dev/shm`, `/run`, `/tmp`, and `/var/tmp`.
-    #[serde(skip_serializing_if = "skip_true")]
-    #[default = true]
-    pub read_only_tmpfs: bool,
+    ///
+    /// Podman defaults to `true` if not set.
+    pub read_only_tmpfs: Option<bool>,
 
     /// The rootfs to use for the container.
     pub rootfs: Option<Rootfs>,
@@ -327,6 +328,7 @@ impl Downgrade for Container {
 
         if version < PodmanVersion::V5_0 {
             if self.notify.is_healthy() {
+                self.notify = Notify::default();
                 if version < PodmanVersion::V4_7 {
                     return Err(DowngradeError::Option {
                         quadlet_option: "Notify",
@@ -334,7 +336,6 @@ impl Downgrade for Container {
                         supported_version: PodmanVersion::V4_7,
                     });
                 }
-                self.notify = Notify::default();
                 self.push_arg("sdnotify", "healthy");
             }
 
@@ -418,8 +419,7 @@ impl Container {
 
     /// Remove Quadlet options added in Podman v4.8.0
     fn remove_v4_8_options(&mut self) {
-        if !self.read_only_tmpfs {
-            self.read_only_tmpfs = true;
+        if self.read_only_tmpfs.take() == Some(false) {
             self.podman_args_push_str("--read-only-tmpfs=false");
         }
 
@@ -535,8 +535,17 @@ impl Container {
         self.podman_args_push_str(&format!("--{flag} {arg}"));
     }
 
+    /// Remove duplicate and conflicting arguments from `podman_args`.
+    ///
+    /// See [`podman_args::dedup()`].
+    pub fn dedup_podman_args(&mut self) {
+        if let Some(podman_args) = self.podman_args.as_deref().and_then(podman_args::dedup) {
+            self.podman_args = Some(podman_args);
+        }
+    }
+
     /// Push `string` to `podman_args`, adding a space if needed.
-    fn podman_args_push_str(&mut self, string: &str) {
+    pub fn podman_args_push_str(&mut self, string: &str) {
         let podman_args = self.podman_args.get_or_insert_with(String::new);
         if !podman_args.is_empty() {
             podman_args.push(' ');
@@ -166,7 +166,7 @@ impl<'de> Deserialize<'de> for Idmap {
 
 struct Visitor;
 
-impl<'de> de::Visitor<'de> for Visitor {
+impl de::Visitor<'_> for Visitor {
     type Value = Idmap;
 
     fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
@@ -0,0 +1,190 @@
+//! Normalization of a [`Container`](super::Container)'s `PodmanArgs=`.
+//!
+//! Also used for the `GlobalArgs=` of [`Globals`](crate::quadlet::Globals).
+
+use crate::{escape::arg_quote, warning};
+
+/// Flags which may only be given once to `podman run`, the last one given takes effect.
+const SINGLE_VALUED: &[&str] = &[
+    "arch",
+    "cgroup-parent",
+    "cgroupns",
+    "cpu-shares",
+    "cpus",
+    "entrypoint",
+    "hostname",
+    "init-path",
+    "ipc",
+    "log-driver",
+    "memory",
+    "memory-reservation",
+    "memory-swap",
+    "oom-score-adj",
+    "os",
+    "pid",
+    "pids-limit",
+    "platform",
+    "pull",
+    "restart",
+    "shm-size",
+    "stop-signal",
+    "stop-timeout",
+    "user",
+    "userns",
+    "uts",
+    "variant",
+    "workdir",
+];
+
+/// A flag and its value, if any.
+#[derive(Debug, PartialEq, Eq)]
+struct Arg {
+    /// The flag, including leading dashes.
+    flag: String,
+
+    /// The value given to the flag.
+    value: Option<String>,
+
+    /// Whether the flag and value are joined with an `=`.
+    joined: bool,
+}
+
+impl Arg {
+    /// Returns `true` if `self` and `other` give the same value to the same flag.
+    fn same(&self, other: &Self) -> bool {
+        self.flag == other.flag && self.value == other.value
+    }
+
+    /// Returns `true` if the flag is one of the `single_valued` flags, which may only be given once.
+    fn is_single_valued(&self, single_valued: &[&str]) -> bool {
+        self.flag
+            .strip_prefix("--")
+            .is_some_and(|flag| single_valued.contains(&flag))
+    }
+}
+
+/// Remove duplicate arguments from `podman_args`.
+///
+/// Exact duplicates of a flag and its value are removed, keeping the first. If a single-valued
+/// flag, like `--platform`, is given multiple times with different values, a warning is emitted
+/// and the last is kept, as Podman would use it. Otherwise, the order of the arguments is kept.
+///
+/// Returns [`None`] if nothing was removed, or `podman_args` could not be split, so the original
+/// string (and its quoting) can be kept.
+pub fn dedup(podman_args: &str) -> Option<String> {
+    dedup_option(podman_args, "PodmanArgs", SINGLE_VALUED)
+}
+
+/// Remove duplicate arguments from `args`, the value of the Quadlet `option`.
+///
+/// See [`dedup()`], `single_valued` is the list of flags, without leading dashes, which may only be
+/// given once.
+pub(in crate::quadlet) fn dedup_option(
+    args: &str,
+    option: &str,
+    single_valued: &[&str],
+) -> Option<String> {
+    let args = parse(shlex::split(args)?);
+
+    let mut normalized: Vec<Arg> = Vec::with_capacity(args.len());
+    let mut changed = false;
+    for arg in args {
+        if normalized.iter().any(|existing| existing.same(&arg)) {
+            changed = true;
+        } else if let Some(index) = normalized
+            .iter()
+            .position(|existing| arg.is_single_valued(single_valued) && existing.flag == arg.flag)
+        {
+            let replaced = normalized.remove(index);
+            warning::warn(format_args!(
+                "`{}` given multiple times in `{option}=`, \
+                    keeping `{}` instead of `{}`",
+                arg.flag,
+                arg.value.as_deref().unwrap_or_default(),
+                replaced.value.as_deref().unwrap_or_default(),
+            ));
+            normalized.push(arg);
+            changed = true;
+        } else {
+            normalized.push(arg);
+        }
+    }
+
+    changed.then(|| {
+        normalized
+            .iter()
+            .map(
+                |Arg {
+                     flag,
+                     value,
+                     joined,
+                 }| match value {
+                    Some(value) if *joined => format!("{flag}={}", arg_quote(value)),
+                    Some(value) => format!("{flag} {}", arg_quote(value)),
+                    None => flag.clone(),
+                },
+            )
+            .collect::<Vec<_>>()
+            .join(" ")
+    })
+}
+
+/// Group split arguments into flags and their values.
+///
+/// An argument not starting with `-`, or looking like a negative number, is the value of the
+/// preceding flag.
+fn parse(args: Vec<String>) -> Vec<Arg> {
+    let mut parsed: Vec<Arg> = Vec::with_capacity(args.len());
+    for arg in args {
+        let is_value = !arg.starts_with('-')
+            || arg
+                .strip_prefix('-')
+                .is_some_and(|number| number.starts_with(|char: char| char.is_ascii_digit()));
+        match parsed.last_mut() {
+            Some(last) if is_value && last.value.is_none() => last.value = Some(arg),
+            _ => {
+                let (flag, value, joined) = match arg.split_once('=') {
+                    Some((flag, value)) if arg.starts_with('-') => {
+                        (flag.to_owned(), Some(value.to_owned()), true)
+                    }
+                    _ => (arg, None, false),
+                };
+                parsed.push(Arg {
+                    flag,
+                    value,
+                    joined,
+                });
+            }
+        }
+    }
+    parsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duplicates_and_conflicts() {
+        warning::take();
+        let podman_args = "--privileged --platform linux/amd64 --privileged \
+            --security-opt label=disable --platform=linux/arm64 --security-opt mask=/tmp";
+        assert_eq!(
+            dedup(podman_args).as_deref(),
+            Some(
+                "--privileged --security-opt 'label=disable' --platform=linux/arm64 \
+                    --security-opt 'mask=/tmp'"
+            )
+        );
+        assert_eq!(
+            warning::take(),
+            ["`--platform` given multiple times in `PodmanArgs=`, \
+                keeping `linux/arm64` instead of `linux/amd64`"]
+        );
+    }
+
+    #[test]
+    fn unchanged() {
+        assert_eq!(dedup("--oom-score-adj -500 --cap-add 'A B'"), None);
+    }
+}
@@ -187,6 +187,10 @@ impl TryFrom<mount::Volume> for Volume {
 
         let mut options = Options::try_from(volume.unwrap_or_default())?;
         options.read_only = read_only;
+        ensure!(
+            options.subpath.is_none() || source.is_some(),
+            "`volume.subpath` requires a named volume `source`"
+        );
 
         Ok(Self {
             source: source.map(Into::into),
@@ -352,6 +356,9 @@ pub struct Options {
 
     /// Create an idmapped mount to the target user namespace in the container.
     pub idmap: Option<Idmap>,
+
+    /// Path inside a named volume to mount instead of the volume root.
+    pub subpath: Option<PathBuf>,
 }
 
 impl HostPaths for Options {
@@ -361,6 +368,11 @@ impl HostPaths for Options {
 }
 
 impl Options {
+    /// Returns `true` if all options are set to their default values.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
     /// Parse `option` and fold it into `self`.
     fn try_fold(mut self, option: &str) -> Result<Self, ParseOptionsError> {
         // option could be "option" or "option=value".
@@ -410,6 +422,10 @@ impl Options {
                 let value = value.map(str::parse).transpose()?.unwrap_or_default();
                 self.idmap = Some(value);
             }
+            "subpath" => {
+                let value = value.ok_or(ParseOptionsError::RequiresValue("subpath"))?;
+                set_option("subpath", &mut self.subpath, || value.into())?;
+            }
             option => {
                 self.bind_propagation = option
                     .parse()
@@ -487,6 +503,7 @@ impl Display for Options {
             recursive_bind,
             bind_propagation,
             ref idmap,
+            ref subpath,
         } = *self;
 
         // Format is ":option,...".
@@ -539,6 +556,10 @@ impl Display for Options {
             }
         }
 
+        if let Some(subpath) = subpath {
+            f.write_option(format_args!("subpath={}", subpath.display()))?;
+        }
+
         Ok(())
     }
 }
@@ -580,10 +601,6 @@ impl TryFrom<VolumeOptions> for Options {
             extensions,
         }: VolumeOptions,
     ) -> Result<Self, Self::Error> {
-        ensure!(
-            subpath.is_none(),
-            "`subpath` volume option is not supported"
-        );
         ensure!(
             extensions.is_empty(),
             "compose extensions are not supported"
@@ -591,6 +608,7 @@ impl TryFrom<VolumeOptions> for Options {
 
         Ok(Self {
             no_copy,
+            subpath,
             ..Self::default()
         })
     }
@@ -602,15 +620,13 @@ impl TryFrom<BindOptions> for Options {
     fn try_from(
         BindOptions {
             propagation,
-            create_host_path,
+            // Podman does not create the source of a bind mount if it does not exist, so the
+            // option is ignored. The compose conversion warns if the source is missing.
+            create_host_path: _,
             selinux,
             extensions,
         }: BindOptions,
     ) -> Result<Self, Self::Error> {
-        ensure!(
-            !create_host_path,
-            "`create_host_path` bind mount option is not supported"
-        );
         ensure!(
             extensions.is_empty(),
             "compose extensions are not supported"
@@ -698,7 +714,7 @@ mod tests {
     fn all_options() {
         let string =
             "/host/path:/container/path:ro,Z,O,upperdir=/upper/dir,workdir=/work/dir,U,nocopy,dev,\
-                noexec,suid,rbind,shared,idmap";
+                noexec,suid,rbind,shared,idmap,subpath=sub/path";
         let volume: Volume = string.parse().unwrap();
         let options = Options {
             read_only: true,
@@ -715,6 +731,7 @@ mod tests {
             recursive_bind: true,
             bind_propagation: BindPropagation::Shared,
             idmap: Some(Idmap::default()),
+            subpath: Some("sub/path".into()),
         };
         assert_eq!(
             volume,
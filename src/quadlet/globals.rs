@@ -5,7 +5,29 @@ use std::{
 
 use serde::Serialize;
 
-use super::{Downgrade, DowngradeError, HostPaths, PodmanVersion};
+use super::{container::podman_args, Downgrade, DowngradeError, HostPaths, PodmanVersion};
+
+/// Global flags which may only be given once to `podman`, the last one given takes effect.
+const SINGLE_VALUED: &[&str] = &[
+    "cgroup-manager",
+    "config",
+    "connection",
+    "events-backend",
+    "identity",
+    "imagestore",
+    "log-level",
+    "network-backend",
+    "network-cmd-path",
+    "network-config-dir",
+    "root",
+    "runroot",
+    "runtime",
+    "ssh",
+    "storage-driver",
+    "tmpdir",
+    "url",
+    "volumepath",
+];
 
 /// Global Quadlet options that apply to all resource types.
 #[derive(Serialize, Debug, Default, Clone, PartialEq)]
@@ -18,6 +40,30 @@ pub struct Globals {
     pub global_args: Option<String>,
 }
 
+impl Globals {
+    /// Remove duplicate and conflicting arguments from `global_args` and duplicate
+    /// `containers_conf_module`s.
+    ///
+    /// Exact duplicates are removed, keeping the first. If a single-valued flag, like
+    /// `--log-level`, is given multiple times with different values, a warning is emitted and the
+    /// last is kept, as Podman would use it.
+    pub fn dedup(&mut self) {
+        let mut modules = Vec::with_capacity(self.containers_conf_module.len());
+        for module in std::mem::take(&mut self.containers_conf_module) {
+            if !modules.contains(&module) {
+                modules.push(module);
+            }
+        }
+        self.containers_conf_module = modules;
+
+        if let Some(global_args) = self.global_args.as_deref().and_then(|global_args| {
+            podman_args::dedup_option(global_args, "GlobalArgs", SINGLE_VALUED)
+        }) {
+            self.global_args = Some(global_args);
+        }
+    }
+}
+
 impl Downgrade for Globals {
     fn downgrade(&mut self, version: PodmanVersion) -> Result<(), DowngradeError> {
         if version < PodmanVersion::V4_8 {
@@ -67,4 +113,24 @@ mod tests {
         let globals = Globals::default();
         assert!(globals.to_string().is_empty(), "globals: {globals}");
     }
+
+    #[test]
+    fn dedup() {
+        let mut globals = Globals {
+            containers_conf_module: vec!["a.conf".into(), "b.conf".into(), "a.conf".into()],
+            global_args: Some(
+                "--storage-opt overlay.mount_program=/usr/bin/fuse-overlayfs --log-level debug \
+                    --storage-opt overlay.mount_program=/usr/bin/fuse-overlayfs"
+                    .to_owned(),
+            ),
+        };
+        globals.dedup();
+        assert_eq!(
+            globals.to_string(),
+            "ContainersConfModule=a.conf\n\
+            ContainersConfModule=b.conf\n\
+            GlobalArgs=--storage-opt 'overlay.mount_program=/usr/bin/fuse-overlayfs' \
+                --log-level debug\n"
+        );
+    }
 }
@@ -44,10 +44,13 @@ pub struct Kube {
 }
 
 impl Kube {
-    pub fn new(yaml: YamlFile) -> Self {
+    /// Create a [`Kube`] which plays `yaml`, passing it the Kubernetes ConfigMap YAML files at the
+    /// `config_map` paths.
+    #[allow(clippy::doc_markdown)]
+    pub fn new(yaml: YamlFile, config_map: Vec<PathBuf>) -> Self {
         Self {
             auto_update: Vec::new(),
-            config_map: Vec::new(),
+            config_map,
             log_driver: None,
             network: Vec::new(),
             podman_args: None,
@@ -156,7 +159,7 @@ impl AutoUpdate {
                         })
                         .or_else(|| container.is_empty().then_some(Self::All(auto_update)))
                 })
-                .map_or(true, |auto_update| {
+                .is_none_or(|auto_update| {
                     auto_updates.push(auto_update);
                     false
                 })
@@ -262,7 +265,7 @@ mod tests {
 
     #[test]
     fn kube_default_empty() {
-        let kube = Kube::new(PathBuf::from("yaml").into());
+        let kube = Kube::new(PathBuf::from("yaml").into(), Vec::new());
         assert_eq!(kube.to_string(), "[Kube]\nYaml=yaml\n");
     }
 
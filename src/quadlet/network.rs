@@ -11,7 +11,7 @@ use ipnet::IpNet;
 use serde::{Serialize, Serializer};
 use thiserror::Error;
 
-use crate::serde::quadlet::quote_spaces_join_space;
+use crate::{serde::quadlet::quote_spaces_join_space, warning};
 
 use super::{Downgrade, DowngradeError, PodmanVersion};
 
@@ -102,6 +102,9 @@ impl Downgrade for Network {
     }
 }
 
+/// Network drivers supported by Podman.
+const KNOWN_DRIVERS: [&str; 3] = ["bridge", "macvlan", "ipvlan"];
+
 impl TryFrom<compose_spec::Network> for Network {
     type Error = color_eyre::Report;
 
@@ -138,6 +141,15 @@ impl TryFrom<compose_spec::Network> for Network {
             "compose extensions are not supported"
         );
 
+        if let Some(driver) = &driver {
+            if !KNOWN_DRIVERS.contains(&driver.as_str()) {
+                warning::warn(format_args!(
+                    "network driver `{driver}` is not known to be supported by Podman, \
+                        supported drivers are `bridge`, `macvlan`, and `ipvlan`"
+                ));
+            }
+        }
+
         let network = Self {
             driver: driver.map(Into::into),
             options: driver_opts
@@ -168,6 +180,16 @@ impl TryFrom<compose_spec::Network> for Network {
                     Err(eyre!("`aux_addresses` is not supported"))
                 } else if !extensions.is_empty() {
                     Err(eyre!("compose extensions are not supported"))
+                } else if let Some((subnet, gateway)) = subnet
+                    .zip(gateway)
+                    .filter(|(subnet, gateway)| !subnet.contains(gateway))
+                {
+                    Err(eyre!("`gateway` {gateway} is not in `subnet` {subnet}"))
+                } else if let Some((subnet, ip_range)) = subnet
+                    .zip(ip_range)
+                    .filter(|(subnet, ip_range)| !subnet.contains(ip_range))
+                {
+                    Err(eyre!("`ip_range` {ip_range} is not in `subnet` {subnet}"))
                 } else {
                     network.subnet.extend(subnet);
                     network.ip_range.extend(ip_range.map(Into::into));
@@ -274,4 +296,94 @@ mod tests {
         let network = Network::default();
         assert_eq!(network.to_string(), "[Network]\n");
     }
+
+    #[test]
+    fn driver_and_options() {
+        let network = compose_spec::Network {
+            driver: Some("macvlan".into()),
+            driver_opts: [("parent".parse().expect("valid key"), "eth0".into())].into(),
+            ..compose_spec::Network::default()
+        };
+        let network = Network::try_from(network).expect("network converts");
+        assert_eq!(
+            network.to_string(),
+            "[Network]\nDriver=macvlan\nOptions=parent=eth0\n"
+        );
+        assert!(warning::take().is_empty());
+    }
+
+    #[test]
+    fn internal_and_ipv6() {
+        let network = compose_spec::Network {
+            internal: true,
+            enable_ipv6: true,
+            ..compose_spec::Network::default()
+        };
+        let network = Network::try_from(network).expect("network converts");
+        assert_eq!(network.to_string(), "[Network]\nInternal=true\nIPv6=true\n");
+    }
+
+    #[test]
+    fn ipam_config() {
+        let network: compose_spec::Network = serde_yaml::from_str(
+            "
+            ipam:
+              config:
+                - subnet: 10.89.0.0/24
+                  gateway: 10.89.0.1
+                  ip_range: 10.89.0.128/25
+                - subnet: fd00:89::/64
+            ",
+        )
+        .expect("network is valid");
+        let network = Network::try_from(network).expect("network converts");
+        assert_eq!(
+            network.to_string(),
+            "[Network]\n\
+                Gateway=10.89.0.1\n\
+                IPRange=10.89.0.128/25\n\
+                Subnet=10.89.0.0/24\n\
+                Subnet=fd00:89::/64\n"
+        );
+    }
+
+    #[test]
+    fn ipam_config_invalid() {
+        let subnet: Result<compose_spec::Network, _> = serde_yaml::from_str(
+            "
+            ipam:
+              config:
+                - subnet: 10.89.0.0
+            ",
+        );
+        assert!(subnet.is_err());
+
+        let network: compose_spec::Network = serde_yaml::from_str(
+            "
+            ipam:
+              config:
+                - subnet: 10.89.0.0/24
+                  gateway: 10.90.0.1
+            ",
+        )
+        .expect("network is valid");
+        assert!(Network::try_from(network).is_err());
+    }
+
+    #[test]
+    fn unknown_driver_warns() {
+        let network = compose_spec::Network {
+            driver: Some("overlay".into()),
+            ..compose_spec::Network::default()
+        };
+        let network = Network::try_from(network).expect("network converts");
+        assert_eq!(network.driver.as_deref(), Some("overlay"));
+
+        let warnings = warning::take();
+        assert_eq!(warnings.len(), 1);
+        assert!(
+            warnings.iter().any(|warning| warning.contains("`overlay`")),
+            "{warnings:?}"
+        );
+    }
 }
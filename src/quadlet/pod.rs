@@ -1,5 +1,6 @@
 use std::{
     fmt::{self, Display, Formatter},
+    net::IpAddr,
     path::PathBuf,
 };
 
@@ -11,6 +12,21 @@ use super::{container::Volume, Downgrade, DowngradeError, HostPaths, PodmanVersi
 #[derive(Serialize, Debug, Default, Clone, PartialEq)]
 #[serde(rename_all = "PascalCase")]
 pub struct Pod {
+    /// Add host-to-IP mapping to `/etc/hosts`.
+    pub add_host: Vec<String>,
+
+    /// Set network-scoped DNS resolver/nameserver for containers in this pod.
+    #[serde(rename = "DNS")]
+    pub dns: Vec<IpAddr>,
+
+    /// Set custom DNS options.
+    #[serde(rename = "DNSOption")]
+    pub dns_option: Vec<String>,
+
+    /// Set custom DNS search domains.
+    #[serde(rename = "DNSSearch")]
+    pub dns_search: Vec<String>,
+
     /// Specify a custom network for the pod.
     pub network: Vec<String>,
 
@@ -53,6 +69,18 @@ impl Downgrade for Pod {
             for network_alias in std::mem::take(&mut self.network_alias) {
                 self.push_arg("network-alias", &network_alias);
             }
+            for add_host in std::mem::take(&mut self.add_host) {
+                self.push_arg("add-host", &add_host);
+            }
+            for dns in std::mem::take(&mut self.dns) {
+                self.push_arg("dns", &dns.to_string());
+            }
+            for dns_option in std::mem::take(&mut self.dns_option) {
+                self.push_arg("dns-option", &dns_option);
+            }
+            for dns_search in std::mem::take(&mut self.dns_search) {
+                self.push_arg("dns-search", &dns_search);
+            }
         }
 
         if version < PodmanVersion::V5_0 {
@@ -68,7 +96,7 @@ impl Downgrade for Pod {
 
 impl Pod {
     /// Add `--{flag} {arg}` to `PodmanArgs=`.
-    fn push_arg(&mut self, flag: &str, arg: &str) {
+    pub fn push_arg(&mut self, flag: &str, arg: &str) {
         let podman_args = self.podman_args.get_or_insert_with(String::new);
         if !podman_args.is_empty() {
             podman_args.push(' ');
@@ -78,4 +106,13 @@ impl Pod {
         podman_args.push(' ');
         podman_args.push_str(arg);
     }
+
+    /// Add `string` to `PodmanArgs=`.
+    pub fn podman_args_push_str(&mut self, string: &str) {
+        let podman_args = self.podman_args.get_or_insert_with(String::new);
+        if !podman_args.is_empty() {
+            podman_args.push(' ');
+        }
+        podman_args.push_str(string);
+    }
 }
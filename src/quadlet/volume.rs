@@ -4,10 +4,13 @@ use std::{
     path::PathBuf,
 };
 
-use color_eyre::eyre::{ensure, Context};
+use color_eyre::eyre::{ensure, WrapErr};
 use serde::Serialize;
 
-use crate::{cli::volume::Opt, serde::quadlet::quote_spaces_join_space};
+use crate::{
+    cli::volume::{Opt, ParseOptError},
+    serde::quadlet::quote_spaces_join_space,
+};
 
 use super::{Downgrade, DowngradeError, HostPaths, PodmanVersion};
 
@@ -117,24 +120,41 @@ impl TryFrom<compose_spec::Volume> for Volume {
             "compose extensions are not supported"
         );
 
-        let options: Vec<Opt> = driver_opts
-            .into_iter()
-            .enumerate()
-            .map(|(index, (option, value))| {
-                let value = String::from(value);
-                let value = (!value.is_empty()
-                    && (option != "copy" || !matches!(value.as_str(), "true" | "1")))
-                .then_some(value);
-                Opt::parse(option.as_str(), value)
-                    .wrap_err_with(|| format!("error converting `driver_opts[{index}]`"))
-            })
-            .collect::<Result<_, _>>()?;
-
-        Ok(Self {
+        // Options not known to Podman's `local` driver are passed through with `--opt`,
+        // as they may be used by other volume drivers.
+        let mut options = Vec::new();
+        let mut other_options = Vec::new();
+        for (option, value) in driver_opts {
+            let value = String::from(value);
+            if option == "copy" && matches!(value.as_str(), "false" | "0") {
+                continue;
+            }
+            let value = (!value.is_empty()
+                && (option != "copy" || !matches!(value.as_str(), "true" | "1")))
+            .then_some(value);
+            match Opt::parse(option.as_str(), value.clone()) {
+                Ok(opt) => options.push(opt),
+                Err(ParseOptError::InvalidVolumeDriverOption(_)) => other_options.push(
+                    value.map_or_else(|| option.to_string(), |value| format!("{option}={value}")),
+                ),
+                Err(error) => {
+                    return Err(error).wrap_err_with(|| {
+                        format!("error converting volume driver option `{option}`")
+                    });
+                }
+            }
+        }
+
+        let mut volume = Self {
             driver,
             label: labels.into_list().into_iter().collect(),
             ..options.into()
-        })
+        };
+        for option in other_options {
+            volume.push_arg("opt", &option);
+        }
+
+        Ok(volume)
     }
 }
 
@@ -154,4 +174,60 @@ mod tests {
         let volume = Volume::default();
         assert_eq!(volume.to_string(), "[Volume]\n");
     }
+
+    #[test]
+    fn nfs_volume() {
+        let volume: compose_spec::Volume = serde_yaml::from_str(
+            "
+            driver: local
+            driver_opts:
+              type: nfs
+              o: addr=10.0.0.1,rw
+              device: :/export/data
+            ",
+        )
+        .expect("volume is valid");
+        let volume = Volume::try_from(volume).expect("volume converts");
+        assert_eq!(
+            volume.to_string(),
+            "[Volume]\n\
+                Device=:/export/data\n\
+                Driver=local\n\
+                Options=addr=10.0.0.1,rw\n\
+                Type=nfs\n"
+        );
+    }
+
+    #[test]
+    fn other_driver_opts() {
+        let volume: compose_spec::Volume = serde_yaml::from_str(
+            "
+            driver: plugin
+            driver_opts:
+              size: 10G
+            ",
+        )
+        .expect("volume is valid");
+        let volume = Volume::try_from(volume).expect("volume converts");
+        assert_eq!(
+            volume.to_string(),
+            "[Volume]\nDriver=plugin\nPodmanArgs=--opt size=10G\n"
+        );
+    }
+
+    #[test]
+    fn invalid_known_driver_opt() {
+        let volume: compose_spec::Volume = serde_yaml::from_str(
+            "
+            driver_opts:
+              type: ''
+            ",
+        )
+        .expect("volume is valid");
+        let error = Volume::try_from(volume).expect_err("`type` requires a value");
+        assert_eq!(
+            error.root_cause().to_string(),
+            "volume driver option `type` requires a value"
+        );
+    }
 }
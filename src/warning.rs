@@ -0,0 +1,28 @@
+//! Warnings for non-fatal issues found while generating files.
+//!
+//! Warnings are printed to stderr as they occur. In tests, they are also recorded so they can be
+//! inspected with [`take()`].
+
+use std::fmt::Display;
+
+use color_eyre::owo_colors::OwoColorize;
+
+#[cfg(test)]
+thread_local! {
+    /// Warnings emitted on the current thread.
+    static WARNINGS: std::cell::RefCell<Vec<String>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/// Print a warning to stderr.
+pub fn warn(message: impl Display) {
+    let message = message.to_string();
+    eprintln!("{}: {message}", "warning".yellow().bold());
+    #[cfg(test)]
+    WARNINGS.with_borrow_mut(|warnings| warnings.push(message));
+}
+
+/// Take all warnings recorded on the current thread.
+#[cfg(test)]
+pub fn take() -> Vec<String> {
+    WARNINGS.take()
+}